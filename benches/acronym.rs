@@ -0,0 +1,78 @@
+mod common;
+
+use common as bench;
+use criterion::{
+    criterion_group,
+    criterion_main,
+    measurement::WallTime,
+    BenchmarkGroup,
+    Criterion,
+};
+use norm::{fzf::Acronym, CaseSensitivity, StrParser};
+
+impl bench::Metric for Acronym {
+    type Parser = StrParser;
+
+    fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.set_case_sensitivity(case_sensitivity)
+    }
+}
+
+fn group(c: &mut Criterion) -> BenchmarkGroup<WallTime> {
+    c.benchmark_group("acronym")
+}
+
+fn short(c: &mut Criterion) {
+    bench::short(Acronym::new(), None, group(c));
+}
+
+fn medium_start(c: &mut Criterion) {
+    bench::medium_start(Acronym::new(), None, group(c));
+}
+
+fn medium_middle(c: &mut Criterion) {
+    bench::medium_middle(Acronym::new(), None, group(c));
+}
+
+fn medium_end(c: &mut Criterion) {
+    bench::medium_end(Acronym::new(), None, group(c));
+}
+
+fn long_start(c: &mut Criterion) {
+    bench::long_start(Acronym::new(), None, group(c));
+}
+
+fn long_middle(c: &mut Criterion) {
+    bench::long_middle(Acronym::new(), None, group(c));
+}
+
+fn long_end(c: &mut Criterion) {
+    bench::long_end(Acronym::new(), None, group(c));
+}
+
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(Acronym::new(), None, group(c));
+}
+
+// Splits the long candidate into words and matches every character of the
+// query against their initials and prefixes.
+fn long_scattered(c: &mut Criterion) {
+    bench::long_scattered(Acronym::new(), None, group(c));
+}
+
+criterion_group!(
+    benches,
+    short,
+    medium_start,
+    medium_middle,
+    medium_end,
+    long_start,
+    long_middle,
+    long_end,
+    long_non_match,
+    long_scattered,
+);
+criterion_main!(benches);
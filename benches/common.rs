@@ -8,21 +8,10 @@ use criterion::{
     BenchmarkId,
     Throughput,
 };
-use norm::CaseSensitivity;
+use norm::{CaseSensitivity, Parser};
 
-pub trait Metric {
-    type Query<'a>: Copy;
-
-    type Parser: Parser<Self>;
-
-    fn dist(&mut self, query: Self::Query<'_>, candidate: &str);
-
-    fn dist_and_ranges(
-        &mut self,
-        query: Self::Query<'_>,
-        candidate: &str,
-        ranges: &mut Vec<Range<usize>>,
-    );
+pub trait Metric: for<'a> norm::Metric<Query<'a>: Copy> {
+    type Parser: Parser<Self> + Default;
 
     fn set_case_sensitivity(
         &mut self,
@@ -30,10 +19,6 @@ pub trait Metric {
     ) -> &mut Self;
 }
 
-pub trait Parser<M: Metric + ?Sized>: Default {
-    fn parse<'a>(&'a mut self, s: &str) -> M::Query<'a>;
-}
-
 // TODO: docs
 fn param(
     case: CaseSensitivity,
@@ -115,7 +100,7 @@ fn bench<'a, M, C>(
         group.bench_function(id, |b| {
             b.iter(|| {
                 for candidate in candidates.clone() {
-                    metric.dist_and_ranges(query, candidate, ranges);
+                    metric.distance_and_ranges(query, candidate, ranges);
                 }
             })
         });
@@ -123,7 +108,7 @@ fn bench<'a, M, C>(
         group.bench_function(id, |b| {
             b.iter(|| {
                 for candidate in candidates.clone() {
-                    metric.dist(query, candidate);
+                    metric.distance(query, candidate);
                 }
             })
         });
@@ -265,3 +250,84 @@ pub fn long_end<M: Metric>(
         },
     )
 }
+
+// Benchmarks a query whose characters all appear in the long candidate, but
+// not in order, so that the candidate is only rejected after scanning it
+// whole.
+pub fn long_non_match<M: Metric>(
+    metric: M,
+    suffix: Option<&str>,
+    mut group: BenchmarkGroup<WallTime>,
+) {
+    for_all_cases_and_ranges(
+        metric,
+        "long_non_match",
+        suffix,
+        |metric, id, ranges| {
+            // All the characters of this query appear in the candidate, but
+            // not in this order, so the whole candidate has to be scanned
+            // before it's discarded.
+            let query = "lifexxx";
+            let candidates = core::iter::once(LONG_TEXT);
+            bench(&mut group, id, metric, query, candidates, ranges);
+        },
+    )
+}
+
+/// The characters of this query are scattered all over [`LONG_TEXT`], so it's
+/// a much worse match than the query itself.
+const SCATTERED_QUERY: &str = "blindlife";
+
+// Benchmarks a query that matches the long candidate, but only after
+// scoring a lot of gaps. This is the baseline for `long_within`.
+pub fn long_scattered<M: Metric>(
+    metric: M,
+    suffix: Option<&str>,
+    mut group: BenchmarkGroup<WallTime>,
+) {
+    for_all_cases_and_ranges(
+        metric,
+        "long_scattered",
+        suffix,
+        |metric, id, ranges| {
+            let candidates = core::iter::once(LONG_TEXT);
+            bench(&mut group, id, metric, SCATTERED_QUERY, candidates, ranges);
+        },
+    )
+}
+
+// Benchmarks the same match as `long_scattered`, but with a cutoff that
+// only the query itself can reach, so that the candidate is rejected by
+// `distance_within`.
+pub fn long_within<M: Metric>(
+    mut metric: M,
+    suffix: Option<&str>,
+    mut group: BenchmarkGroup<WallTime>,
+) {
+    let mut parser = M::Parser::default();
+
+    let query = parser.parse(SCATTERED_QUERY);
+
+    group.throughput(Throughput::Elements(1));
+
+    for case in [
+        CaseSensitivity::Sensitive,
+        CaseSensitivity::Insensitive,
+        CaseSensitivity::Smart,
+    ] {
+        metric.set_case_sensitivity(case);
+
+        // Only the candidates that match at least as well as the query
+        // itself make the cut, so the scattered match should be discarded
+        // well before it's fully scored.
+        let cutoff = metric
+            .distance(query, SCATTERED_QUERY)
+            .expect("the query matches itself");
+
+        let id = BenchmarkId::new("long_within", param(case, false, suffix));
+
+        group.bench_function(id, |b| {
+            b.iter(|| metric.distance_within(query, LONG_TEXT, &cutoff))
+        });
+    }
+}
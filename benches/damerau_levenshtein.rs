@@ -0,0 +1,81 @@
+mod common;
+
+use common as bench;
+use criterion::{
+    criterion_group,
+    criterion_main,
+    measurement::WallTime,
+    BenchmarkGroup,
+    Criterion,
+};
+use norm::{levenshtein::DamerauLevenshtein, CaseSensitivity, StrParser};
+
+impl bench::Metric for DamerauLevenshtein {
+    type Parser = StrParser;
+
+    fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.set_case_sensitivity(case_sensitivity)
+    }
+}
+
+fn group(c: &mut Criterion) -> BenchmarkGroup<WallTime> {
+    c.benchmark_group("damerau_levenshtein")
+}
+
+fn short(c: &mut Criterion) {
+    bench::short(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn medium_start(c: &mut Criterion) {
+    bench::medium_start(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn medium_middle(c: &mut Criterion) {
+    bench::medium_middle(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn medium_end(c: &mut Criterion) {
+    bench::medium_end(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn long_start(c: &mut Criterion) {
+    bench::long_start(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn long_middle(c: &mut Criterion) {
+    bench::long_middle(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn long_end(c: &mut Criterion) {
+    bench::long_end(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn long_scattered(c: &mut Criterion) {
+    bench::long_scattered(DamerauLevenshtein::new(), None, group(c));
+}
+
+fn long_within(c: &mut Criterion) {
+    bench::long_within(DamerauLevenshtein::new(), None, group(c));
+}
+
+criterion_group!(
+    benches,
+    short,
+    medium_start,
+    medium_middle,
+    medium_end,
+    long_start,
+    long_middle,
+    long_end,
+    long_non_match,
+    long_scattered,
+    long_within,
+);
+criterion_main!(benches);
@@ -1,5 +1,3 @@
-use core::ops::Range;
-
 mod common;
 
 use common as bench;
@@ -11,35 +9,13 @@ use criterion::{
     Criterion,
 };
 use norm::{
-    fzf::{FzfParser, FzfQuery, FzfV2},
+    fzf::{FzfParser, FzfV2},
     CaseSensitivity,
-    Metric,
 };
 
-impl bench::Parser<FzfV2> for FzfParser {
-    fn parse<'a>(&'a mut self, s: &str) -> FzfQuery<'a> {
-        self.parse(s)
-    }
-}
-
 impl bench::Metric for FzfV2 {
-    type Query<'a> = FzfQuery<'a>;
-
     type Parser = FzfParser;
 
-    #[inline(always)]
-    fn dist(&mut self, query: FzfQuery, candidate: &str) {
-        self.distance(query, candidate);
-    }
-    #[inline(always)]
-    fn dist_and_ranges(
-        &mut self,
-        query: FzfQuery,
-        candidate: &str,
-        ranges: &mut Vec<Range<usize>>,
-    ) {
-        self.distance_and_ranges(query, candidate, ranges);
-    }
     fn set_case_sensitivity(
         &mut self,
         case_sensitivity: CaseSensitivity,
@@ -80,6 +56,24 @@ fn long_end(c: &mut Criterion) {
     bench::long_end(FzfV2::new(), None, group(c));
 }
 
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(FzfV2::new(), None, group(c));
+}
+
+fn long_non_match_scalar(c: &mut Criterion) {
+    norm::set_simd_enabled(false);
+    bench::long_non_match(FzfV2::new(), Some("scalar"), group(c));
+    norm::set_simd_enabled(true);
+}
+
+fn long_scattered(c: &mut Criterion) {
+    bench::long_scattered(FzfV2::new(), None, group(c));
+}
+
+fn long_within(c: &mut Criterion) {
+    bench::long_within(FzfV2::new(), None, group(c));
+}
+
 criterion_group!(
     benches,
     short,
@@ -89,5 +83,9 @@ criterion_group!(
     long_start,
     long_middle,
     long_end,
+    long_non_match,
+    long_non_match_scalar,
+    long_scattered,
+    long_within,
 );
 criterion_main!(benches);
@@ -0,0 +1,80 @@
+mod common;
+
+use common as bench;
+use criterion::{
+    criterion_group,
+    criterion_main,
+    measurement::WallTime,
+    BenchmarkGroup,
+    Criterion,
+};
+use norm::{hamming::Hamming, CaseSensitivity, StrParser};
+
+impl bench::Metric for Hamming {
+    type Parser = StrParser;
+
+    fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.set_case_sensitivity(case_sensitivity)
+    }
+}
+
+fn group(c: &mut Criterion) -> BenchmarkGroup<WallTime> {
+    c.benchmark_group("hamming")
+}
+
+fn short(c: &mut Criterion) {
+    bench::short(Hamming::new(), None, group(c));
+}
+
+fn medium_start(c: &mut Criterion) {
+    bench::medium_start(Hamming::new(), None, group(c));
+}
+
+fn medium_middle(c: &mut Criterion) {
+    bench::medium_middle(Hamming::new(), None, group(c));
+}
+
+fn medium_end(c: &mut Criterion) {
+    bench::medium_end(Hamming::new(), None, group(c));
+}
+
+fn long_start(c: &mut Criterion) {
+    bench::long_start(Hamming::new(), None, group(c));
+}
+
+fn long_middle(c: &mut Criterion) {
+    bench::long_middle(Hamming::new(), None, group(c));
+}
+
+fn long_end(c: &mut Criterion) {
+    bench::long_end(Hamming::new(), None, group(c));
+}
+
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(Hamming::new(), None, group(c));
+}
+
+// With a cutoff every window of the long candidate is abandoned as soon as it
+// has one mismatch too many.
+fn long_non_match_max_mismatches(c: &mut Criterion) {
+    let mut hamming = Hamming::new();
+    hamming.set_max_mismatches(Some(1));
+    bench::long_non_match(hamming, Some("max_mismatches"), group(c));
+}
+
+criterion_group!(
+    benches,
+    short,
+    medium_start,
+    medium_middle,
+    medium_end,
+    long_start,
+    long_middle,
+    long_end,
+    long_non_match,
+    long_non_match_max_mismatches,
+);
+criterion_main!(benches);
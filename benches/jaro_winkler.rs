@@ -0,0 +1,71 @@
+mod common;
+
+use common as bench;
+use criterion::{
+    criterion_group,
+    criterion_main,
+    measurement::WallTime,
+    BenchmarkGroup,
+    Criterion,
+};
+use norm::{jaro_winkler::JaroWinkler, CaseSensitivity, StrParser};
+
+impl bench::Metric for JaroWinkler {
+    type Parser = StrParser;
+
+    fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.set_case_sensitivity(case_sensitivity)
+    }
+}
+
+fn group(c: &mut Criterion) -> BenchmarkGroup<WallTime> {
+    c.benchmark_group("jaro_winkler")
+}
+
+fn short(c: &mut Criterion) {
+    bench::short(JaroWinkler::new(), None, group(c));
+}
+
+fn medium_start(c: &mut Criterion) {
+    bench::medium_start(JaroWinkler::new(), None, group(c));
+}
+
+fn medium_middle(c: &mut Criterion) {
+    bench::medium_middle(JaroWinkler::new(), None, group(c));
+}
+
+fn medium_end(c: &mut Criterion) {
+    bench::medium_end(JaroWinkler::new(), None, group(c));
+}
+
+fn long_start(c: &mut Criterion) {
+    bench::long_start(JaroWinkler::new(), None, group(c));
+}
+
+fn long_middle(c: &mut Criterion) {
+    bench::long_middle(JaroWinkler::new(), None, group(c));
+}
+
+fn long_end(c: &mut Criterion) {
+    bench::long_end(JaroWinkler::new(), None, group(c));
+}
+
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(JaroWinkler::new(), None, group(c));
+}
+
+criterion_group!(
+    benches,
+    short,
+    medium_start,
+    medium_middle,
+    medium_end,
+    long_start,
+    long_middle,
+    long_end,
+    long_non_match,
+);
+criterion_main!(benches);
@@ -0,0 +1,86 @@
+mod common;
+
+use common as bench;
+use criterion::{
+    criterion_group,
+    criterion_main,
+    measurement::WallTime,
+    BenchmarkGroup,
+    Criterion,
+};
+use norm::{ngram::NGram, CaseSensitivity, StrParser};
+
+impl bench::Metric for NGram {
+    type Parser = StrParser;
+
+    fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.set_case_sensitivity(case_sensitivity)
+    }
+}
+
+fn group(c: &mut Criterion) -> BenchmarkGroup<WallTime> {
+    c.benchmark_group("ngram")
+}
+
+fn short(c: &mut Criterion) {
+    bench::short(NGram::new(), None, group(c));
+}
+
+fn medium_start(c: &mut Criterion) {
+    bench::medium_start(NGram::new(), None, group(c));
+}
+
+fn medium_middle(c: &mut Criterion) {
+    bench::medium_middle(NGram::new(), None, group(c));
+}
+
+fn medium_end(c: &mut Criterion) {
+    bench::medium_end(NGram::new(), None, group(c));
+}
+
+fn long_start(c: &mut Criterion) {
+    bench::long_start(NGram::new(), None, group(c));
+}
+
+fn long_middle(c: &mut Criterion) {
+    bench::long_middle(NGram::new(), None, group(c));
+}
+
+fn long_end(c: &mut Criterion) {
+    bench::long_end(NGram::new(), None, group(c));
+}
+
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(NGram::new(), None, group(c));
+}
+
+fn long_middle_bigrams(c: &mut Criterion) {
+    let mut ngram = NGram::new();
+    ngram.set_n(2);
+    bench::long_middle(ngram, Some("bigrams"), group(c));
+}
+
+// Padding adds `n - 1` grams to both ends of every word of the long candidate.
+fn long_middle_padded(c: &mut Criterion) {
+    let mut ngram = NGram::new();
+    ngram.set_padding(true);
+    bench::long_middle(ngram, Some("padded"), group(c));
+}
+
+criterion_group!(
+    benches,
+    short,
+    medium_start,
+    medium_middle,
+    medium_end,
+    long_start,
+    long_middle,
+    long_end,
+    long_non_match,
+    long_middle_bigrams,
+    long_middle_padded,
+);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+mod common;
+
+use common as bench;
+use criterion::{
+    criterion_group,
+    criterion_main,
+    measurement::WallTime,
+    BenchmarkGroup,
+    Criterion,
+};
+use norm::phonetic::{Phonetic, PhoneticAlgorithm};
+use norm::{CaseSensitivity, StrParser};
+
+impl bench::Metric for Phonetic {
+    type Parser = StrParser;
+
+    fn set_case_sensitivity(
+        &mut self,
+        _case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        // Phonetic codes are always case-insensitive.
+        self
+    }
+}
+
+fn group(c: &mut Criterion) -> BenchmarkGroup<WallTime> {
+    c.benchmark_group("phonetic")
+}
+
+fn short(c: &mut Criterion) {
+    bench::short(Phonetic::new(), None, group(c));
+}
+
+fn medium_start(c: &mut Criterion) {
+    bench::medium_start(Phonetic::new(), None, group(c));
+}
+
+fn medium_middle(c: &mut Criterion) {
+    bench::medium_middle(Phonetic::new(), None, group(c));
+}
+
+fn medium_end(c: &mut Criterion) {
+    bench::medium_end(Phonetic::new(), None, group(c));
+}
+
+fn long_start(c: &mut Criterion) {
+    bench::long_start(Phonetic::new(), None, group(c));
+}
+
+fn long_middle(c: &mut Criterion) {
+    bench::long_middle(Phonetic::new(), None, group(c));
+}
+
+fn long_end(c: &mut Criterion) {
+    bench::long_end(Phonetic::new(), None, group(c));
+}
+
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(Phonetic::new(), None, group(c));
+}
+
+fn long_middle_soundex(c: &mut Criterion) {
+    let mut phonetic = Phonetic::new();
+    phonetic.set_algorithm(PhoneticAlgorithm::Soundex);
+    bench::long_middle(phonetic, Some("soundex"), group(c));
+}
+
+fn long_middle_metaphone(c: &mut Criterion) {
+    let mut phonetic = Phonetic::new();
+    phonetic.set_algorithm(PhoneticAlgorithm::Metaphone);
+    bench::long_middle(phonetic, Some("metaphone"), group(c));
+}
+
+criterion_group!(
+    benches,
+    short,
+    medium_start,
+    medium_middle,
+    medium_end,
+    long_start,
+    long_middle,
+    long_end,
+    long_non_match,
+    long_middle_soundex,
+    long_middle_metaphone,
+);
+criterion_main!(benches);
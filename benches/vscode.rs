@@ -0,0 +1,72 @@
+mod common;
+
+use common as bench;
+use criterion::{
+    criterion_group,
+    criterion_main,
+    measurement::WallTime,
+    BenchmarkGroup,
+    Criterion,
+};
+use norm::{vscode::VsCode, CaseSensitivity, StrParser};
+
+impl bench::Metric for VsCode {
+    type Parser = StrParser;
+
+    fn set_case_sensitivity(
+        &mut self,
+        _case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        // VS Code's scorer always compares characters case-insensitively.
+        self
+    }
+}
+
+fn group(c: &mut Criterion) -> BenchmarkGroup<WallTime> {
+    c.benchmark_group("vscode")
+}
+
+fn short(c: &mut Criterion) {
+    bench::short(VsCode::new(), None, group(c));
+}
+
+fn medium_start(c: &mut Criterion) {
+    bench::medium_start(VsCode::new(), None, group(c));
+}
+
+fn medium_middle(c: &mut Criterion) {
+    bench::medium_middle(VsCode::new(), None, group(c));
+}
+
+fn medium_end(c: &mut Criterion) {
+    bench::medium_end(VsCode::new(), None, group(c));
+}
+
+fn long_start(c: &mut Criterion) {
+    bench::long_start(VsCode::new(), None, group(c));
+}
+
+fn long_middle(c: &mut Criterion) {
+    bench::long_middle(VsCode::new(), None, group(c));
+}
+
+fn long_end(c: &mut Criterion) {
+    bench::long_end(VsCode::new(), None, group(c));
+}
+
+fn long_non_match(c: &mut Criterion) {
+    bench::long_non_match(VsCode::new(), None, group(c));
+}
+
+criterion_group!(
+    benches,
+    short,
+    medium_start,
+    medium_middle,
+    medium_end,
+    long_start,
+    long_middle,
+    long_end,
+    long_non_match,
+);
+criterion_main!(benches);
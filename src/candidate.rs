@@ -1,5 +1,12 @@
+// Only the fzf metrics use all of this module.
+#![cfg_attr(
+    not(any(feature = "fzf-v1", feature = "fzf-v2")),
+    allow(dead_code)
+)]
+
 use core::ops::Range;
 
+use crate::subsequence;
 use crate::utils::*;
 
 /// TODO: docs
@@ -7,6 +14,17 @@ use crate::utils::*;
 pub(crate) enum Candidate<'a> {
     Ascii(&'a [u8]),
     Unicode(&'a [char]),
+
+    /// A non-ASCII candidate that hasn't been decoded into chars.
+    ///
+    /// Offsets are still expressed in chars, but converting them to byte
+    /// offsets takes linear time, so this is meant for algorithms that scan
+    /// the candidate once and then decode the part they're interested in.
+    ///
+    /// ASCII characters are searched for at the byte level, which is only
+    /// correct if the candidate is not normalized (a normalized `'é'` could
+    /// match an `'e'`), so this variant must not be used with normalization.
+    Utf8(&'a str),
 }
 
 impl core::fmt::Debug for Candidate<'_> {
@@ -20,6 +38,8 @@ impl core::fmt::Debug for Candidate<'_> {
             Candidate::Unicode(slice) => {
                 slice.iter().collect::<String>().fmt(f)
             },
+
+            Candidate::Utf8(str) => str.fmt(f),
         }
     }
 }
@@ -31,6 +51,9 @@ impl<'a> Candidate<'a> {
         match self {
             Candidate::Ascii(candidate) => candidate[char_idx] as _,
             Candidate::Unicode(candidate) => candidate[char_idx],
+            Candidate::Utf8(candidate) => {
+                candidate.chars().nth(char_idx).unwrap()
+            },
         }
     }
 
@@ -50,6 +73,9 @@ impl<'a> Candidate<'a> {
             Candidate::Unicode(slice) => {
                 Chars::Unicode(slice[char_offset..].iter())
             },
+            Candidate::Utf8(str) => {
+                Chars::Utf8(str[utf8_byte_offset(str, char_offset)..].chars())
+            },
         }
     }
 
@@ -59,43 +85,43 @@ impl<'a> Candidate<'a> {
         match self {
             Candidate::Ascii(slice) => slice.len(),
             Candidate::Unicode(slice) => slice.len(),
+            Candidate::Utf8(str) => str.chars().count(),
         }
     }
 
+    #[cfg(any(feature = "fzf-v2", feature = "skim"))]
     /// TODO: docs
     #[inline(always)]
-    pub fn find_first_from(
+    pub fn find_last(
         self,
-        char_offset: usize,
         ch: char,
         is_case_sensitive: bool,
         char_eq: CharEq,
     ) -> Option<usize> {
         match self {
             Candidate::Ascii(slice) => {
-                if !ch.is_ascii() {
-                    return None;
+                if ch.is_ascii() {
+                    find_last_ascii(ch as _, slice, is_case_sensitive)
+                } else {
+                    None
                 }
-
-                let slice = &slice[char_offset..];
-
-                find_first_ascii(ch as _, slice, is_case_sensitive)
-                    .map(|offset| offset + char_offset)
             },
 
-            Candidate::Unicode(slice) => {
-                let slice = &slice[char_offset..];
+            Candidate::Unicode(slice) => find_last_unicode(ch, slice, char_eq),
 
-                find_first_unicode(ch, slice, char_eq)
-                    .map(|idx| idx + char_offset)
+            Candidate::Utf8(str) => {
+                find_last_utf8(ch, str, is_case_sensitive, char_eq)
+                    .map(|idx| utf8_char_len(&str.as_bytes()[..idx]))
             },
         }
     }
 
     /// TODO: docs
+    #[allow(dead_code)]
     #[inline(always)]
-    pub fn find_last(
+    pub fn find_last_from(
         self,
+        end_offset: usize,
         ch: char,
         is_case_sensitive: bool,
         char_eq: CharEq,
@@ -103,39 +129,177 @@ impl<'a> Candidate<'a> {
         match self {
             Candidate::Ascii(slice) => {
                 if ch.is_ascii() {
+                    let slice = &slice[..end_offset];
                     find_last_ascii(ch as _, slice, is_case_sensitive)
                 } else {
                     None
                 }
             },
 
-            Candidate::Unicode(slice) => find_last_unicode(ch, slice, char_eq),
+            Candidate::Unicode(slice) => {
+                let slice = &slice[..end_offset];
+                find_last_unicode(ch, slice, char_eq)
+            },
+
+            Candidate::Utf8(str) => {
+                let str = &str[..utf8_byte_offset(str, end_offset)];
+                find_last_utf8(ch, str, is_case_sensitive, char_eq)
+                    .map(|idx| utf8_char_len(&str.as_bytes()[..idx]))
+            },
         }
     }
 
-    /// TODO: docs
-    #[allow(dead_code)]
+    /// Looks for the first occurrence of `chars` as a subsequence of the
+    /// candidate, calling `on_match` with the char offset of every matched
+    /// character.
+    ///
+    /// Returns the char offset of the character matching the last element of
+    /// `chars`.
     #[inline(always)]
-    pub fn find_last_from(
+    pub fn find_subsequence<I, F>(
         self,
-        end_offset: usize,
-        ch: char,
+        chars: I,
         is_case_sensitive: bool,
         char_eq: CharEq,
-    ) -> Option<usize> {
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
         match self {
-            Candidate::Ascii(slice) => {
-                if ch.is_ascii() {
-                    let slice = &slice[..end_offset];
-                    find_last_ascii(ch as _, slice, is_case_sensitive)
-                } else {
-                    None
+            Candidate::Ascii(slice) => subsequence::find_first(
+                slice,
+                chars,
+                is_case_sensitive,
+                on_match,
+            ),
+
+            Candidate::Unicode(slice) => {
+                let mut last_match = None;
+
+                let mut offset = 0;
+
+                for ch in chars {
+                    let idx =
+                        find_first_unicode(ch, &slice[offset..], char_eq)?
+                            + offset;
+
+                    on_match(idx);
+
+                    last_match = Some(idx);
+
+                    offset = idx + 1;
                 }
+
+                last_match
             },
 
+            Candidate::Utf8(str) => {
+                let mut last_match = None;
+
+                let mut byte_offset = 0;
+
+                let mut char_offset = 0;
+
+                for ch in chars {
+                    let (idx, ch_len) = find_first_utf8(
+                        ch,
+                        &str[byte_offset..],
+                        is_case_sensitive,
+                        char_eq,
+                    )?;
+
+                    let idx = byte_offset + idx;
+
+                    char_offset +=
+                        utf8_char_len(&str.as_bytes()[byte_offset..idx]);
+
+                    on_match(char_offset);
+
+                    last_match = Some(char_offset);
+
+                    byte_offset = idx + ch_len;
+
+                    char_offset += 1;
+                }
+
+                last_match
+            },
+        }
+    }
+
+    /// Like [`find_subsequence`](Self::find_subsequence), but scans the
+    /// candidate backwards starting from `end_offset` (excluded), looking for
+    /// `chars` in reverse order.
+    ///
+    /// Returns the char offset of the character matching the last element of
+    /// `chars`, i.e. the start of the match.
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[inline(always)]
+    pub fn find_subsequence_rev<I, F>(
+        self,
+        end_offset: usize,
+        chars: I,
+        is_case_sensitive: bool,
+        char_eq: CharEq,
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
+        match self {
+            Candidate::Ascii(slice) => subsequence::find_last(
+                &slice[..end_offset],
+                chars,
+                is_case_sensitive,
+                on_match,
+            ),
+
             Candidate::Unicode(slice) => {
-                let slice = &slice[..end_offset];
-                find_last_unicode(ch, slice, char_eq)
+                let mut last_match = None;
+
+                let mut end = end_offset;
+
+                for ch in chars {
+                    let idx = find_last_unicode(ch, &slice[..end], char_eq)?;
+
+                    on_match(idx);
+
+                    last_match = Some(idx);
+
+                    end = idx;
+                }
+
+                last_match
+            },
+
+            Candidate::Utf8(str) => {
+                let mut last_match = None;
+
+                let mut byte_end = utf8_byte_offset(str, end_offset);
+
+                let mut char_end = end_offset;
+
+                for ch in chars {
+                    let idx = find_last_utf8(
+                        ch,
+                        &str[..byte_end],
+                        is_case_sensitive,
+                        char_eq,
+                    )?;
+
+                    char_end -= utf8_char_len(&str.as_bytes()[idx..byte_end]);
+
+                    on_match(char_end);
+
+                    last_match = Some(char_end);
+
+                    byte_end = idx;
+                }
+
+                last_match
             },
         }
     }
@@ -151,9 +315,14 @@ impl<'a> Candidate<'a> {
             Candidate::Unicode(slice) => {
                 slice.iter().take_while(|&&ch| ch == ' ').count()
             },
+
+            Candidate::Utf8(str) => {
+                str.bytes().take_while(|&byte| byte == b' ').count()
+            },
         }
     }
 
+    #[cfg(feature = "fzf-v2")]
     /// TODO: docs
     #[inline(always)]
     pub fn matches(
@@ -170,9 +339,18 @@ impl<'a> Candidate<'a> {
             Candidate::Unicode(slice) => {
                 CandidateMatches::from_unicode(ch, slice, char_eq, 0)
             },
+
+            Candidate::Utf8(str) => CandidateMatches::from_utf8(
+                ch,
+                str,
+                is_case_sensitive,
+                char_eq,
+                0,
+            ),
         }
     }
 
+    #[cfg(feature = "fzf-v2")]
     /// TODO: docs
     #[inline(always)]
     pub fn matches_from(
@@ -197,6 +375,17 @@ impl<'a> Candidate<'a> {
                 let slice = &slice[char_offset..];
                 CandidateMatches::from_unicode(ch, slice, char_eq, char_offset)
             },
+
+            Candidate::Utf8(str) => {
+                let str = &str[utf8_byte_offset(str, char_offset)..];
+                CandidateMatches::from_utf8(
+                    ch,
+                    str,
+                    is_case_sensitive,
+                    char_eq,
+                    char_offset,
+                )
+            },
         }
     }
 
@@ -209,6 +398,10 @@ impl<'a> Candidate<'a> {
             Candidate::Unicode(slice) => {
                 Candidate::Unicode(&slice[char_range])
             },
+
+            Candidate::Utf8(str) => {
+                Candidate::Utf8(&str[self.to_byte_range(char_range)])
+            },
         }
     }
 
@@ -220,6 +413,7 @@ impl<'a> Candidate<'a> {
             Candidate::Unicode(slice) => {
                 slice[..char_offset].iter().map(|&ch| ch.len_utf8()).sum()
             },
+            Candidate::Utf8(str) => utf8_byte_offset(str, char_offset),
         }
     }
 
@@ -243,6 +437,18 @@ impl<'a> Candidate<'a> {
 
                 start..end
             },
+
+            Candidate::Utf8(str) => {
+                let start = utf8_byte_offset(str, char_range.start);
+
+                let end = start
+                    + utf8_byte_offset(
+                        &str[start..],
+                        char_range.end - char_range.start,
+                    );
+
+                start..end
+            },
         }
     }
 
@@ -257,6 +463,10 @@ impl<'a> Candidate<'a> {
             Candidate::Unicode(slice) => {
                 slice.iter().rev().take_while(|&&ch| ch == ' ').count()
             },
+
+            Candidate::Utf8(str) => {
+                str.bytes().rev().take_while(|&byte| byte == b' ').count()
+            },
         }
     }
 }
@@ -312,10 +522,66 @@ fn find_last_unicode(
         .find_map(|(idx, &ch)| char_eq(needle, ch).then_some(idx))
 }
 
+/// Returns the byte offset of the first char in `haystack` that matches
+/// `needle`, together with the length in bytes of that char.
+#[inline(always)]
+fn find_first_utf8(
+    needle: char,
+    haystack: &str,
+    is_case_sensitive: bool,
+    char_eq: CharEq,
+) -> Option<(usize, usize)> {
+    if needle.is_ascii() {
+        // Every byte of a multi-byte char is >= 0x80, so an ASCII needle can
+        // only match ASCII bytes.
+        find_first_ascii(needle as _, haystack.as_bytes(), is_case_sensitive)
+            .map(|idx| (idx, 1))
+    } else {
+        haystack.char_indices().find_map(|(idx, ch)| {
+            char_eq(needle, ch).then_some((idx, ch.len_utf8()))
+        })
+    }
+}
+
+/// Returns the byte offset of the last char in `haystack` that matches
+/// `needle`.
+#[inline(always)]
+fn find_last_utf8(
+    needle: char,
+    haystack: &str,
+    is_case_sensitive: bool,
+    char_eq: CharEq,
+) -> Option<usize> {
+    if needle.is_ascii() {
+        find_last_ascii(needle as _, haystack.as_bytes(), is_case_sensitive)
+    } else {
+        haystack
+            .char_indices()
+            .rev()
+            .find_map(|(idx, ch)| char_eq(needle, ch).then_some(idx))
+    }
+}
+
+/// Returns the number of chars encoded in the given UTF-8 bytes.
+#[inline(always)]
+fn utf8_char_len(bytes: &[u8]) -> usize {
+    // Every char starts with exactly one byte that's not a continuation byte,
+    // i.e. one that's not of the form `0b10xx_xxxx`.
+    bytes.iter().filter(|&&byte| (byte as i8) >= -0x40).count()
+}
+
+/// Returns the byte offset of the char at the given char offset, or the
+/// length of the string if the offset is equal to its char length.
+#[inline(always)]
+fn utf8_byte_offset(str: &str, char_offset: usize) -> usize {
+    str.char_indices().nth(char_offset).map_or(str.len(), |(idx, _)| idx)
+}
+
 /// TODO: docs
 pub(crate) enum Chars<'a> {
     Ascii(core::slice::Iter<'a, u8>),
     Unicode(core::slice::Iter<'a, char>),
+    Utf8(core::str::Chars<'a>),
 }
 
 impl Iterator for Chars<'_> {
@@ -326,6 +592,7 @@ impl Iterator for Chars<'_> {
         match self {
             Chars::Ascii(iter) => iter.next().copied().map(char::from),
             Chars::Unicode(iter) => iter.next().copied(),
+            Chars::Utf8(iter) => iter.next(),
         }
     }
 }
@@ -336,16 +603,19 @@ impl DoubleEndedIterator for Chars<'_> {
         match self {
             Chars::Ascii(iter) => iter.next_back().copied().map(char::from),
             Chars::Unicode(iter) => iter.next_back().copied(),
+            Chars::Utf8(iter) => iter.next_back(),
         }
     }
 }
 
+#[cfg(feature = "fzf-v2")]
 /// TODO: docs
 pub(crate) struct CandidateMatches<'a> {
     iter: CandidateMatchesIter<'a>,
     start_offset: usize,
 }
 
+#[cfg(feature = "fzf-v2")]
 impl<'a> CandidateMatches<'a> {
     #[inline(always)]
     fn from_ascii(
@@ -383,14 +653,30 @@ impl<'a> CandidateMatches<'a> {
         let iter = UnicodeMatches::new(needle, haystack, char_eq);
         Self { iter: CandidateMatchesIter::Unicode(iter), start_offset }
     }
+
+    #[inline(always)]
+    fn from_utf8(
+        needle: char,
+        haystack: &'a str,
+        is_case_sensitive: bool,
+        char_eq: CharEq,
+        start_offset: usize,
+    ) -> Self {
+        let iter =
+            Utf8Matches::new(needle, haystack, is_case_sensitive, char_eq);
+        Self { iter: CandidateMatchesIter::Utf8(iter), start_offset }
+    }
 }
 
+#[cfg(feature = "fzf-v2")]
 enum CandidateMatchesIter<'a> {
     Memchr(memchr::Memchr<'a>),
     Memchr2(memchr::Memchr2<'a>),
     Unicode(UnicodeMatches<'a>),
+    Utf8(Utf8Matches<'a>),
 }
 
+#[cfg(feature = "fzf-v2")]
 impl Iterator for CandidateMatches<'_> {
     type Item = usize;
 
@@ -400,11 +686,13 @@ impl Iterator for CandidateMatches<'_> {
             CandidateMatchesIter::Memchr(memchr) => memchr.next(),
             CandidateMatchesIter::Memchr2(memchr2) => memchr2.next(),
             CandidateMatchesIter::Unicode(unicode) => unicode.next(),
+            CandidateMatchesIter::Utf8(utf8) => utf8.next(),
         }
         .map(|offset| self.start_offset + offset)
     }
 }
 
+#[cfg(feature = "fzf-v2")]
 struct UnicodeMatches<'a> {
     needle: char,
     haystack: &'a [char],
@@ -412,12 +700,14 @@ struct UnicodeMatches<'a> {
     offset: usize,
 }
 
+#[cfg(feature = "fzf-v2")]
 impl<'a> UnicodeMatches<'a> {
     fn new(ch: char, haystack: &'a [char], char_eq: CharEq) -> Self {
         Self { needle: ch, haystack, char_eq, offset: 0 }
     }
 }
 
+#[cfg(feature = "fzf-v2")]
 impl Iterator for UnicodeMatches<'_> {
     type Item = usize;
 
@@ -437,3 +727,170 @@ impl Iterator for UnicodeMatches<'_> {
         Some(offset)
     }
 }
+
+#[cfg(feature = "fzf-v2")]
+struct Utf8Matches<'a> {
+    needle: char,
+    haystack: &'a str,
+    is_case_sensitive: bool,
+    char_eq: CharEq,
+    offset: usize,
+}
+
+#[cfg(feature = "fzf-v2")]
+impl<'a> Utf8Matches<'a> {
+    fn new(
+        ch: char,
+        haystack: &'a str,
+        is_case_sensitive: bool,
+        char_eq: CharEq,
+    ) -> Self {
+        Self { needle: ch, haystack, is_case_sensitive, char_eq, offset: 0 }
+    }
+}
+
+#[cfg(feature = "fzf-v2")]
+impl Iterator for Utf8Matches<'_> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (idx, ch_len) = find_first_utf8(
+            self.needle,
+            self.haystack,
+            self.is_case_sensitive,
+            self.char_eq,
+        )?;
+
+        let offset =
+            self.offset + utf8_char_len(&self.haystack.as_bytes()[..idx]);
+
+        self.haystack = &self.haystack[idx + ch_len..];
+
+        self.offset = offset + 1;
+
+        Some(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf8_same_as_unicode() {
+        let mut rng = crate::utils::Xorshift::new(0x9e37_79b9_7f4a_7c15);
+
+        let alphabet = ['a', 'A', 'b', 'é', 'É', 'ß', '_', ' ', '名', '🦀'];
+
+        let mut random_string = |max_len: usize| {
+            let len = rng.next(max_len + 1);
+            (0..len)
+                .map(|_| alphabet[rng.next(alphabet.len())])
+                .collect::<String>()
+        };
+
+        for _ in 0..10_000 {
+            let candidate = random_string(24);
+
+            let pattern = random_string(4);
+
+            let chars = candidate.chars().collect::<Vec<_>>();
+
+            let unicode = Candidate::Unicode(&chars);
+
+            let utf8 = Candidate::Utf8(&candidate);
+
+            for is_case_sensitive in [true, false] {
+                let char_eq = char_eq(is_case_sensitive, false);
+
+                let mut expected = Vec::new();
+
+                let mut actual = Vec::new();
+
+                assert_eq!(
+                    unicode.find_subsequence(
+                        pattern.chars(),
+                        is_case_sensitive,
+                        char_eq,
+                        |offset| expected.push(offset),
+                    ),
+                    utf8.find_subsequence(
+                        pattern.chars(),
+                        is_case_sensitive,
+                        char_eq,
+                        |offset| actual.push(offset),
+                    ),
+                    "{candidate:?} {pattern:?}"
+                );
+
+                assert_eq!(expected, actual, "{candidate:?} {pattern:?}");
+
+                let end_offset = chars.len() / 2;
+
+                #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+                assert_eq!(
+                    unicode.find_subsequence_rev(
+                        end_offset,
+                        pattern.chars().rev(),
+                        is_case_sensitive,
+                        char_eq,
+                        |_| {},
+                    ),
+                    utf8.find_subsequence_rev(
+                        end_offset,
+                        pattern.chars().rev(),
+                        is_case_sensitive,
+                        char_eq,
+                        |_| {},
+                    ),
+                    "{candidate:?} {pattern:?}"
+                );
+
+                if let Some(ch) = pattern.chars().next() {
+                    #[cfg(any(feature = "fzf-v2", feature = "skim"))]
+                    assert_eq!(
+                        unicode.find_last(ch, is_case_sensitive, char_eq),
+                        utf8.find_last(ch, is_case_sensitive, char_eq),
+                    );
+
+                    #[cfg(feature = "fzf-v2")]
+                    assert_eq!(
+                        unicode
+                            .matches_from(
+                                end_offset,
+                                ch,
+                                is_case_sensitive,
+                                char_eq
+                            )
+                            .collect::<Vec<_>>(),
+                        utf8.matches_from(
+                            end_offset,
+                            ch,
+                            is_case_sensitive,
+                            char_eq
+                        )
+                        .collect::<Vec<_>>(),
+                        "{candidate:?} {ch:?}"
+                    );
+                }
+            }
+
+            let range = chars.len() / 3..chars.len() / 2;
+
+            assert_eq!(
+                unicode.to_byte_range(range.clone()),
+                utf8.to_byte_range(range.clone())
+            );
+
+            assert_eq!(
+                unicode.slice(range.clone()).chars().collect::<String>(),
+                utf8.slice(range).chars().collect::<String>()
+            );
+
+            assert_eq!(unicode.leading_spaces(), utf8.leading_spaces());
+
+            assert_eq!(unicode.trailing_spaces(), utf8.trailing_spaces());
+        }
+    }
+}
@@ -0,0 +1,598 @@
+//! Combinators to build new metrics out of existing ones.
+//!
+//! The types in this module are created by the provided methods of the
+//! [`Metric`] trait, in the same way as the adapters of the standard
+//! library's `Iterator`. See [`Metric::then`], [`Metric::filter`],
+//! [`Metric::map_candidate`], [`Metric::reversed`] and [`Metric::weighted`]
+//! for more information.
+
+use core::cmp::{Ordering, Reverse};
+use core::ops::Range;
+
+use crate::Metric;
+
+/// A metric that ranks candidates with a first metric, breaking ties with a
+/// second one.
+///
+/// This `struct` is created by the [`then`](Metric::then) method on
+/// [`Metric`]. See its documentation for more.
+#[derive(Debug, Clone, Default)]
+pub struct Then<A, B> {
+    /// The metric whose distance is compared first.
+    first: A,
+
+    /// The metric used to break the ties of the first one.
+    second: B,
+}
+
+impl<A, B> Then<A, B> {
+    /// Creates a new `Then`.
+    #[inline(always)]
+    pub(crate) fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns a mutable reference to the first metric, e.g. to change its
+    /// options.
+    #[inline(always)]
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Returns a mutable reference to the second metric, e.g. to change its
+    /// options.
+    #[inline(always)]
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+}
+
+impl<A: Metric, B: Metric> Metric for Then<A, B> {
+    type Query<'a> = (A::Query<'a>, B::Query<'a>);
+
+    type Distance = (A::Distance, B::Distance);
+
+    #[inline]
+    fn distance(
+        &mut self,
+        (first_query, second_query): Self::Query<'_>,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let first = self.first.distance(first_query, candidate)?;
+        let second = self.second.distance(second_query, candidate)?;
+        Some((first, second))
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        (first_query, second_query): Self::Query<'_>,
+        candidate: &str,
+        ranges_buf: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let initial_len = ranges_buf.len();
+
+        let first = self.first.distance_and_ranges(
+            first_query,
+            candidate,
+            ranges_buf,
+        )?;
+
+        let Some(second) = self.second.distance(second_query, candidate)
+        else {
+            // The candidate is not a match, so we remove the ranges added by
+            // the first metric.
+            ranges_buf.truncate(initial_len);
+            return None;
+        };
+
+        Some((first, second))
+    }
+
+    #[inline]
+    fn distance_within(
+        &mut self,
+        (first_query, second_query): Self::Query<'_>,
+        candidate: &str,
+        (first_max, second_max): &Self::Distance,
+    ) -> Option<Self::Distance> {
+        let first =
+            self.first.distance_within(first_query, candidate, first_max)?;
+
+        // The distances are compared lexicographically, so the second one
+        // only has to be within its cutoff if the first one is a tie.
+        let second = if first == *first_max {
+            self.second.distance_within(second_query, candidate, second_max)?
+        } else {
+            self.second.distance(second_query, candidate)?
+        };
+
+        Some((first, second))
+    }
+}
+
+/// A metric that only matches the candidates satisfying a predicate.
+///
+/// This `struct` is created by the [`filter`](Metric::filter) method on
+/// [`Metric`]. See its documentation for more.
+#[derive(Clone)]
+pub struct Filter<M, F> {
+    /// The metric whose matches are filtered.
+    metric: M,
+
+    /// Called with every candidate, which is only scored if it returns
+    /// `true`.
+    predicate: F,
+}
+
+impl<M: core::fmt::Debug, F> core::fmt::Debug for Filter<M, F> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Filter")
+            .field("metric", &self.metric)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M, F> Filter<M, F> {
+    /// Creates a new `Filter`.
+    #[inline(always)]
+    pub(crate) fn new(metric: M, predicate: F) -> Self {
+        Self { metric, predicate }
+    }
+
+    /// Returns a mutable reference to the inner metric, e.g. to change its
+    /// options.
+    #[inline(always)]
+    pub fn metric_mut(&mut self) -> &mut M {
+        &mut self.metric
+    }
+}
+
+impl<M, F> Metric for Filter<M, F>
+where
+    M: Metric,
+    F: FnMut(&str) -> bool,
+{
+    type Query<'a> = M::Query<'a>;
+
+    type Distance = M::Distance;
+
+    #[inline]
+    fn distance(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        if !(self.predicate)(candidate) {
+            return None;
+        }
+
+        self.metric.distance(query, candidate)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+        ranges_buf: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        if !(self.predicate)(candidate) {
+            return None;
+        }
+
+        self.metric.distance_and_ranges(query, candidate, ranges_buf)
+    }
+
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+        max_distance: &Self::Distance,
+    ) -> Option<Self::Distance> {
+        if !(self.predicate)(candidate) {
+            return None;
+        }
+
+        self.metric.distance_within(query, candidate, max_distance)
+    }
+}
+
+/// A metric that matches the query against a sub-string of every candidate.
+///
+/// This `struct` is created by the [`map_candidate`](Metric::map_candidate)
+/// method on [`Metric`]. See its documentation for more.
+#[derive(Clone)]
+pub struct MapCandidate<M, F> {
+    /// The metric run on the mapped candidate.
+    metric: M,
+
+    /// Maps every candidate to the part of it that should be matched.
+    map: F,
+}
+
+impl<M: core::fmt::Debug, F> core::fmt::Debug for MapCandidate<M, F> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MapCandidate")
+            .field("metric", &self.metric)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M, F> MapCandidate<M, F> {
+    /// Creates a new `MapCandidate`.
+    #[inline(always)]
+    pub(crate) fn new(metric: M, map: F) -> Self {
+        Self { metric, map }
+    }
+
+    /// Returns a mutable reference to the inner metric, e.g. to change its
+    /// options.
+    #[inline(always)]
+    pub fn metric_mut(&mut self) -> &mut M {
+        &mut self.metric
+    }
+}
+
+impl<M, F> Metric for MapCandidate<M, F>
+where
+    M: Metric,
+    F: for<'c> FnMut(&'c str) -> &'c str,
+{
+    type Query<'a> = M::Query<'a>;
+
+    type Distance = M::Distance;
+
+    #[inline]
+    fn distance(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let mapped = (self.map)(candidate);
+        self.metric.distance(query, mapped)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+        ranges_buf: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let mapped = (self.map)(candidate);
+
+        let initial_len = ranges_buf.len();
+
+        let distance =
+            self.metric.distance_and_ranges(query, mapped, ranges_buf)?;
+
+        if ranges_buf.len() == initial_len {
+            return Some(distance);
+        }
+
+        match substr_offset(candidate, mapped) {
+            Some(offset) => {
+                for range in &mut ranges_buf[initial_len..] {
+                    range.start += offset;
+                    range.end += offset;
+                }
+            },
+
+            // The ranges don't point into the candidate, so we drop them.
+            None => ranges_buf.truncate(initial_len),
+        }
+
+        Some(distance)
+    }
+
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+        max_distance: &Self::Distance,
+    ) -> Option<Self::Distance> {
+        let mapped = (self.map)(candidate);
+        self.metric.distance_within(query, mapped, max_distance)
+    }
+}
+
+/// Returns the byte offset of `substr` in `s`, or `None` if `substr` is not
+/// a sub-slice of `s`.
+#[inline(always)]
+fn substr_offset(s: &str, substr: &str) -> Option<usize> {
+    let start = s.as_ptr() as usize;
+
+    let substr_start = substr.as_ptr() as usize;
+
+    (start <= substr_start && substr_start + substr.len() <= start + s.len())
+        .then(|| substr_start - start)
+}
+
+/// A metric that ranks candidates in the opposite order of another metric.
+///
+/// This `struct` is created by the [`reversed`](Metric::reversed) method on
+/// [`Metric`]. See its documentation for more.
+#[derive(Debug, Clone, Default)]
+pub struct Reversed<M> {
+    /// The metric whose distances are reversed.
+    metric: M,
+}
+
+impl<M> Reversed<M> {
+    /// Creates a new `Reversed`.
+    #[inline(always)]
+    pub(crate) fn new(metric: M) -> Self {
+        Self { metric }
+    }
+
+    /// Returns a mutable reference to the inner metric, e.g. to change its
+    /// options.
+    #[inline(always)]
+    pub fn metric_mut(&mut self) -> &mut M {
+        &mut self.metric
+    }
+}
+
+impl<M: Metric> Metric for Reversed<M> {
+    type Query<'a> = M::Query<'a>;
+
+    type Distance = Reverse<M::Distance>;
+
+    #[inline]
+    fn distance(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.metric.distance(query, candidate).map(Reverse)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+        ranges_buf: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        self.metric
+            .distance_and_ranges(query, candidate, ranges_buf)
+            .map(Reverse)
+    }
+}
+
+/// A distance that can be expressed as a relevance, i.e. a number where
+/// higher values indicate better matches.
+///
+/// This is used by [`Weighted`] to combine the distance of a candidate with
+/// its external weight.
+pub trait Relevance {
+    /// Returns the relevance of the match. Two distances `a` and `b` of the
+    /// same metric should satisfy `a < b` if and only if `a.relevance() >
+    /// b.relevance()`.
+    fn relevance(&self) -> f64;
+}
+
+/// The ways a [`Weighted`] metric can combine the distance of a candidate
+/// with its weight.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Combination {
+    /// Candidates are ranked by the sum of their
+    /// [relevance](Relevance::relevance) and their weight, so weights should
+    /// be expressed in the same unit as the relevances of the inner metric.
+    Add,
+
+    /// Candidates are ranked by the product of their
+    /// [relevance](Relevance::relevance) and their weight. This is meant to
+    /// be used with metrics whose relevances are never negative.
+    Multiply,
+
+    /// Candidates are ranked by the distance of the inner metric, and the
+    /// weight is only used to break the ties between candidates with the
+    /// same distance.
+    #[default]
+    TieBreak,
+}
+
+/// A metric that boosts the candidates of another metric with an external,
+/// per-candidate weight, e.g. how recently or how frequently they were used.
+///
+/// This `struct` is created by the [`weighted`](Metric::weighted) method on
+/// [`Metric`]. See its documentation for more.
+#[derive(Clone)]
+pub struct Weighted<M, W> {
+    /// The metric whose distances are weighted.
+    metric: M,
+
+    /// Returns the weight of every candidate, where higher is better.
+    weight: W,
+
+    /// How the weight is combined with the distance of the inner metric.
+    combination: Combination,
+}
+
+impl<M: core::fmt::Debug, W> core::fmt::Debug for Weighted<M, W> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Weighted")
+            .field("metric", &self.metric)
+            .field("combination", &self.combination)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M, W> Weighted<M, W> {
+    /// Creates a new `Weighted`.
+    #[inline(always)]
+    pub(crate) fn new(metric: M, weight: W) -> Self {
+        Self { metric, weight, combination: Combination::default() }
+    }
+
+    /// Returns a mutable reference to the inner metric, e.g. to change its
+    /// options.
+    #[inline(always)]
+    pub fn metric_mut(&mut self) -> &mut M {
+        &mut self.metric
+    }
+
+    /// Sets how the distance of a candidate is combined with its weight. The
+    /// default is [`Combination::TieBreak`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::combinators::Combination;
+    /// # use norm::levenshtein::Levenshtein;
+    /// # use norm::Metric;
+    /// let weight = |candidate: &str| match candidate {
+    ///     "fob" => 1.0,
+    ///     _ => 0.0,
+    /// };
+    ///
+    /// let mut lev = Levenshtein::new().weighted(weight);
+    ///
+    /// // By default the weight only breaks ties.
+    /// assert!(lev.distance("foo", "foo") < lev.distance("foo", "fob"));
+    ///
+    /// lev.set_combination(Combination::Add);
+    ///
+    /// // "foo" has a relevance of 1.0, "fob" of 0.5 + 1.0.
+    /// assert!(lev.distance("foo", "fob") < lev.distance("foo", "foo"));
+    /// ```
+    #[inline(always)]
+    pub fn set_combination(&mut self, combination: Combination) -> &mut Self {
+        self.combination = combination;
+        self
+    }
+
+    /// Combines the distance of a candidate with its weight.
+    #[inline(always)]
+    fn combine<D: Relevance>(
+        &self,
+        distance: D,
+        weight: f64,
+    ) -> WeightedDistance<D> {
+        let combined = match self.combination {
+            Combination::Add => Some(distance.relevance() + weight),
+            // Relevances can be infinite (e.g. fzy's exact matches), and
+            // multiplying them by a zero weight would give NaN.
+            Combination::Multiply => {
+                let relevance = distance.relevance().clamp(f64::MIN, f64::MAX);
+                Some(relevance * weight)
+            },
+            Combination::TieBreak => None,
+        };
+
+        WeightedDistance { combined, distance, weight }
+    }
+}
+
+impl<M, W> Metric for Weighted<M, W>
+where
+    M: Metric,
+    M::Distance: Relevance,
+    W: FnMut(&str) -> f64,
+{
+    type Query<'a> = M::Query<'a>;
+
+    type Distance = WeightedDistance<M::Distance>;
+
+    #[inline]
+    fn distance(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let distance = self.metric.distance(query, candidate)?;
+        let weight = (self.weight)(candidate);
+        Some(self.combine(distance, weight))
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+        ranges_buf: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let distance =
+            self.metric.distance_and_ranges(query, candidate, ranges_buf)?;
+        let weight = (self.weight)(candidate);
+        Some(self.combine(distance, weight))
+    }
+}
+
+/// The distance type of a [`Weighted`] metric.
+///
+/// Distances are ordered by their combined relevance first (unless the
+/// combination is [`Combination::TieBreak`]), then by the distance of the
+/// inner metric, and finally by descending weight.
+#[derive(Debug, Clone, Copy)]
+pub struct WeightedDistance<D> {
+    /// The combined relevance, or `None` if the weight is only used to break
+    /// ties.
+    combined: Option<f64>,
+
+    /// The distance returned by the inner metric.
+    distance: D,
+
+    /// The weight of the candidate.
+    weight: f64,
+}
+
+impl<D: Ord> PartialEq for WeightedDistance<D> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<D: Ord> Eq for WeightedDistance<D> {}
+
+impl<D: Ord> PartialOrd for WeightedDistance<D> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<D: Ord> Ord for WeightedDistance<D> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher relevances and weights are better, so they're compared in
+        // reverse to make the type act like a distance.
+        let combined = match (self.combined, other.combined) {
+            (Some(this), Some(other)) => other.total_cmp(&this),
+            _ => Ordering::Equal,
+        };
+
+        combined
+            .then_with(|| self.distance.cmp(&other.distance))
+            .then_with(|| other.weight.total_cmp(&self.weight))
+    }
+}
+
+impl<D> WeightedDistance<D> {
+    /// Returns the distance computed by the inner metric.
+    #[inline(always)]
+    pub fn inner(&self) -> &D {
+        &self.distance
+    }
+
+    /// Returns the weight of the candidate.
+    #[inline(always)]
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Returns the combined relevance of the candidate, or `None` if the
+    /// distance was created with [`Combination::TieBreak`].
+    #[inline(always)]
+    pub fn combined(&self) -> Option<f64> {
+        self.combined
+    }
+}
@@ -0,0 +1,293 @@
+use core::any::Any;
+use core::cmp::Ordering;
+use core::ops::Range;
+
+use crate::{Metric, Parser, StrParser};
+
+/// A dyn-compatible version of the [`Metric`] trait.
+///
+/// [`Metric`] can't be used as a trait object because its
+/// [`Query`](Metric::Query) is a generic associated type. This trait takes
+/// the query as a raw string instead, and it returns a type-erased
+/// [`DynDistance`], so that different metrics can be stored behind a
+/// `Box<dyn DynMetric>` and switched at runtime.
+///
+/// It's implemented by the [`Dynamic`] wrapper, which parses the query with
+/// the metric's [`Parser`] on every call.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::fzf::{FzfParser, FzfV1, FzfV2};
+/// # use norm::{DynMetric, Dynamic};
+/// fn metric(use_v2: bool) -> Box<dyn DynMetric> {
+///     if use_v2 {
+///         Box::new(Dynamic::with_parser(FzfV2::new(), FzfParser::new()))
+///     } else {
+///         Box::new(Dynamic::with_parser(FzfV1::new(), FzfParser::new()))
+///     }
+/// }
+///
+/// let mut fzf = metric(true);
+///
+/// let mut ranges = Vec::new();
+///
+/// let foo = fzf.distance_and_ranges("fb", "foo_bar", &mut ranges).unwrap();
+///
+/// assert_eq!(ranges, [0..1, 4..5]);
+///
+/// let fob = fzf.distance("fb", "fooobar").unwrap();
+///
+/// assert!(foo < fob);
+/// ```
+pub trait DynMetric {
+    /// Same as [`Metric::distance`], but with a raw query string and a
+    /// type-erased distance.
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<DynDistance>;
+
+    /// Same as [`Metric::distance_and_ranges`], but with a raw query string
+    /// and a type-erased distance.
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges_buf: &mut Vec<Range<usize>>,
+    ) -> Option<DynDistance>;
+
+    /// Same as [`Metric::distance_within`], but with a raw query string and
+    /// type-erased distances.
+    ///
+    /// The default implementation filters the result of
+    /// [`distance`](DynMetric::distance), while [`Dynamic`] forwards the
+    /// call to the metric it wraps if `max_distance` was returned by the same
+    /// kind of metric.
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        max_distance: &DynDistance,
+    ) -> Option<DynDistance> {
+        self.distance(query, candidate)
+            .filter(|distance| distance <= max_distance)
+    }
+}
+
+/// A wrapper around a [`Metric`] and its [`Parser`] implementing
+/// [`DynMetric`].
+///
+/// Metrics whose queries are plain strings are wrapped with
+/// [`Dynamic::new`], which uses the [`StrParser`], while the other metrics
+/// are wrapped together with their parser with [`Dynamic::with_parser`].
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::levenshtein::Levenshtein;
+/// # use norm::{DynMetric, Dynamic};
+/// let mut lev: Box<dyn DynMetric> =
+///     Box::new(Dynamic::new(Levenshtein::new()));
+///
+/// let distance = lev.distance("kitten", "sitting").unwrap();
+///
+/// assert!(distance < lev.distance("kitten", "mittens and gloves").unwrap());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Dynamic<M, P = StrParser> {
+    /// The wrapped metric.
+    metric: M,
+
+    /// Parses the raw query strings into the metric's query type.
+    parser: P,
+}
+
+impl<M> Dynamic<M> {
+    /// Creates a new `Dynamic` wrapping a metric whose queries are plain
+    /// strings.
+    #[inline(always)]
+    pub fn new(metric: M) -> Self {
+        Self { metric, parser: StrParser }
+    }
+}
+
+impl<M, P> Dynamic<M, P> {
+    /// Creates a new `Dynamic` wrapping a metric and the parser used to turn
+    /// raw strings into its queries.
+    #[inline(always)]
+    pub fn with_parser(metric: M, parser: P) -> Self {
+        Self { metric, parser }
+    }
+
+    /// Returns a mutable reference to the inner metric, e.g. to change its
+    /// options.
+    #[inline(always)]
+    pub fn metric_mut(&mut self) -> &mut M {
+        &mut self.metric
+    }
+}
+
+impl<M, P> DynMetric for Dynamic<M, P>
+where
+    M: Metric,
+    M::Distance: Clone + core::fmt::Debug + 'static,
+    P: Parser<M>,
+{
+    #[inline]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<DynDistance> {
+        let query = self.parser.parse(query);
+        self.metric.distance(query, candidate).map(DynDistance::new)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges_buf: &mut Vec<Range<usize>>,
+    ) -> Option<DynDistance> {
+        let query = self.parser.parse(query);
+        self.metric
+            .distance_and_ranges(query, candidate, ranges_buf)
+            .map(DynDistance::new)
+    }
+
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        max_distance: &DynDistance,
+    ) -> Option<DynDistance> {
+        let query = self.parser.parse(query);
+
+        match max_distance.downcast_ref::<M::Distance>() {
+            Some(max) => self
+                .metric
+                .distance_within(query, candidate, max)
+                .map(DynDistance::new),
+
+            None => self
+                .metric
+                .distance(query, candidate)
+                .map(DynDistance::new)
+                .filter(|distance| distance <= max_distance),
+        }
+    }
+}
+
+/// The type-erased distance returned by a [`DynMetric`].
+///
+/// Two `DynDistance`s wrapping the same distance type are ordered like the
+/// distances they wrap. Distances returned by different kinds of metrics are
+/// never equal, and they're ordered by their type: the order is consistent
+/// within a single run of the program, but it's otherwise unspecified and
+/// meaningless.
+#[derive(Debug)]
+pub struct DynDistance {
+    /// The wrapped distance, which can be of any type.
+    inner: Box<dyn ErasedDistance>,
+}
+
+impl Clone for DynDistance {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone_boxed() }
+    }
+}
+
+impl PartialEq for DynDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DynDistance {}
+
+impl PartialOrd for DynDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DynDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        let other = other.inner.as_any();
+
+        self.inner.cmp_erased(other).unwrap_or_else(|| {
+            self.inner.as_any().type_id().cmp(&other.type_id())
+        })
+    }
+}
+
+impl DynDistance {
+    /// Creates a new [`DynDistance`] wrapping the given distance.
+    #[inline(always)]
+    fn new<D: Ord + Clone + core::fmt::Debug + 'static>(distance: D) -> Self {
+        Self { inner: Box::new(distance) }
+    }
+
+    /// Returns a reference to the wrapped distance if it's of type `D`, or
+    /// `None` if it isn't.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::{Levenshtein, LevenshteinDistance};
+    /// # use norm::{DynMetric, Dynamic};
+    /// let mut lev = Dynamic::new(Levenshtein::new());
+    ///
+    /// let distance = lev.distance("kitten", "sitting").unwrap();
+    ///
+    /// let edits = distance
+    ///     .downcast_ref::<LevenshteinDistance>()
+    ///     .map(|distance| distance.edits());
+    ///
+    /// assert_eq!(edits, Some(3));
+    /// ```
+    #[inline(always)]
+    pub fn downcast_ref<D: 'static>(&self) -> Option<&D> {
+        self.inner.as_any().downcast_ref()
+    }
+}
+
+/// An object-safe [`Ord`] and [`Clone`] implemented by every distance type.
+trait ErasedDistance: core::fmt::Debug {
+    /// Compares `self` to `other`, returning `None` if `other` is not of the
+    /// same type.
+    fn cmp_erased(&self, other: &dyn Any) -> Option<Ordering>;
+
+    /// Upcasts `self` to [`Any`], so that it can be downcast to its concrete
+    /// type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Clones `self` into a new box.
+    fn clone_boxed(&self) -> Box<dyn ErasedDistance>;
+}
+
+impl<D: Ord + Clone + core::fmt::Debug + 'static> ErasedDistance for D {
+    #[inline(always)]
+    fn cmp_erased(&self, other: &dyn Any) -> Option<Ordering> {
+        other.downcast_ref::<D>().map(|other| self.cmp(other))
+    }
+
+    #[inline(always)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline(always)]
+    fn clone_boxed(&self) -> Box<dyn ErasedDistance> {
+        Box::new(self.clone())
+    }
+}
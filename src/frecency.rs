@@ -0,0 +1,142 @@
+/// The number of seconds in an hour.
+const HOUR: u64 = 60 * 60;
+
+/// The number of seconds in a day.
+const DAY: u64 = 24 * HOUR;
+
+/// The number of seconds in a week.
+const WEEK: u64 = 7 * DAY;
+
+/// A record of how frequently and how recently an item was used, scored with
+/// the same decay rules used by [zoxide].
+///
+/// Every access increases the rank of the item by one, and the
+/// [score](Self::score) of the item multiplies its rank by a factor that
+/// depends on how long ago it was last accessed:
+///
+/// - within the last hour: `4.0`;
+/// - within the last day: `2.0`;
+/// - within the last week: `0.5`;
+/// - otherwise: `0.25`.
+///
+/// Timestamps are expressed in seconds, and they can be measured from any
+/// fixed point in time (usually the Unix epoch) as long as it's the same for
+/// all of them.
+///
+/// Scores are meant to be used as the weights of a
+/// [`Weighted`](crate::combinators::Weighted) metric.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::Frecency;
+/// let mut frecency = Frecency::new(1_000);
+///
+/// frecency.access(2_000);
+///
+/// assert_eq!(frecency.rank(), 2.0);
+///
+/// // Accessed in the last hour.
+/// assert_eq!(frecency.score(2_500), 8.0);
+///
+/// // Accessed in the last week.
+/// assert_eq!(frecency.score(2_000 + 3 * 24 * 60 * 60), 1.0);
+/// ```
+///
+/// [zoxide]: https://github.com/ajeetdsouza/zoxide
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frecency {
+    /// The number of accesses, scaled down every time the item is aged, like
+    /// zoxide's `rank`.
+    rank: f64,
+
+    /// The timestamp of the last access, in seconds.
+    last_access: u64,
+}
+
+impl Frecency {
+    /// Creates a new `Frecency` for an item accessed for the first time at
+    /// the given timestamp.
+    #[inline(always)]
+    pub fn new(now: u64) -> Self {
+        Self::from_parts(1.0, now)
+    }
+
+    /// Creates a new `Frecency` from a rank and the timestamp of the last
+    /// access, e.g. to restore one that was previously saved to disk.
+    #[inline(always)]
+    pub fn from_parts(rank: f64, last_access: u64) -> Self {
+        Self { rank, last_access }
+    }
+
+    /// Records an access to the item at the given timestamp.
+    #[inline(always)]
+    pub fn access(&mut self, now: u64) {
+        self.rank += 1.0;
+        self.last_access = self.last_access.max(now);
+    }
+
+    /// Multiplies the rank of the item by the given factor.
+    ///
+    /// Like zoxide, this can be used to age all the items once the sum of
+    /// their ranks grows over a maximum, so that items that are no longer
+    /// used eventually fall behind. Items whose rank drops below `1.0` can
+    /// then be forgotten.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::Frecency;
+    /// let mut items = vec![Frecency::from_parts(9_000.0, 0)];
+    ///
+    /// items.push(Frecency::from_parts(2_000.0, 0));
+    /// items.push(Frecency::from_parts(1.0, 0));
+    ///
+    /// let max_total = 10_000.0;
+    ///
+    /// let total = items.iter().map(Frecency::rank).sum::<f64>();
+    ///
+    /// if total > max_total {
+    ///     let factor = 0.9 * max_total / total;
+    ///     items.iter_mut().for_each(|item| item.age(factor));
+    ///     items.retain(|item| item.rank() >= 1.0);
+    /// }
+    ///
+    /// assert_eq!(items.len(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn age(&mut self, factor: f64) {
+        self.rank *= factor;
+    }
+
+    /// Returns the timestamp of the last access to the item.
+    #[inline(always)]
+    pub fn last_access(&self) -> u64 {
+        self.last_access
+    }
+
+    /// Returns the rank of the item, i.e. how many times it was accessed if
+    /// it was never [aged](Self::age).
+    #[inline(always)]
+    pub fn rank(&self) -> f64 {
+        self.rank
+    }
+
+    /// Returns the frecency score of the item at the given timestamp.
+    #[inline(always)]
+    pub fn score(&self, now: u64) -> f64 {
+        let elapsed = now.saturating_sub(self.last_access);
+
+        let factor = if elapsed < HOUR {
+            4.0
+        } else if elapsed < DAY {
+            2.0
+        } else if elapsed < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+
+        self.rank * factor
+    }
+}
@@ -67,8 +67,29 @@
 //!
 //! # Features flags
 //!
+//! - `acronym`: enables the [`Acronym`](metrics::fzf::Acronym) metric;
+//! - `damerau-levenshtein`: enables the
+//!   [`DamerauLevenshtein`](metrics::levenshtein::DamerauLevenshtein) metric;
 //! - `fzf-v1`: enables the [`FzfV1`](metrics::fzf::FzfV1) metric;
 //! - `fzf-v2`: enables the [`FzfV2`](metrics::fzf::FzfV2) metric;
+//! - `fzy`: enables the [`Fzy`](metrics::fzy::Fzy) metric;
+//! - `hamming`: enables the [`Hamming`](metrics::hamming::Hamming) metric;
+//! - `jaro-winkler`: enables the
+//!   [`JaroWinkler`](metrics::jaro_winkler::JaroWinkler) metric;
+//! - `lcs`: enables the [`Lcs`](metrics::lcs::Lcs) metric;
+//! - `levenshtein`: enables the
+//!   [`Levenshtein`](metrics::levenshtein::Levenshtein) metric;
+//! - `ngram`: enables the [`NGram`](metrics::ngram::NGram) metric;
+//! - `phonetic`: enables the [`Phonetic`](metrics::phonetic::Phonetic)
+//!   metric and the Soundex, Metaphone and Double Metaphone encoders;
+//! - `ratcliff-obershelp`: enables the
+//!   [`RatcliffObershelp`](metrics::ratcliff_obershelp::RatcliffObershelp)
+//!   metric;
+//! - `skim`: enables the [`Skim`](metrics::fzf::Skim) metric;
+//! - `token-ratio`: enables the
+//!   [`TokenRatio`](metrics::token_ratio::TokenRatio) metric, together with
+//!   the `ratcliff-obershelp` feature;
+//! - `vscode`: enables the [`VsCode`](metrics::vscode::VsCode) metric;
 //!
 //! [bench]: https://github.com/noib3/fuzzy-benches
 
@@ -82,24 +103,84 @@
 
 extern crate alloc;
 
-#[cfg(feature = "__any-metric")]
+#[cfg(any(
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "fzy",
+    feature = "skim"
+))]
 mod candidate;
 mod case_sensitivity;
+pub mod combinators;
+mod dyn_metric;
+mod frecency;
 #[cfg(feature = "__any-metric")]
 mod matched_ranges;
+#[cfg(any(
+    feature = "acronym",
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "lcs",
+    feature = "skim"
+))]
+mod matrix;
 mod metric;
 #[cfg(feature = "__any-metric")]
 mod metrics;
 #[cfg(feature = "__any-metric")]
 mod normalize;
-#[cfg(feature = "__any-metric")]
+mod parser;
+mod similarity;
+#[cfg(any(
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "fzy",
+    feature = "skim"
+))]
+mod subsequence;
+#[cfg(any(
+    feature = "acronym",
+    feature = "damerau-levenshtein",
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "fzy",
+    feature = "hamming",
+    feature = "jaro-winkler",
+    feature = "lcs",
+    feature = "levenshtein",
+    feature = "ratcliff-obershelp",
+    feature = "skim",
+    feature = "vscode"
+))]
 mod utils;
 
-#[cfg(feature = "__any-metric")]
-use candidate::{Candidate, CandidateMatches};
+#[cfg(any(
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "fzy",
+    feature = "skim"
+))]
+use candidate::Candidate;
+#[cfg(feature = "fzf-v2")]
+use candidate::CandidateMatches;
 pub use case_sensitivity::CaseSensitivity;
+pub use dyn_metric::{DynDistance, DynMetric, Dynamic};
+pub use frecency::Frecency;
 #[cfg(feature = "__any-metric")]
 use matched_ranges::MatchedRanges;
 pub use metric::Metric;
 #[cfg(feature = "__any-metric")]
 pub use metrics::*;
+pub use parser::{Parser, StrParser};
+pub use similarity::Similarity;
+#[cfg(all(
+    feature = "__benches",
+    any(
+        feature = "fzf-v1",
+        feature = "fzf-v2",
+        feature = "fzy",
+        feature = "skim"
+    )
+))]
+#[doc(hidden)]
+pub use subsequence::set_simd_enabled;
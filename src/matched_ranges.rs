@@ -180,6 +180,14 @@ impl<'a> MatchedRanges<'a> {
         }
     }
 
+    /// Removes all the ranges inserted since this struct was created, leaving
+    /// the underlying buffer as it was.
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
+    #[inline(always)]
+    pub(crate) fn clear(&mut self) {
+        self.ranges.truncate(self.initial_len);
+    }
+
     /// TODO: docs
     #[inline(always)]
     fn insert_at(&mut self, idx: usize, range: Range<usize>) {
@@ -0,0 +1,351 @@
+//! A reusable 2D matrix used by the metrics based on dynamic programming.
+
+// Only FzfV2 uses all of this module.
+#![cfg_attr(not(feature = "fzf-v2"), allow(dead_code))]
+
+use core::ops::{Index, IndexMut};
+
+pub(crate) trait MatrixItem: Copy + Ord + core::fmt::Display {
+    /// The value every cell is reset to when a matrix is allocated.
+    fn fill() -> Self;
+
+    /// The number of characters needed to print the value, used to align the
+    /// columns in the `Debug` output of a [`Matrix`].
+    fn printed_width(&self) -> usize;
+}
+
+impl MatrixItem for i64 {
+    #[inline]
+    fn fill() -> Self {
+        0
+    }
+
+    fn printed_width(&self) -> usize {
+        if *self == 0 {
+            1
+        } else {
+            (self.ilog10() + 1) as usize
+        }
+    }
+}
+
+impl MatrixItem for usize {
+    #[inline]
+    fn fill() -> Self {
+        0
+    }
+
+    fn printed_width(&self) -> usize {
+        if *self == 0 {
+            1
+        } else {
+            (self.ilog10() + 1) as usize
+        }
+    }
+}
+
+/// Creating a new [`MatrixSlab`] allocates `256 * size_of::<T>()` bytes.
+#[derive(Clone)]
+pub(crate) struct MatrixSlab<T: MatrixItem> {
+    vec: Vec<T>,
+}
+
+impl<T: Default + MatrixItem> Default for MatrixSlab<T> {
+    #[inline]
+    fn default() -> MatrixSlab<T> {
+        // We allocate a 256 cell matrix slab by default to minimize the
+        // need to re-allocate for long `query * candidate` pairs.
+        Self { vec: vec![T::default(); 256] }
+    }
+}
+
+impl<T: MatrixItem> MatrixSlab<T> {
+    /// Returns a `width * height` matrix with every cell reset to
+    /// [`MatrixItem::fill`], growing the slab if it's too small.
+    #[inline]
+    pub fn alloc(&mut self, width: usize, height: usize) -> Matrix<'_, T> {
+        debug_assert!(height * width > 0);
+
+        if height * width > self.vec.len() {
+            self.vec.resize(height * width, T::fill());
+        }
+
+        let slice = &mut self.vec[..height * width];
+
+        slice.fill(T::fill());
+
+        Matrix { slice, height, width }
+    }
+}
+
+/// A `width * height` matrix borrowed from a [`MatrixSlab`].
+pub(crate) struct Matrix<'a, T: MatrixItem> {
+    /// The cells of the matrix, stored row by row:
+    ///
+    /// ```text
+    /// <width><width>...<width>
+    /// \---- height times ----/
+    /// ```
+    slice: &'a mut [T],
+    height: usize,
+    width: usize,
+}
+
+/// Prints the matrix like this:
+///
+/// ```text
+///   ┌                         ┐
+///   │0  16 16 13 12 11 10 9  8│
+///   │0  0  0  0  0  0  0  0  0│
+///   │0  0  0  0  0  0  0  0  0│
+///   └                         ┘
+/// ```
+impl<T: MatrixItem> core::fmt::Debug for Matrix<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use core::fmt::Write;
+
+        // The matrix should never be empty, but just in case.
+        if self.slice.is_empty() {
+            return f.write_str("[ ]");
+        }
+
+        // The character width of the biggest score in the whole matrix.
+        let max_score_width = {
+            let max_score = self.slice.iter().copied().max().unwrap();
+            max_score.printed_width()
+        };
+
+        // The character width of the biggest score in the last column.
+        let last_col_max_score_width = {
+            // The cell in the last column of the first row.
+            let first_row_last_col =
+                self.cols(self.top_left()).last().unwrap();
+
+            let last_col_max_score = self
+                .rows(first_row_last_col)
+                .map(|cell| self[cell])
+                .max()
+                .unwrap();
+
+            last_col_max_score.printed_width()
+        };
+
+        let printed_matrix_inner_width = (self.width - 1)
+            * (max_score_width + 1)
+            + last_col_max_score_width;
+
+        let opening_char: char;
+
+        let closing_char: char;
+
+        if self.height == 1 {
+            opening_char = '[';
+            closing_char = ']';
+        } else {
+            f.write_char('┌')?;
+            f.write_str(&" ".repeat(printed_matrix_inner_width))?;
+            f.write_char('┐')?;
+            f.write_char('\n')?;
+            opening_char = '│';
+            closing_char = '│';
+        }
+
+        for cell in self.rows(self.top_left()) {
+            f.write_char(opening_char)?;
+
+            for cell in self.cols(cell) {
+                let item = self[cell];
+
+                write!(f, "{item}")?;
+
+                let num_spaces = if self.col_of(cell) + 1 == self.width {
+                    last_col_max_score_width - item.printed_width()
+                } else {
+                    max_score_width - item.printed_width() + 1
+                };
+
+                f.write_str(&" ".repeat(num_spaces))?;
+            }
+
+            f.write_char(closing_char)?;
+
+            f.write_char('\n')?;
+        }
+
+        if self.height > 1 {
+            f.write_char('└')?;
+            f.write_str(&" ".repeat(printed_matrix_inner_width))?;
+            f.write_char('┘')?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, T: MatrixItem + Copy> Matrix<'a, T> {
+    /// Returns the value of the cell, or `None` if it's out of bounds.
+    #[inline(always)]
+    pub fn get_value(&self, cell: MatrixCell) -> Option<T> {
+        self.slice.get(cell.0).copied()
+    }
+}
+
+impl<'a, T: MatrixItem> Matrix<'a, T> {
+    /// Returns the column of the cell.
+    #[inline]
+    pub fn col_of(&self, cell: MatrixCell) -> usize {
+        cell.0 % self.width
+    }
+
+    /// Returns an iterator over the cells of the same row, starting from the
+    /// given one and moving right.
+    #[inline]
+    pub fn cols(&self, starting_from: MatrixCell) -> Cols {
+        Cols {
+            next: starting_from,
+            remaining: self.width - self.col_of(starting_from),
+        }
+    }
+
+    /// Returns the cell one row down and one column right of the given one.
+    #[inline]
+    pub fn down_right(&self, cell: MatrixCell) -> MatrixCell {
+        MatrixCell(cell.0 + self.width + 1)
+    }
+
+    /// Returns the number of rows of the matrix.
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the cell to the left of the given one.
+    #[inline]
+    pub fn left(&self, cell: MatrixCell) -> MatrixCell {
+        MatrixCell(cell.0 - 1)
+    }
+
+    /// Returns the row of the cell.
+    #[inline]
+    pub fn row_of(&self, cell: MatrixCell) -> usize {
+        cell.0 / self.width
+    }
+
+    /// Returns the cells of the given row.
+    #[inline]
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        let start = row * self.width;
+        let end = start + self.width;
+        &mut self.slice[start..end]
+    }
+
+    #[inline]
+    pub fn rows(&self, starting_from: MatrixCell) -> Rows {
+        Rows {
+            next: starting_from,
+            matrix_width: self.width,
+            remaining: self.height - self.row_of(starting_from),
+        }
+    }
+
+    /// Returns the cell in the first row and the first column.
+    #[inline]
+    pub fn top_left(&self) -> MatrixCell {
+        MatrixCell(0)
+    }
+
+    /// Returns the cells of two different rows at once, where `row_idx_a` has
+    /// to come before `row_idx_b`.
+    #[inline]
+    pub fn two_rows_mut(
+        &mut self,
+        row_idx_a: usize,
+        row_idx_b: usize,
+    ) -> (&mut Row<T>, &mut Row<T>) {
+        debug_assert!(row_idx_a < row_idx_b);
+
+        let start_b = row_idx_b * self.width;
+
+        let (part_a, part_b) = self.slice.split_at_mut(start_b);
+
+        let start_a = row_idx_a * self.width;
+
+        (&mut part_a[start_a..start_a + self.width], &mut part_b[..self.width])
+    }
+
+    #[inline]
+    pub fn up_left(&self, cell: MatrixCell) -> MatrixCell {
+        MatrixCell(cell.0 - self.width - 1)
+    }
+
+    /// Returns the number of columns of the matrix.
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+}
+
+pub(crate) type Row<T> = [T];
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MatrixCell(pub(crate) usize);
+
+impl<T: MatrixItem> Index<MatrixCell> for Matrix<'_, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: MatrixCell) -> &Self::Output {
+        &self.slice[index.0]
+    }
+}
+
+impl<T: MatrixItem> IndexMut<MatrixCell> for Matrix<'_, T> {
+    #[inline]
+    fn index_mut(&mut self, index: MatrixCell) -> &mut Self::Output {
+        &mut self.slice[index.0]
+    }
+}
+
+/// An iterator over the cells of a row, moving right. See [`Matrix::cols`].
+pub(crate) struct Cols {
+    next: MatrixCell,
+    remaining: usize,
+}
+
+impl Iterator for Cols {
+    type Item = MatrixCell;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let this = self.next;
+        self.next.0 += 1;
+        self.remaining -= 1;
+        Some(this)
+    }
+}
+
+/// An iterator over the cells of a column, moving down. See
+/// [`Matrix::rows`].
+pub(crate) struct Rows {
+    next: MatrixCell,
+    matrix_width: usize,
+    remaining: usize,
+}
+
+impl Iterator for Rows {
+    type Item = MatrixCell;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let this = self.next;
+        self.next.0 += self.matrix_width;
+        self.remaining -= 1;
+        Some(this)
+    }
+}
@@ -1,5 +1,7 @@
 use core::ops::Range;
 
+use crate::combinators::*;
+
 /// A trait representing a distance metric on strings.
 ///
 /// This trait encapsulates the logic for comparing a query to a candidate
@@ -127,4 +129,229 @@ pub trait Metric {
         candidate: &str,
         ranges_buf: &mut Vec<Range<usize>>,
     ) -> Option<Self::Distance>;
+
+    /// This method has the same semantics as [`Self::distance`], except that
+    /// it also returns `None` if the distance between the query and the
+    /// candidate is greater than `max_distance`.
+    ///
+    /// The default implementation simply computes the full distance and
+    /// compares it to `max_distance`, but metrics can override it to give up
+    /// as soon as they know that the candidate can't get within the cutoff,
+    /// which is usually a lot faster than computing the exact distance of a
+    /// bad match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfParser, FzfV2};
+    /// # use norm::Metric;
+    /// let mut fzf = FzfV2::new();
+    /// let mut parser = FzfParser::new();
+    ///
+    /// let query = parser.parse("foo");
+    ///
+    /// let cutoff = fzf.distance(query, "f_o_o").unwrap();
+    ///
+    /// assert_eq!(
+    ///     fzf.distance_within(query, "foo_bar", &cutoff),
+    ///     fzf.distance(query, "foo_bar")
+    /// );
+    ///
+    /// // "f__o__o" is a worse match than the cutoff, so it's filtered out.
+    /// assert!(fzf.distance(query, "f__o__o").is_some());
+    /// assert!(fzf.distance_within(query, "f__o__o", &cutoff).is_none());
+    /// ```
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: Self::Query<'_>,
+        candidate: &str,
+        max_distance: &Self::Distance,
+    ) -> Option<Self::Distance> {
+        self.distance(query, candidate)
+            .filter(|distance| distance <= max_distance)
+    }
+
+    /// Creates a metric that ranks the candidates with this metric, using
+    /// `other` to break the ties between candidates with the same distance.
+    ///
+    /// The query of the new metric is a tuple with the queries of the two
+    /// metrics, and its distance is a tuple with their two distances, which
+    /// are compared lexicographically. A candidate is a match only if both
+    /// metrics consider it a match, and the ranges are the ones of this
+    /// metric.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfParser, FzfV2};
+    /// # use norm::levenshtein::Levenshtein;
+    /// # use norm::Metric;
+    /// let mut fzf = FzfV2::new();
+    /// let mut parser = FzfParser::new();
+    ///
+    /// let query = parser.parse("foo");
+    ///
+    /// // The characters after the match don't affect fzf's distance.
+    /// assert_eq!(
+    ///     fzf.distance(query, "foo_bar"),
+    ///     fzf.distance(query, "foo_bar_baz")
+    /// );
+    ///
+    /// let mut metric = fzf.then(Levenshtein::new());
+    ///
+    /// let mut ranges = Vec::new();
+    ///
+    /// let foo_bar = metric
+    ///     .distance_and_ranges((query, "foo"), "foo_bar", &mut ranges)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(ranges, [0..3]);
+    ///
+    /// let foo_bar_baz = metric.distance((query, "foo"), "foo_bar_baz");
+    ///
+    /// // The tie is broken by the Levenshtein distance.
+    /// assert!(foo_bar < foo_bar_baz.unwrap());
+    /// ```
+    #[inline(always)]
+    fn then<M: Metric>(self, other: M) -> Then<Self, M>
+    where
+        Self: Sized,
+    {
+        Then::new(self, other)
+    }
+
+    /// Creates a metric that only matches the candidates for which the given
+    /// predicate returns `true`, ranking them with this metric.
+    ///
+    /// The predicate is called before this metric, so it can also be used to
+    /// cheaply discard candidates that are known not to be relevant.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfParser, FzfV2};
+    /// # use norm::Metric;
+    /// let mut parser = FzfParser::new();
+    ///
+    /// let mut fzf = FzfV2::new().filter(|path| !path.starts_with('.'));
+    ///
+    /// let query = parser.parse("git");
+    ///
+    /// assert!(fzf.distance(query, "src/git.rs").is_some());
+    /// assert!(fzf.distance(query, ".gitignore").is_none());
+    /// ```
+    #[inline(always)]
+    fn filter<F>(self, predicate: F) -> Filter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&str) -> bool,
+    {
+        Filter::new(self, predicate)
+    }
+
+    /// Creates a metric that matches the query against the sub-string of
+    /// every candidate returned by the given function, e.g. the file name of
+    /// a path.
+    ///
+    /// The ranges reported by this metric are moved back by the offset of the
+    /// sub-string, so they're still byte ranges of the original candidate.
+    /// If the function returns a string that is not a sub-slice of the
+    /// candidate, e.g. a string literal, there's no such offset, and the
+    /// distance is returned without any ranges.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfParser, FzfV2};
+    /// # use norm::Metric;
+    /// let mut parser = FzfParser::new();
+    /// let mut ranges = Vec::new();
+    ///
+    /// let mut fzf = FzfV2::new()
+    ///     .map_candidate(|path| path.rsplit('/').next().unwrap_or(path));
+    ///
+    /// let query = parser.parse("main");
+    ///
+    /// let _ = fzf.distance_and_ranges(query, "src/main.rs", &mut ranges);
+    ///
+    /// assert_eq!(ranges, [4..8]);
+    ///
+    /// // Only the file name is matched against the query.
+    /// assert!(fzf.distance(query, "main/lib.rs").is_none());
+    /// ```
+    #[inline(always)]
+    fn map_candidate<F>(self, map: F) -> MapCandidate<Self, F>
+    where
+        Self: Sized,
+        F: for<'c> FnMut(&'c str) -> &'c str,
+    {
+        MapCandidate::new(self, map)
+    }
+
+    /// Creates a metric that matches the same candidates as this metric, but
+    /// ranks them in the opposite order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::Levenshtein;
+    /// # use norm::Metric;
+    /// let mut lev = Levenshtein::new().reversed();
+    ///
+    /// // The most different candidates now come first.
+    /// assert!(lev.distance("abc", "xyz") < lev.distance("abc", "abd"));
+    /// ```
+    #[inline(always)]
+    fn reversed(self) -> Reversed<Self>
+    where
+        Self: Sized,
+    {
+        Reversed::new(self)
+    }
+
+    /// Creates a metric that combines the distance of every candidate with
+    /// an external weight returned by the given function, where higher
+    /// weights are better.
+    ///
+    /// The weight can come from anywhere, e.g. from a lookup keyed by the
+    /// candidate or from a [`Frecency`](crate::Frecency) score. How it's
+    /// combined with the distance can be configured with
+    /// [`Weighted::set_combination`], and by default it's only used to break
+    /// the ties between candidates with the same distance.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::collections::HashMap;
+    /// # use norm::fzf::{FzfParser, FzfV2};
+    /// # use norm::{Frecency, Metric};
+    /// let now = 10_000;
+    ///
+    /// let mut history = HashMap::new();
+    ///
+    /// history.insert("src/lib.rs", Frecency::new(now - 60));
+    ///
+    /// let mut fzf = FzfV2::new().weighted(|path| {
+    ///     history.get(path).map_or(0.0, |frecency| frecency.score(now))
+    /// });
+    ///
+    /// let mut parser = FzfParser::new();
+    ///
+    /// let query = parser.parse("lib");
+    ///
+    /// // The two paths are equally good matches, but "src/lib.rs" was used
+    /// // recently.
+    /// assert!(
+    ///     fzf.distance(query, "src/lib.rs") < fzf.distance(query, "doc/lib.rs")
+    /// );
+    /// ```
+    #[inline(always)]
+    fn weighted<W>(self, weight: W) -> Weighted<Self, W>
+    where
+        Self: Sized,
+        W: FnMut(&str) -> f64,
+    {
+        Weighted::new(self, weight)
+    }
 }
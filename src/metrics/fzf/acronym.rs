@@ -0,0 +1,382 @@
+use core::ops::Range;
+
+use super::*;
+use crate::utils::{self, CharEq};
+use crate::{CaseSensitivity, MatchedRanges, Metric};
+
+/// A metric that matches the query against the initials of the words of the
+/// candidate.
+///
+/// The candidate is split into words at the same boundaries used by fzf's
+/// scoring: a word starts after a whitespace, a delimiter or any other
+/// non-word character, at a camelCase boundary like the `"C"` in
+/// `"getCurrentUser"`, and at the first digit of a number. An uppercase
+/// letter followed by a lowercase one also starts a new word if it follows
+/// another uppercase letter, so `"HTTPServer"` is split into `"HTTP"` and
+/// `"Server"`.
+///
+/// Every character of the query is then matched, in order, either to the
+/// initial of a word or to the character following the previous match in the
+/// same word, so that `"gcu"`, `"getcu"` and `"gcuser"` all match
+/// `"getCurrentUser"`. Words can be skipped, but a word can't be matched once
+/// a later one was. See [`AcronymDistance`] for how candidates are ranked:
+/// queries made only of initials always come first.
+///
+/// The ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// matched characters of every word.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::fzf::Acronym;
+/// # use norm::Metric;
+/// let mut acronym = Acronym::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance = acronym
+///     .distance_and_ranges("hrp", "http_request_parser", &mut ranges)
+///     .unwrap();
+///
+/// assert!(distance.is_acronym());
+///
+/// assert_eq!(ranges, [0..1, 5..6, 13..14]);
+///
+/// // The initials have to be matched in order.
+/// assert!(acronym.distance("gcu", "getCurrentUser").is_some());
+/// assert!(acronym.distance("gcu", "getUserCount").is_none());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "acronym")))]
+#[derive(Clone, Default)]
+pub struct Acronym {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// The scheme whose character classes are used to split the candidate
+    /// into words.
+    scheme: Scheme,
+
+    /// The characters of the last query.
+    query_chars: Vec<char>,
+
+    /// The byte offsets and the characters of the last candidate.
+    candidate_chars: Vec<(usize, char)>,
+
+    /// The char ranges of the words of the last candidate.
+    words: Vec<Range<usize>>,
+
+    /// The dynamic programming table, with a row for every suffix of the
+    /// query and a column for every suffix of the words.
+    table: Vec<Option<AcronymCell>>,
+}
+
+/// A cell of the dynamic programming table of an [`Acronym`].
+#[derive(Clone, Copy)]
+struct AcronymCell {
+    /// The best `(extra_chars, skipped_words)` of the matches of the query
+    /// suffix starting at the row of the cell in the words starting at its
+    /// column.
+    cost: (usize, usize),
+
+    /// How many characters of the word at the column of the cell are matched
+    /// by the best match, or zero if the word is skipped.
+    matched_len: usize,
+}
+
+impl core::fmt::Debug for Acronym {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Acronym")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Acronym {
+    /// Creates a new `Acronym`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::Acronym;
+    /// # use norm::Metric;
+    /// let mut acronym = Acronym::new();
+    ///
+    /// assert!(acronym.distance("ee", "élan_étoile").is_none());
+    ///
+    /// acronym.set_candidate_normalization(true);
+    ///
+    /// assert!(acronym.distance("ee", "élan_étoile").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::Acronym;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut acronym = Acronym::new();
+    ///
+    /// assert!(acronym.distance("gcu", "GetCurrentUser").is_some());
+    ///
+    /// acronym.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert!(acronym.distance("gcu", "GetCurrentUser").is_none());
+    /// assert!(acronym.distance("GCU", "GetCurrentUser").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Computes the best match of the query in the words of the candidate,
+    /// leaving the table filled so that the match can be walked back.
+    #[inline]
+    fn best_match(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<AcronymDistance> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let char_eq =
+            utils::char_eq(is_case_sensitive, self.candidate_normalization);
+
+        self.query_chars.clear();
+        self.query_chars.extend(query.chars());
+
+        self.candidate_chars.clear();
+        self.candidate_chars.extend(candidate.char_indices());
+
+        split_words(&self.candidate_chars, &self.scheme, &mut self.words);
+
+        if self.query_chars.is_empty() {
+            return Some(AcronymDistance::new(0, 0, self.words.len()));
+        }
+
+        fill_table(
+            &self.query_chars,
+            &self.candidate_chars,
+            &self.words,
+            char_eq,
+            &mut self.table,
+        );
+
+        let AcronymCell { cost: (extra_chars, skipped_words), .. } =
+            self.table[0]?;
+
+        // Every word with at least one matched character has an initial in
+        // the query, and the remaining characters are the extra ones.
+        let matched_words = self.query_chars.len() - extra_chars;
+
+        Some(AcronymDistance::new(
+            extra_chars,
+            skipped_words,
+            self.words.len() - matched_words,
+        ))
+    }
+}
+
+impl Metric for Acronym {
+    type Query<'a> = &'a str;
+
+    type Distance = AcronymDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.best_match(query, candidate)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let distance = self.best_match(query, candidate)?;
+
+        let mut ranges = MatchedRanges::from(ranges);
+
+        let width = self.words.len() + 1;
+
+        let mut row = 0;
+
+        let mut col = 0;
+
+        while row < self.query_chars.len() {
+            let cell =
+                self.table[row * width + col].expect("cell is reachable");
+
+            if cell.matched_len > 0 {
+                let word_start = self.words[col].start;
+
+                let start = self.candidate_chars[word_start].0;
+
+                let end = self
+                    .candidate_chars
+                    .get(word_start + cell.matched_len)
+                    .map_or(candidate.len(), |&(offset, _)| offset);
+
+                ranges.insert(start..end);
+
+                row += cell.matched_len;
+            }
+
+            col += 1;
+        }
+
+        Some(distance)
+    }
+}
+
+/// Fills `words` with the char ranges of the words of the candidate.
+#[inline]
+fn split_words(
+    candidate: &[(usize, char)],
+    scheme: &Scheme,
+    words: &mut Vec<Range<usize>>,
+) {
+    use CharClass::*;
+
+    words.clear();
+
+    let mut prev_class = scheme.initial_char_class;
+
+    let mut word_start = None;
+
+    for (idx, &(_, ch)) in candidate.iter().enumerate() {
+        let class = char_class(ch, scheme);
+
+        let is_word_char = matches!(class, Lower | Upper | Letter | Number);
+
+        let is_boundary = match (prev_class, class) {
+            (_, WhiteSpace | NonWord | Delimiter) => true,
+            (WhiteSpace | NonWord | Delimiter, _) => true,
+            (Lower, Upper) => true,
+            (prev, Number) => prev != Number,
+            (Upper, Upper) => candidate
+                .get(idx + 1)
+                .is_some_and(|&(_, next)| char_class(next, scheme) == Lower),
+            _ => false,
+        };
+
+        if is_boundary {
+            if let Some(start) = word_start.take() {
+                words.push(start..idx);
+            }
+        }
+
+        if is_word_char && word_start.is_none() {
+            word_start = Some(idx);
+        }
+
+        prev_class = class;
+    }
+
+    if let Some(start) = word_start {
+        words.push(start..candidate.len());
+    }
+}
+
+/// Fills the dynamic programming table, where the cell at row `q` and column
+/// `w` holds the best way to match the query starting at its `q`-th
+/// character in the candidate starting at its `w`-th word, or `None` if
+/// there's no way to do it.
+#[inline]
+fn fill_table(
+    query: &[char],
+    candidate: &[(usize, char)],
+    words: &[Range<usize>],
+    char_eq: CharEq,
+    table: &mut Vec<Option<AcronymCell>>,
+) {
+    let height = query.len() + 1;
+
+    let width = words.len() + 1;
+
+    table.clear();
+    table.resize(height * width, None);
+
+    // Once the whole query is matched the remaining words are ignored.
+    for col in 0..width {
+        table[query.len() * width + col] =
+            Some(AcronymCell { cost: (0, 0), matched_len: 0 });
+    }
+
+    for row in (0..query.len()).rev() {
+        for (col, word) in words.iter().enumerate().rev() {
+            let mut best: Option<AcronymCell> = None;
+
+            let max_len = word.len().min(query.len() - row);
+
+            for len in 1..=max_len {
+                let query_ch = query[row + len - 1];
+
+                let (_, candidate_ch) = candidate[word.start + len - 1];
+
+                if !char_eq(query_ch, candidate_ch) {
+                    break;
+                }
+
+                let Some(next) = table[(row + len) * width + col + 1] else {
+                    continue;
+                };
+
+                let cost = (next.cost.0 + len - 1, next.cost.1);
+
+                if best.is_none_or(|best| cost < best.cost) {
+                    best = Some(AcronymCell { cost, matched_len: len });
+                }
+            }
+
+            // Skipping the word is only worth it if it's strictly better, so
+            // that earlier words are preferred on ties.
+            if let Some(next) = table[row * width + col + 1] {
+                let cost = (next.cost.0, next.cost.1 + 1);
+
+                if best.is_none_or(|best| cost < best.cost) {
+                    best = Some(AcronymCell { cost, matched_len: 0 });
+                }
+            }
+
+            table[row * width + col] = best;
+        }
+    }
+}
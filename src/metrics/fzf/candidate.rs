@@ -1,7 +1,10 @@
+#[cfg(feature = "fzf-v2")]
 use super::*;
 use crate::utils::*;
+#[cfg(feature = "fzf-v2")]
 use crate::{Candidate, CandidateMatches};
 
+#[cfg(feature = "fzf-v2")]
 /// TODO: docs
 pub(super) struct CandidateV2<'a> {
     /// TODO: docs
@@ -44,6 +47,7 @@ impl CandidateOpts {
     }
 }
 
+#[cfg(feature = "fzf-v2")]
 impl<'a> CandidateV2<'a> {
     #[inline(always)]
     pub fn bonus_at(&mut self, char_idx: usize, scheme: &Scheme) -> Score {
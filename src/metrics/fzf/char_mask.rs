@@ -0,0 +1,161 @@
+use crate::normalize::normalize;
+use crate::Candidate;
+
+/// A bitmask recording which ASCII letters and digits appear in a string.
+///
+/// Letters are case-folded, so `'a'` and `'A'` set the same bit. All the other
+/// characters are ignored.
+///
+/// Since a pattern can't match a candidate that's missing one of its letters
+/// or digits, comparing the mask of the pattern with the mask of the candidate
+/// lets us reject most non-matches without running the actual matching
+/// algorithm.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) struct CharMask(u64);
+
+impl CharMask {
+    /// Returns `true` if every bit set in `other` is also set in `self`.
+    #[inline(always)]
+    pub fn contains(self, other: Self) -> bool {
+        other.0 & !self.0 == 0
+    }
+
+    /// Returns the number of bits set in `other` that are not set in `self`.
+    #[inline(always)]
+    pub fn missing(self, other: Self) -> usize {
+        (other.0 & !self.0).count_ones() as usize
+    }
+
+    /// Creates the mask of a candidate.
+    ///
+    /// If `normalize_candidate` is `true` the non-ASCII characters of the
+    /// candidate are normalized before being added to the mask, so that
+    /// candidates like `"ƒöö"` still pass the check for patterns like
+    /// `"foo"`.
+    #[inline]
+    pub fn from_candidate(
+        candidate: Candidate,
+        normalize_candidate: bool,
+    ) -> Self {
+        match candidate {
+            Candidate::Ascii(bytes) => Self::from_ascii(bytes),
+
+            Candidate::Unicode(chars) => {
+                let mut mask = Self::default();
+
+                for &ch in chars {
+                    mask.insert(ch);
+
+                    if normalize_candidate && !ch.is_ascii() {
+                        mask.insert(normalize(ch));
+                    }
+                }
+
+                mask
+            },
+
+            Candidate::Utf8(str) => {
+                // The bytes of multi-byte chars are never ASCII, so they
+                // don't set any bit.
+                let mut mask = Self::from_ascii(str.as_bytes());
+
+                if normalize_candidate {
+                    for ch in str.chars().filter(|ch| !ch.is_ascii()) {
+                        mask.insert(normalize(ch));
+                    }
+                }
+
+                mask
+            },
+        }
+    }
+
+    /// Creates the mask of a pattern.
+    #[inline]
+    pub fn from_chars(chars: &[char]) -> Self {
+        let mut mask = Self::default();
+
+        for &ch in chars {
+            mask.insert(ch);
+        }
+
+        mask
+    }
+
+    #[inline(always)]
+    fn from_ascii(bytes: &[u8]) -> Self {
+        let mut bits = 0;
+
+        for &byte in bytes {
+            bits |= bit(byte);
+        }
+
+        Self(bits)
+    }
+
+    #[inline(always)]
+    fn insert(&mut self, ch: char) {
+        if ch.is_ascii() {
+            self.0 |= bit(ch as u8);
+        }
+    }
+}
+
+/// Returns the bit of an ASCII letter or digit, or 0 for any other byte.
+#[inline(always)]
+fn bit(byte: u8) -> u64 {
+    match byte {
+        b'a'..=b'z' => 1 << (byte - b'a'),
+        b'A'..=b'Z' => 1 << (byte - b'A'),
+        b'0'..=b'9' => 1 << (26 + byte - b'0'),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> CharMask {
+        CharMask::from_chars(&s.chars().collect::<Vec<_>>())
+    }
+
+    fn ascii(s: &str) -> CharMask {
+        CharMask::from_candidate(Candidate::Ascii(s.as_bytes()), false)
+    }
+
+    #[test]
+    fn char_mask_case_folded() {
+        assert_eq!(chars("abc"), chars("CBA"));
+        assert!(ascii("Foo Bar").contains(chars("fb")));
+        assert!(ascii("foo bar").contains(chars("FB")));
+    }
+
+    #[test]
+    fn char_mask_ignores_non_alphanumeric() {
+        assert_eq!(chars("a-b _/1"), chars("ab1"));
+        assert!(ascii("ab1").contains(chars("!a b$")));
+        assert!(ascii("").contains(chars("  ")));
+    }
+
+    #[test]
+    fn char_mask_missing_char() {
+        assert!(!ascii("foo bar").contains(chars("baz")));
+        assert!(!ascii("abc").contains(chars("abc1")));
+    }
+
+    #[test]
+    fn char_mask_normalized_candidate() {
+        let candidate = "ƒöö".chars().collect::<Vec<_>>();
+
+        let candidate = Candidate::Unicode(&candidate);
+
+        assert!(
+            !CharMask::from_candidate(candidate, false).contains(chars("foo"))
+        );
+
+        assert!(
+            CharMask::from_candidate(candidate, true).contains(chars("foo"))
+        );
+    }
+}
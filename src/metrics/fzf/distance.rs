@@ -36,6 +36,27 @@ impl FzfDistance {
         Self(score)
     }
 
+    /// Returns the score wrapped by the distance.
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[inline(always)]
+    pub(super) fn score(self) -> Score {
+        self.0
+    }
+
+    /// Divides the score by the given maximum score, clamping the result
+    /// between `0.0` and `1.0`.
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[inline(always)]
+    pub(super) fn normalize(self, max_score: Score) -> f64 {
+        // Only queries made of inverse patterns have a maximum score of zero,
+        // and every candidate they match is a perfect match.
+        if max_score <= 0 {
+            return 1.0;
+        }
+
+        (self.0 as f64 / max_score as f64).clamp(0.0, 1.0)
+    }
+
     /// Returns a score representation of the distance.
     ///
     /// This is not part of the public API and should not be relied upon.
@@ -47,3 +68,96 @@ impl FzfDistance {
         self.0
     }
 }
+
+impl crate::combinators::Relevance for FzfDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        self.0 as f64
+    }
+}
+
+/// The acronym distance type.
+///
+/// This struct is returned by [`Acronym`](super::Acronym)'s
+/// [`Metric`](crate::Metric) implementation. Distances are ordered by the
+/// number of [extra characters](Self::extra_chars) first, so candidates whose
+/// initials spell out the whole query always come before the other ones, then
+/// by the number of [skipped words](Self::skipped_words), and finally by the
+/// number of [unmatched words](Self::unmatched_words).
+#[cfg(feature = "acronym")]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct AcronymDistance {
+    /// The query characters matched somewhere other than a word's initial.
+    extra_chars: usize,
+
+    /// The words without matches before the last matched word.
+    skipped_words: usize,
+
+    /// All the words without matches, including the trailing ones.
+    unmatched_words: usize,
+}
+
+#[cfg(feature = "acronym")]
+impl crate::combinators::Relevance for AcronymDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        // `x / (1 + x)` maps a count to `[0, 1)`, so adding it to the previous
+        // count only breaks its ties. The resulting key grows with the
+        // distance, and the relevance goes from 1.0 for a perfect acronym
+        // towards 0.0.
+        let fraction = |count: f64| count / (1.0 + count);
+
+        let key = self.extra_chars as f64
+            + fraction(
+                self.skipped_words as f64
+                    + fraction(self.unmatched_words as f64),
+            );
+
+        1.0 / (1.0 + key)
+    }
+}
+
+#[cfg(feature = "acronym")]
+impl AcronymDistance {
+    /// Creates a new [`AcronymDistance`].
+    #[inline(always)]
+    pub(super) fn new(
+        extra_chars: usize,
+        skipped_words: usize,
+        unmatched_words: usize,
+    ) -> Self {
+        Self { extra_chars, skipped_words, unmatched_words }
+    }
+
+    /// Returns the number of characters of the query that were matched to a
+    /// character of the candidate other than the initial of a word.
+    #[inline(always)]
+    pub fn extra_chars(self) -> usize {
+        self.extra_chars
+    }
+
+    /// Returns `true` if every character of the query was matched to the
+    /// initial of a word, i.e. if the query is an acronym of (part of) the
+    /// candidate.
+    #[inline(always)]
+    pub fn is_acronym(self) -> bool {
+        self.extra_chars == 0
+    }
+
+    /// Returns the number of words of the candidate that come before the last
+    /// matched word without having any of their characters matched.
+    #[inline(always)]
+    pub fn skipped_words(self) -> usize {
+        self.skipped_words
+    }
+
+    /// Returns the number of words of the candidate that don't have any of
+    /// their characters matched, including the ones after the last matched
+    /// word.
+    #[inline(always)]
+    pub fn unmatched_words(self) -> usize {
+        self.unmatched_words
+    }
+}
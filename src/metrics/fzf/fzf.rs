@@ -9,12 +9,24 @@ pub(super) trait Fzf {
     /// TODO: docs
     fn alloc_chars<'a>(&mut self, candidate: &str) -> &'a [char];
 
-    /// TODO: docs
-    fn char_eq(&self, pattern: Pattern) -> CharEq;
+    /// Whether the candidate's characters should be normalized before being
+    /// compared to the query.
+    fn candidate_normalization(&self) -> bool;
+
+    /// Returns the options used to compare the characters of the given
+    /// pattern to the ones of the candidate.
+    fn candidate_opts(&self, pattern: Pattern) -> CandidateOpts;
 
     /// TODO: docs
     fn scheme(&self) -> &Scheme;
 
+    /// Returns the maximum number of typos the given pattern can contain and
+    /// still match a candidate.
+    #[inline(always)]
+    fn typo_budget(&self, _pattern: Pattern) -> usize {
+        0
+    }
+
     /// TODO: docs
     fn fuzzy<const RANGES: bool>(
         &mut self,
@@ -40,13 +52,13 @@ pub(super) trait Fzf {
             },
 
             MatchType::Exact => {
-                let char_eq = self.char_eq(pattern);
+                let opts = self.candidate_opts(pattern);
 
                 if pattern.is_inverse {
                     exact_match::<false>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -54,7 +66,7 @@ pub(super) trait Fzf {
                     exact_match::<RANGES>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -62,13 +74,13 @@ pub(super) trait Fzf {
             },
 
             MatchType::PrefixExact => {
-                let char_eq = self.char_eq(pattern);
+                let opts = self.candidate_opts(pattern);
 
                 if pattern.is_inverse {
                     prefix_match::<false>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -76,7 +88,7 @@ pub(super) trait Fzf {
                     prefix_match::<RANGES>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -84,13 +96,13 @@ pub(super) trait Fzf {
             },
 
             MatchType::SuffixExact => {
-                let char_eq = self.char_eq(pattern);
+                let opts = self.candidate_opts(pattern);
 
                 if pattern.is_inverse {
                     suffix_match::<false>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -98,7 +110,7 @@ pub(super) trait Fzf {
                     suffix_match::<RANGES>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -106,13 +118,13 @@ pub(super) trait Fzf {
             },
 
             MatchType::EqualExact => {
-                let char_eq = self.char_eq(pattern);
+                let opts = self.candidate_opts(pattern);
 
                 if pattern.is_inverse {
                     equal_match::<false>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -120,7 +132,7 @@ pub(super) trait Fzf {
                     equal_match::<RANGES>(
                         pattern,
                         candidate,
-                        char_eq,
+                        opts,
                         self.scheme(),
                         ranges,
                     )
@@ -135,6 +147,61 @@ pub(super) trait Fzf {
         }
     }
 
+    /// Sets the lowest score a candidate has to reach for [`Self::fuzzy`] to
+    /// return it, or `None` to disable the cutoff.
+    ///
+    /// Metrics can use this to give up on a candidate as soon as they know
+    /// that it can't reach the cutoff.
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[inline(always)]
+    fn set_min_score(&mut self, _min_score: Option<Score>) {}
+
+    /// Maps the distance of a candidate to a similarity between `0.0` and
+    /// `1.0`, relative to the highest score a candidate can get for the
+    /// query.
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[inline]
+    fn similarity(&self, query: FzfQuery, distance: FzfDistance) -> f64 {
+        distance.normalize(max_query_score(query, self.scheme()))
+    }
+
+    /// Same as [`Self::distance`], except that it returns `None` for the
+    /// candidates whose distance is greater than `max_distance`.
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: FzfQuery,
+        candidate: &str,
+        max_distance: FzfDistance,
+    ) -> Option<FzfDistance> {
+        let min_score = max_distance.score();
+
+        // No candidate can get a higher score than this, so there's no need
+        // to look at the candidate at all.
+        if max_query_score(query, self.scheme()) < min_score {
+            return None;
+        }
+
+        // The score of an extended query is the sum of the scores of its
+        // conditions, so we can only prune a pattern if it's the only one.
+        let is_single_pattern =
+            matches!(query.search_mode, SearchMode::NotExtended(_));
+
+        if is_single_pattern {
+            self.set_min_score(Some(min_score));
+        }
+
+        let distance =
+            self.distance::<false>(query, candidate, &mut Vec::new());
+
+        if is_single_pattern {
+            self.set_min_score(None);
+        }
+
+        distance.filter(|&distance| distance <= max_distance)
+    }
+
     /// TODO: docs
     #[inline(always)]
     fn distance<const RANGES: bool>(
@@ -149,23 +216,58 @@ pub(super) trait Fzf {
 
         let candidate = if candidate.is_ascii() {
             Candidate::Ascii(candidate.as_bytes())
+        } else if matches!(query.search_mode, SearchMode::NotExtended(_))
+            && !self.candidate_normalization()
+        {
+            // A non-extended query is a single fuzzy pattern, so we let the
+            // metric decide how much of the candidate it needs to decode.
+            Candidate::Utf8(candidate)
         } else {
             Candidate::Unicode(self.alloc_chars(candidate))
         };
 
         let ranges = &mut ranges.into();
 
+        let candidate_mask = CharMask::from_candidate(
+            candidate,
+            self.candidate_normalization(),
+        );
+
         match query.search_mode {
-            SearchMode::NotExtended(pattern) => self
-                .fuzzy::<RANGES>(pattern, candidate, ranges)
-                .map(FzfDistance::from_score),
+            SearchMode::NotExtended(pattern) => {
+                if !candidate_mask.contains(pattern.char_mask)
+                    && candidate_mask.missing(pattern.char_mask)
+                        > self.typo_budget(pattern)
+                {
+                    return None;
+                }
+
+                self.fuzzy::<RANGES>(pattern, candidate, ranges)
+                    .map(FzfDistance::from_score)
+            },
 
-            SearchMode::Extended(conditions) => {
+            SearchMode::Extended(_) => {
                 let mut total_score: Score = 0;
-                for condition in conditions {
-                    total_score += condition.iter().find_map(|pattern| {
+                for condition in query.evaluation_order {
+                    let Some(score) = condition.iter().find_map(|pattern| {
+                        // If the candidate is missing more of the pattern's
+                        // characters than the pattern's typos can make up
+                        // for the pattern can't match, so we can skip it (or
+                        // accept it right away if it's inverse).
+                        if !candidate_mask.contains(pattern.char_mask)
+                            && candidate_mask.missing(pattern.char_mask)
+                                > self.typo_budget(pattern)
+                        {
+                            return pattern.is_inverse.then_some(0);
+                        }
                         self.score::<RANGES>(pattern, candidate, ranges)
-                    })?;
+                    }) else {
+                        // Don't leave the ranges of the conditions that
+                        // matched in the buffer.
+                        ranges.clear();
+                        return None;
+                    };
+                    total_score += score;
                 }
                 Some(FzfDistance::from_score(total_score))
             },
@@ -173,12 +275,66 @@ pub(super) trait Fzf {
     }
 }
 
+/// Returns the highest score a candidate can get for the given query.
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+#[inline]
+fn max_query_score(query: FzfQuery, scheme: &Scheme) -> Score {
+    match query.search_mode {
+        SearchMode::NotExtended(pattern) => max_pattern_score(pattern, scheme),
+
+        SearchMode::Extended(conditions) => conditions
+            .iter()
+            .map(|condition| {
+                condition
+                    .iter()
+                    .map(|pattern| max_pattern_score(pattern, scheme))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum(),
+    }
+}
+
+/// Returns the highest score a candidate can get by matching the given
+/// pattern, i.e. the one of a candidate where every character of the pattern
+/// gets the highest bonus of the scheme.
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+#[inline]
+pub(super) fn max_pattern_score(pattern: Pattern, scheme: &Scheme) -> Score {
+    // Inverse patterns never add to the score.
+    if pattern.is_inverse {
+        return 0;
+    }
+
+    let max_bonus = max_bonus(scheme);
+
+    let char_len = pattern.char_len() as Score;
+
+    // The bonus of the first character is multiplied, and all the following
+    // ones can at most get the same bonus as part of a consecutive chunk.
+    char_len * bonus::MATCH
+        + max_bonus * (char_len - 1 + bonus::FIRST_QUERY_CHAR_MULTIPLIER)
+}
+
+/// Returns the highest bonus a single character can get with the given
+/// scheme, not counting the multiplier of the first character of a pattern.
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+#[inline(always)]
+pub(super) fn max_bonus(scheme: &Scheme) -> Score {
+    scheme
+        .bonus_boundary_white
+        .max(scheme.bonus_boundary_delimiter)
+        .max(bonus::BOUNDARY)
+        .max(bonus::NON_WORD)
+        .max(bonus::CONSECUTIVE)
+}
+
 /// TODO: docs
 #[inline]
 fn exact_match<const RANGES: bool>(
     pattern: Pattern,
     candidate: Candidate,
-    char_eq: CharEq,
+    opts: CandidateOpts,
     scheme: &Scheme,
     ranges: &mut MatchedRanges,
 ) -> Option<Score> {
@@ -186,6 +342,46 @@ fn exact_match<const RANGES: bool>(
         return Some(0);
     }
 
+    let matched_range = match candidate {
+        Candidate::Ascii(haystack)
+            if pattern.char_len() <= MAX_MEMMEM_NEEDLE_LEN =>
+        {
+            exact_match_ascii(
+                pattern,
+                haystack,
+                opts.is_case_sensitive,
+                scheme,
+            )
+        },
+
+        _ => exact_match_naive(pattern, candidate, opts.char_eq, scheme),
+    }?;
+
+    let score = compute_score::<false>(
+        pattern,
+        candidate,
+        matched_range.clone(),
+        opts.char_eq,
+        scheme,
+        ranges,
+    );
+
+    if RANGES {
+        ranges.insert(candidate.to_byte_range(matched_range));
+    }
+
+    Some(score)
+}
+
+/// Returns the char range of the occurrence of the pattern in the candidate
+/// with the highest bonus, preferring the leftmost one in case of ties.
+#[inline]
+fn exact_match_naive(
+    pattern: Pattern,
+    candidate: Candidate,
+    char_eq: CharEq,
+    scheme: &Scheme,
+) -> Option<Range<usize>> {
     // TODO: docs
     let mut best_bonus: i64 = -1;
 
@@ -255,26 +451,193 @@ fn exact_match<const RANGES: bool>(
         }
     }
 
-    if !matched {
-        return None;
+    matched.then_some(best_bonus_char_start..best_bonus_char_end)
+}
+
+/// Same as [`exact_match_naive`], but specialized for ASCII candidates.
+///
+/// Instead of restarting a char-by-char comparison from every position of
+/// the candidate, this uses a substring search to jump straight to the next
+/// occurrence of the pattern, and only computes the bonus of the occurrences
+/// it finds.
+#[inline]
+fn exact_match_ascii(
+    pattern: Pattern,
+    haystack: &[u8],
+    is_case_sensitive: bool,
+    scheme: &Scheme,
+) -> Option<Range<usize>> {
+    let mut buf = [0; MAX_MEMMEM_NEEDLE_LEN];
+
+    let needle = ascii_needle(pattern, &mut buf)?;
+
+    // Case-insensitive searches can still be case-sensitive if the needle
+    // doesn't contain any letters.
+    let is_case_sensitive =
+        is_case_sensitive || !needle.iter().any(u8::is_ascii_alphabetic);
+
+    let finder =
+        is_case_sensitive.then(|| memchr::memmem::Finder::new(needle));
+
+    let mut best_bonus: Score = -1;
+
+    let mut best_start = 0;
+
+    let mut search_start = 0;
+
+    loop {
+        let rest = &haystack[search_start..];
+
+        let offset = match &finder {
+            Some(finder) => finder.find(rest),
+            None => find_ascii_case_insensitive(needle, rest),
+        };
+
+        let Some(offset) = offset else { break };
+
+        let start = search_start + offset;
+
+        let prev_class =
+            restart_prev_class(needle, haystack, start, is_case_sensitive)
+                .map_or(scheme.initial_char_class, |byte| {
+                    char_class(byte as char, scheme)
+                });
+
+        let this_class = char_class(haystack[start] as char, scheme);
+
+        let bonus = compute_bonus(prev_class, this_class, scheme);
+
+        if bonus > best_bonus {
+            best_bonus = bonus;
+            best_start = start;
+        }
+
+        if bonus >= bonus::BOUNDARY {
+            break;
+        }
+
+        // Occurrences can overlap, so we start looking for the next one right
+        // after the start of this one.
+        search_start = start + 1;
     }
 
-    let matched_range = best_bonus_char_start..best_bonus_char_end;
+    (best_bonus >= 0).then_some(best_start..best_start + needle.len())
+}
 
-    let score = compute_score::<false>(
-        pattern,
-        candidate,
-        matched_range.clone(),
-        char_eq,
-        scheme,
-        ranges,
-    );
+/// Returns the byte whose class [`exact_match_naive`] uses as the previous
+/// class when computing the bonus of the occurrence of the needle starting at
+/// `start`, or `None` if it uses the scheme's initial class.
+///
+/// This is usually the byte right before the occurrence, except when the
+/// naive search restarts from `start` after a partial or complete match
+/// starting at `start - 1`. In that case the previous class is the one of the
+/// last byte it compared before bailing out of that match, or, for
+/// single-byte needles, the one of the byte before the run of matching bytes.
+#[inline(always)]
+fn restart_prev_class(
+    needle: &[u8],
+    haystack: &[u8],
+    start: usize,
+    is_case_sensitive: bool,
+) -> Option<u8> {
+    let eq = |needle_byte: u8, byte: u8| {
+        if is_case_sensitive {
+            needle_byte == byte
+        } else {
+            needle_byte.eq_ignore_ascii_case(&byte)
+        }
+    };
 
-    if RANGES {
-        ranges.insert(candidate.to_byte_range(matched_range));
+    let prev_start = start.checked_sub(1)?;
+
+    if !eq(needle[0], haystack[prev_start]) {
+        return Some(haystack[prev_start]);
     }
 
-    Some(score)
+    if needle.len() == 1 {
+        let run_start = haystack[..start]
+            .iter()
+            .rposition(|&byte| !eq(needle[0], byte))
+            .map_or(0, |idx| idx + 1);
+
+        return run_start.checked_sub(1).map(|idx| haystack[idx]);
+    }
+
+    // Since there's an occurrence starting at `start`, the comparison of the
+    // match starting at `start - 1` can't run past the end of the haystack.
+    let matched = needle[1..]
+        .iter()
+        .zip(&haystack[start..])
+        .take_while(|&(&needle_byte, &byte)| eq(needle_byte, byte))
+        .count();
+
+    let bail_out = prev_start + (matched + 1).min(needle.len() - 1);
+
+    Some(haystack[bail_out - 1])
+}
+
+/// The maximum length of a pattern that can be searched for in an ASCII
+/// candidate by [`exact_match_ascii`]. Longer patterns fall back to
+/// [`exact_match_naive`].
+const MAX_MEMMEM_NEEDLE_LEN: usize = 64;
+
+/// Copies the pattern in the given buffer, returning `None` if the pattern
+/// contains non-ASCII characters, in which case it can't be found in an ASCII
+/// candidate.
+#[inline(always)]
+fn ascii_needle<'buf>(
+    pattern: Pattern,
+    buf: &'buf mut [u8; MAX_MEMMEM_NEEDLE_LEN],
+) -> Option<&'buf [u8]> {
+    debug_assert!(pattern.char_len() <= buf.len());
+
+    for (byte, ch) in buf.iter_mut().zip(pattern.chars()) {
+        if !ch.is_ascii() {
+            return None;
+        }
+        *byte = ch as u8;
+    }
+
+    Some(&buf[..pattern.char_len()])
+}
+
+/// Returns the byte offset of the first occurrence of the needle in the
+/// haystack, ignoring the case of ASCII letters.
+#[inline(always)]
+fn find_ascii_case_insensitive(
+    needle: &[u8],
+    haystack: &[u8],
+) -> Option<usize> {
+    let first = needle[0];
+
+    let mut candidates = if first.is_ascii_alphabetic() {
+        memchr::memchr2_iter(
+            first.to_ascii_lowercase(),
+            first.to_ascii_uppercase(),
+            haystack,
+        )
+    } else {
+        memchr::memchr2_iter(first, first, haystack)
+    };
+
+    candidates.find(|&start| {
+        haystack
+            .get(start..start + needle.len())
+            .is_some_and(|window| window.eq_ignore_ascii_case(needle))
+    })
+}
+
+/// Returns `true` if the pattern is equal to the given ASCII bytes.
+#[inline(always)]
+fn ascii_eq(pattern: Pattern, bytes: &[u8], is_case_sensitive: bool) -> bool {
+    pattern.char_len() == bytes.len()
+        && pattern.chars().zip(bytes).all(|(pattern_ch, &byte)| {
+            if is_case_sensitive {
+                pattern_ch == byte as char
+            } else {
+                pattern_ch.eq_ignore_ascii_case(&(byte as char))
+            }
+        })
 }
 
 /// TODO: docs
@@ -282,7 +645,7 @@ fn exact_match<const RANGES: bool>(
 fn prefix_match<const RANGES: bool>(
     pattern: Pattern,
     candidate: Candidate,
-    char_eq: CharEq,
+    opts: CandidateOpts,
     scheme: &Scheme,
     ranges: &mut MatchedRanges,
 ) -> Option<Score> {
@@ -290,35 +653,43 @@ fn prefix_match<const RANGES: bool>(
         return Some(0);
     }
 
-    let mut pattern_chars = pattern.chars();
-
     let ignored_leading_spaces =
         ignored_candidate_leading_spaces(pattern, candidate)?;
 
-    for (candidate_ch, pattern_ch) in candidate
-        .chars_from(ignored_leading_spaces)
-        .zip(pattern_chars.by_ref())
-    {
-        if !char_eq(pattern_ch, candidate_ch) {
-            return None;
-        }
-    }
-
-    if pattern_chars.next().is_some() {
-        return None;
-    }
-
     let matched_range = {
         let start = ignored_leading_spaces;
         let end = start + pattern.char_len();
         start..end
     };
 
+    if let Candidate::Ascii(haystack) = candidate {
+        let bytes = haystack.get(matched_range.clone())?;
+
+        if !ascii_eq(pattern, bytes, opts.is_case_sensitive) {
+            return None;
+        }
+    } else {
+        let mut pattern_chars = pattern.chars();
+
+        for (candidate_ch, pattern_ch) in candidate
+            .chars_from(ignored_leading_spaces)
+            .zip(pattern_chars.by_ref())
+        {
+            if !(opts.char_eq)(pattern_ch, candidate_ch) {
+                return None;
+            }
+        }
+
+        if pattern_chars.next().is_some() {
+            return None;
+        }
+    }
+
     let score = compute_score::<false>(
         pattern,
         candidate,
         matched_range.clone(),
-        char_eq,
+        opts.char_eq,
         scheme,
         ranges,
     );
@@ -335,7 +706,7 @@ fn prefix_match<const RANGES: bool>(
 fn suffix_match<const RANGES: bool>(
     pattern: Pattern,
     candidate: Candidate,
-    char_eq: CharEq,
+    opts: CandidateOpts,
     scheme: &Scheme,
     ranges: &mut MatchedRanges,
 ) -> Option<Score> {
@@ -343,37 +714,45 @@ fn suffix_match<const RANGES: bool>(
         return Some(0);
     }
 
-    let mut pattern_chars = pattern.chars().rev();
-
     let chars_up_to_ignored_spaces = candidate.char_len()
         - ignored_candidate_trailing_spaces(pattern, candidate)?;
 
-    for (candidate_ch, pattern_ch) in candidate
-        .slice(0..chars_up_to_ignored_spaces)
-        .chars()
-        .rev()
-        .zip(pattern_chars.by_ref())
-    {
-        if !char_eq(pattern_ch, candidate_ch) {
-            return None;
-        }
-    }
-
-    if pattern_chars.next().is_some() {
-        return None;
-    }
-
     let matched_range = {
         let end = chars_up_to_ignored_spaces;
-        let start = end - pattern.char_len();
+        let start = end.checked_sub(pattern.char_len())?;
         start..end
     };
 
+    if let Candidate::Ascii(haystack) = candidate {
+        let bytes = &haystack[matched_range.clone()];
+
+        if !ascii_eq(pattern, bytes, opts.is_case_sensitive) {
+            return None;
+        }
+    } else {
+        let mut pattern_chars = pattern.chars().rev();
+
+        for (candidate_ch, pattern_ch) in candidate
+            .slice(0..chars_up_to_ignored_spaces)
+            .chars()
+            .rev()
+            .zip(pattern_chars.by_ref())
+        {
+            if !(opts.char_eq)(pattern_ch, candidate_ch) {
+                return None;
+            }
+        }
+
+        if pattern_chars.next().is_some() {
+            return None;
+        }
+    }
+
     let score = compute_score::<false>(
         pattern,
         candidate,
         matched_range.clone(),
-        char_eq,
+        opts.char_eq,
         scheme,
         ranges,
     );
@@ -390,7 +769,7 @@ fn suffix_match<const RANGES: bool>(
 fn equal_match<const RANGES: bool>(
     pattern: Pattern,
     candidate: Candidate,
-    char_eq: CharEq,
+    opts: CandidateOpts,
     scheme: &Scheme,
     ranges: &mut MatchedRanges,
 ) -> Option<Score> {
@@ -416,28 +795,36 @@ fn equal_match<const RANGES: bool>(
         return None;
     }
 
-    let mut pattern_chars = pattern.chars();
+    if let Candidate::Ascii(haystack) = candidate {
+        let bytes = &haystack[matched_char_range.clone()];
 
-    let mut candidate_chars =
-        candidate.slice(matched_char_range.clone()).chars();
-
-    for (pattern_ch, candidate_ch) in
-        pattern_chars.by_ref().zip(candidate_chars.by_ref())
-    {
-        if !char_eq(pattern_ch, candidate_ch) {
+        if !ascii_eq(pattern, bytes, opts.is_case_sensitive) {
             return None;
         }
-    }
+    } else {
+        let mut pattern_chars = pattern.chars();
 
-    if pattern_chars.next().is_some() || candidate_chars.next().is_some() {
-        return None;
+        let mut candidate_chars =
+            candidate.slice(matched_char_range.clone()).chars();
+
+        for (pattern_ch, candidate_ch) in
+            pattern_chars.by_ref().zip(candidate_chars.by_ref())
+        {
+            if !(opts.char_eq)(pattern_ch, candidate_ch) {
+                return None;
+            }
+        }
+
+        if pattern_chars.next().is_some() || candidate_chars.next().is_some() {
+            return None;
+        }
     }
 
     let score = compute_score::<false>(
         pattern,
         candidate,
         matched_char_range.clone(),
-        char_eq,
+        opts.char_eq,
         scheme,
         ranges,
     );
@@ -479,6 +866,46 @@ fn ignored_candidate_trailing_spaces(
     }
 }
 
+/// Finds the first occurrence of the pattern as a subsequence of the
+/// candidate, returning the char offsets of its characters together with the
+/// end of the window that can contain the best alignment, i.e. the offset
+/// right after the last occurrence of the pattern's last character.
+///
+/// Returns `None` if the pattern is not a subsequence of the candidate.
+#[cfg(any(feature = "fzf-v2", feature = "skim"))]
+#[inline]
+pub(super) fn matches<'idx>(
+    indices_slab: &'idx mut MatchedIndicesSlab,
+    pattern: Pattern,
+    candidate: Candidate,
+    opts: CandidateOpts,
+) -> Option<(&'idx mut [usize], usize)> {
+    let match_offsets = indices_slab.alloc(pattern.char_len());
+
+    let mut pattern_char_idx = 0;
+
+    candidate.find_subsequence(
+        pattern.chars(),
+        opts.is_case_sensitive,
+        opts.char_eq,
+        |offset| {
+            match_offsets[pattern_char_idx] = offset;
+            pattern_char_idx += 1;
+        },
+    )?;
+
+    let last_char_offset_inclusive = candidate
+        .find_last(
+            pattern.char(pattern.char_len() - 1),
+            opts.is_case_sensitive,
+            opts.char_eq,
+        )
+        .unwrap()
+        + 1;
+
+    Some((match_offsets, last_char_offset_inclusive))
+}
+
 /// TODO: docs
 #[inline]
 pub(super) fn compute_score<const RANGES: bool>(
@@ -607,7 +1034,7 @@ mod tests {
         assert!(exact_match::<true>(
             pattern,
             candidate("ABC"),
-            utils::char_eq(true, false),
+            CandidateOpts::new(true, false),
             &Scheme::default(),
             &mut ((&mut ranges_buf).into())
         )
@@ -619,7 +1046,7 @@ mod tests {
             assert!(exact_match::<true>(
                 pattern,
                 candidate("AbC"),
-                utils::char_eq(true, false),
+                CandidateOpts::new(true, false),
                 &Scheme::default(),
                 &mut ((&mut ranges_buf).into())
             )
@@ -634,7 +1061,7 @@ mod tests {
             assert!(exact_match::<true>(
                 pattern,
                 candidate("AbC "),
-                utils::char_eq(true, false),
+                CandidateOpts::new(true, false),
                 &Scheme::default(),
                 &mut ((&mut ranges_buf).into())
             )
@@ -649,7 +1076,7 @@ mod tests {
             assert!(exact_match::<true>(
                 pattern,
                 candidate(" AbC "),
-                utils::char_eq(true, false),
+                CandidateOpts::new(true, false),
                 &Scheme::default(),
                 &mut ((&mut ranges_buf).into())
             )
@@ -664,7 +1091,7 @@ mod tests {
             assert!(exact_match::<true>(
                 pattern,
                 candidate("  AbC"),
-                utils::char_eq(true, false),
+                CandidateOpts::new(true, false),
                 &Scheme::default(),
                 &mut ((&mut ranges_buf).into())
             )
@@ -684,7 +1111,7 @@ mod tests {
         assert!(exact_match::<true>(
             pattern,
             candidate("aabbcc abc"),
-            utils::char_eq(true, false),
+            CandidateOpts::new(true, false),
             &Scheme::default(),
             &mut ((&mut ranges_buf).into())
         )
@@ -692,4 +1119,192 @@ mod tests {
 
         assert_eq!(ranges_buf, [7..10]);
     }
+
+    #[test]
+    fn exact_match_ascii_same_as_naive() {
+        let mut rng = crate::utils::Xorshift::new(0x2545_f491_4f6c_dd1d);
+
+        let alphabet = b"aAbB_- /1";
+
+        let mut random_string = |max_len: usize| {
+            let len = 1 + rng.next(max_len);
+            (0..len)
+                .map(|_| alphabet[rng.next(alphabet.len())] as char)
+                .collect::<String>()
+        };
+
+        for _ in 0..10_000 {
+            let pattern = random_string(3);
+            let candidate = random_string(40);
+            let pattern_chars = pattern.chars().collect::<Vec<_>>();
+            let pattern = Pattern::raw(&pattern_chars);
+
+            for is_case_sensitive in [true, false] {
+                let naive = exact_match_naive(
+                    pattern,
+                    Candidate::Ascii(candidate.as_bytes()),
+                    utils::char_eq(is_case_sensitive, false),
+                    &Scheme::default(),
+                );
+
+                let ascii = exact_match_ascii(
+                    pattern,
+                    candidate.as_bytes(),
+                    is_case_sensitive,
+                    &Scheme::default(),
+                );
+
+                assert_eq!(naive, ascii, "{pattern:?} in {candidate:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn evaluation_order_same_as_query_order() {
+        let mut rng = crate::utils::Xorshift::new(0x1234_5678_9abc_def1);
+
+        let alphabet = b"abAB_ /";
+
+        let queries = [
+            "a 'b ^a b$",
+            "ab !ba 'a_",
+            "^a | b$ ab !'bb",
+            "!a$ | 'b a/b 'b",
+            "ba | ^ab$ ab ^b",
+        ];
+
+        let mut v1 = FzfV1::new();
+
+        let mut v2 = FzfV2::new();
+
+        let mut parser = FzfParser::new();
+
+        for _ in 0..2_000 {
+            let len = rng.next(20);
+
+            let candidate = (0..len)
+                .map(|_| alphabet[rng.next(alphabet.len())] as char)
+                .collect::<String>();
+
+            for query in queries {
+                let planned = parser.parse(query);
+
+                let SearchMode::Extended(conditions) = planned.search_mode
+                else {
+                    panic!("{query:?} is not extended");
+                };
+
+                let unplanned =
+                    FzfQuery { evaluation_order: conditions, ..planned };
+
+                let mut planned_ranges = Vec::new();
+
+                let mut unplanned_ranges = Vec::new();
+
+                assert_eq!(
+                    v1.distance_and_ranges(
+                        planned,
+                        &candidate,
+                        &mut planned_ranges
+                    ),
+                    v1.distance_and_ranges(
+                        unplanned,
+                        &candidate,
+                        &mut unplanned_ranges
+                    ),
+                    "{query:?} on {candidate:?}"
+                );
+
+                assert_eq!(planned_ranges, unplanned_ranges);
+
+                planned_ranges.clear();
+
+                unplanned_ranges.clear();
+
+                assert_eq!(
+                    v2.distance_and_ranges(
+                        planned,
+                        &candidate,
+                        &mut planned_ranges
+                    ),
+                    v2.distance_and_ranges(
+                        unplanned,
+                        &candidate,
+                        &mut unplanned_ranges
+                    ),
+                    "{query:?} on {candidate:?}"
+                );
+
+                assert_eq!(planned_ranges, unplanned_ranges);
+            }
+        }
+    }
+
+    #[test]
+    fn distance_within_same_as_filtered_distance() {
+        let mut rng = crate::utils::Xorshift::new(0x5851_f42d_4c95_7f2d);
+
+        let alphabet = b"abAB_ /";
+
+        let prefixes = ["", "'", "^", "!", "!'", "!^"];
+
+        let mut v1 = FzfV1::new();
+
+        let mut v2 = FzfV2::new();
+
+        let mut parser = FzfParser::new();
+
+        for _ in 0..20_000 {
+            let mut query = String::new();
+
+            for term_idx in 0..1 + rng.next(3) {
+                if term_idx > 0 {
+                    query.push_str(if rng.next(4) == 0 { " | " } else { " " });
+                }
+
+                query.push_str(prefixes[rng.next(prefixes.len())]);
+
+                for _ in 0..1 + rng.next(3) {
+                    query.push(alphabet[rng.next(4)] as char);
+                }
+
+                if rng.next(4) == 0 {
+                    query.push('$');
+                }
+            }
+
+            let candidate = (0..rng.next(20))
+                .map(|_| alphabet[rng.next(alphabet.len())] as char)
+                .collect::<String>();
+
+            let query = parser.parse(&query);
+
+            let max_distance =
+                FzfDistance::from_score(rng.next(120) as Score - 10);
+
+            assert_eq!(
+                Metric::distance_within(
+                    &mut v1,
+                    query,
+                    &candidate,
+                    &max_distance
+                ),
+                Metric::distance(&mut v1, query, &candidate)
+                    .filter(|distance| *distance <= max_distance),
+                "{query:?} on {candidate:?}"
+            );
+
+            assert_eq!(
+                Metric::distance_within(
+                    &mut v2,
+                    query,
+                    &candidate,
+                    &max_distance
+                ),
+                Metric::distance(&mut v2, query, &candidate)
+                    .filter(|distance| *distance <= max_distance),
+                "{query:?} on {candidate:?}"
+            );
+        }
+    }
 }
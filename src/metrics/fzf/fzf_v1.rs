@@ -67,6 +67,10 @@ pub struct FzfV1 {
 
     /// TODO: docs
     scoring_scheme: Scheme,
+
+    /// The lowest score a candidate has to reach, set by
+    /// [`distance_within`](Metric::distance_within).
+    min_score: Option<Score>,
 }
 
 impl core::fmt::Debug for FzfV1 {
@@ -222,6 +226,23 @@ impl Metric for FzfV1 {
     ) -> Option<Self::Distance> {
         <Self as Fzf>::distance::<true>(self, query, candidate, ranges)
     }
+
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: FzfQuery<'_>,
+        candidate: &str,
+        max_distance: &FzfDistance,
+    ) -> Option<Self::Distance> {
+        <Self as Fzf>::distance_within(self, query, candidate, *max_distance)
+    }
+}
+
+impl Similarity for FzfV1 {
+    #[inline]
+    fn similarity(&self, query: FzfQuery<'_>, distance: &FzfDistance) -> f64 {
+        <Self as Fzf>::similarity(self, query, *distance)
+    }
 }
 
 impl Fzf for FzfV1 {
@@ -231,14 +252,19 @@ impl Fzf for FzfV1 {
     }
 
     #[inline(always)]
-    fn char_eq(&self, pattern: Pattern) -> utils::CharEq {
+    fn candidate_normalization(&self) -> bool {
+        self.candidate_normalization
+    }
+
+    #[inline(always)]
+    fn candidate_opts(&self, pattern: Pattern) -> CandidateOpts {
         let is_sensitive = match self.case_sensitivity {
             CaseSensitivity::Sensitive => true,
             CaseSensitivity::Insensitive => false,
             CaseSensitivity::Smart => pattern.has_uppercase,
         };
 
-        utils::char_eq(is_sensitive, self.candidate_normalization)
+        CandidateOpts::new(is_sensitive, self.candidate_normalization)
     }
 
     #[inline(always)]
@@ -246,6 +272,11 @@ impl Fzf for FzfV1 {
         &self.scoring_scheme
     }
 
+    #[inline(always)]
+    fn set_min_score(&mut self, min_score: Option<Score>) {
+        self.min_score = min_score;
+    }
+
     #[inline(always)]
     fn fuzzy<const RANGES: bool>(
         &mut self,
@@ -253,20 +284,31 @@ impl Fzf for FzfV1 {
         candidate: Candidate,
         ranges: &mut MatchedRanges,
     ) -> Option<Score> {
-        let is_sensitive = match self.case_sensitivity {
-            CaseSensitivity::Sensitive => true,
-            CaseSensitivity::Insensitive => false,
-            CaseSensitivity::Smart => pattern.has_uppercase,
-        };
-
-        let opts =
-            CandidateOpts::new(is_sensitive, self.candidate_normalization);
+        let opts = self.candidate_opts(pattern);
 
         let end_forward = forward_pass(pattern, candidate, opts)?;
 
         let start_backward =
             backward_pass(pattern, candidate, end_forward, opts);
 
+        if let Some(min_score) = self.min_score {
+            let gap_len = end_forward - start_backward - pattern.char_len();
+
+            // Every character between the first and the last match is part
+            // of a gap, and the cheapest way to fit them is a single gap.
+            let gap_penalty = match gap_len as Score {
+                0 => 0,
+                len => penalty::GAP_START + (len - 1) * penalty::GAP_EXTENSION,
+            };
+
+            let max_score =
+                max_pattern_score(pattern, &self.scoring_scheme) - gap_penalty;
+
+            if max_score < min_score {
+                return None;
+            }
+        }
+
         let score = compute_score::<RANGES>(
             pattern,
             candidate,
@@ -287,26 +329,14 @@ fn forward_pass(
     candidate: Candidate,
     opts: CandidateOpts,
 ) -> Option<usize> {
-    let mut pattern_chars = pattern.chars();
-
-    let mut pattern_char = pattern_chars.next()?;
-
-    let mut end_offset = 0;
-
-    loop {
-        end_offset = candidate.find_first_from(
-            end_offset,
-            pattern_char,
-            opts.is_case_sensitive,
-            opts.char_eq,
-        )? + 1;
-
-        if let Some(next) = pattern_chars.next() {
-            pattern_char = next;
-        } else {
-            return Some(end_offset);
-        }
-    }
+    let last_match_offset = candidate.find_subsequence(
+        pattern.chars(),
+        opts.is_case_sensitive,
+        opts.char_eq,
+        |_| {},
+    )?;
+
+    Some(last_match_offset + 1)
 }
 
 /// TODO: docs
@@ -317,26 +347,13 @@ fn backward_pass(
     end_offset: usize,
     opts: CandidateOpts,
 ) -> usize {
-    let mut pattern_chars = pattern.chars().rev();
-
-    let mut pattern_char = pattern_chars.next().expect("pattern is not empty");
-
-    let mut start_offset = end_offset;
-
-    loop {
-        start_offset = candidate
-            .find_last_from(
-                start_offset,
-                pattern_char,
-                opts.is_case_sensitive,
-                opts.char_eq,
-            )
-            .unwrap();
-
-        if let Some(next) = pattern_chars.next() {
-            pattern_char = next;
-        } else {
-            return start_offset;
-        }
-    }
+    candidate
+        .find_subsequence_rev(
+            end_offset,
+            pattern.chars().rev(),
+            opts.is_case_sensitive,
+            opts.char_eq,
+            |_| {},
+        )
+        .expect("the forward pass found a match")
 }
@@ -61,7 +61,7 @@ use crate::*;
 ///
 /// [sw]: https://en.wikipedia.org/wiki/Smith%E2%80%93Waterman_algorithm
 #[cfg_attr(docsrs, doc(cfg(feature = "fzf-v2")))]
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct FzfV2 {
     /// TODO: docs
     candidate_slab: CandidateSlab,
@@ -75,8 +75,40 @@ pub struct FzfV2 {
     /// TODO: docs
     scoring_scheme: Scheme,
 
-    /// TODO: docs
+    /// The buffers used to score candidates without typos.
     slab: V2Slab,
+
+    /// The maximum number of typos allowed in every fuzzy pattern, where zero
+    /// disables the typo path entirely.
+    max_typos: usize,
+
+    /// The penalty subtracted from the score of an alignment for every typo.
+    typo_penalty: Score,
+
+    /// The buffers used to score candidates when the pattern is not a
+    /// subsequence of them.
+    typo_slab: TypoSlab,
+
+    /// The lowest score a candidate has to reach, set by
+    /// [`distance_within`](Metric::distance_within).
+    min_score: Option<Score>,
+}
+
+impl Default for FzfV2 {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            candidate_slab: CandidateSlab::default(),
+            candidate_normalization: false,
+            case_sensitivity: CaseSensitivity::default(),
+            scoring_scheme: Scheme::default(),
+            slab: V2Slab::default(),
+            max_typos: 0,
+            typo_penalty: penalty::TYPO,
+            typo_slab: TypoSlab::default(),
+            min_score: None,
+        }
+    }
 }
 
 impl core::fmt::Debug for FzfV2 {
@@ -91,6 +123,8 @@ impl core::fmt::Debug for FzfV2 {
             .field("candidate_normalization", &self.candidate_normalization)
             .field("case_sensitivity", &self.case_sensitivity)
             .field("scoring_scheme", &scoring_scheme)
+            .field("max_typos", &self.max_typos)
+            .field("typo_penalty", &self.typo_penalty)
             .finish_non_exhaustive()
     }
 }
@@ -207,6 +241,131 @@ impl FzfV2 {
         self.scoring_scheme = scheme.into_inner();
         self
     }
+
+    /// Sets the maximum number of typos a fuzzy pattern can contain and still
+    /// match a candidate. The default is `0`, i.e. every character of the
+    /// pattern has to appear in the candidate.
+    ///
+    /// A typo is either a character of the pattern replaced by a different
+    /// one (a substitution), an extra character in the pattern (an
+    /// insertion), or two adjacent characters of the pattern in the wrong
+    /// order (a transposition). The budget applies to every pattern of an
+    /// extended-search query on its own, but exact and inverse patterns never
+    /// tolerate typos, and neither do patterns of a single character. A
+    /// pattern always has to match at least one of its characters, so the
+    /// budget is capped at the length of the pattern minus one.
+    ///
+    /// Typos are only considered when the pattern isn't a subsequence of the
+    /// candidate, every typo subtracts the penalty set with
+    /// [`set_typo_penalty`](Self::set_typo_penalty) from the score, and the
+    /// characters of the candidate aligned to a typo are not part of the
+    /// ranges returned by
+    /// [`distance_and_ranges`](Metric::distance_and_ranges).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfV2, FzfParser};
+    /// # use norm::Metric;
+    /// let mut fzf = FzfV2::new();
+    /// let mut parser = FzfParser::new();
+    /// let mut ranges = Vec::new();
+    ///
+    /// let query = parser.parse("recieve");
+    ///
+    /// assert!(fzf.distance(query, "receive").is_none());
+    ///
+    /// fzf.set_max_typos(1);
+    ///
+    /// let _ = fzf.distance_and_ranges(query, "receive", &mut ranges).unwrap();
+    ///
+    /// // The swapped "ei" is a transposition, so it's not part of the ranges.
+    /// assert_eq!(ranges, [0..3, 5..7]);
+    ///
+    /// // Patterns with more typos than the budget still don't match.
+    /// assert!(fzf.distance(parser.parse("reveice"), "receive").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_max_typos(&mut self, max_typos: usize) -> &mut Self {
+        self.max_typos = max_typos;
+        self
+    }
+
+    /// Sets the penalty subtracted from the score of a candidate for every
+    /// typo in the pattern. The default is the same as the score of a single
+    /// matched character, which ranks candidates with typos below most of the
+    /// ones without them.
+    ///
+    /// This only has an effect if typos are allowed with
+    /// [`set_max_typos`](Self::set_max_typos).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfV2, FzfParser};
+    /// # use norm::Metric;
+    /// let mut fzf = FzfV2::new();
+    /// let mut parser = FzfParser::new();
+    ///
+    /// fzf.set_max_typos(1);
+    ///
+    /// let query = parser.parse("fxo");
+    ///
+    /// let default_penalty = fzf.distance(query, "foo").unwrap();
+    ///
+    /// fzf.set_typo_penalty(0);
+    ///
+    /// let no_penalty = fzf.distance(query, "foo").unwrap();
+    ///
+    /// assert!(no_penalty < default_penalty);
+    /// ```
+    #[inline(always)]
+    pub fn set_typo_penalty(&mut self, penalty: u32) -> &mut Self {
+        self.typo_penalty = penalty as _;
+        self
+    }
+
+    /// Scores a fuzzy pattern that is not a subsequence of the candidate,
+    /// allowing it to contain typos.
+    #[inline]
+    fn fuzzy_with_typos<const RANGES: bool>(
+        &mut self,
+        pattern: Pattern,
+        candidate: Candidate,
+        opts: CandidateOpts,
+        ranges: &mut MatchedRanges,
+    ) -> Option<Score> {
+        let max_typos = self.typo_budget(pattern);
+
+        if max_typos == 0 {
+            return None;
+        }
+
+        let candidate = match candidate {
+            Candidate::Utf8(str) => {
+                Candidate::Unicode(self.candidate_slab.alloc(str))
+            },
+            _ => candidate,
+        };
+
+        let candidate = CandidateV2::new(
+            candidate,
+            &mut self.slab.bonus,
+            self.scoring_scheme.initial_char_class,
+            opts,
+        );
+
+        typos::fuzzy::<RANGES>(
+            &mut self.typo_slab,
+            pattern,
+            candidate,
+            opts.char_eq,
+            max_typos,
+            self.typo_penalty,
+            &self.scoring_scheme,
+            ranges,
+        )
+    }
 }
 
 impl Metric for FzfV2 {
@@ -233,6 +392,23 @@ impl Metric for FzfV2 {
     ) -> Option<Self::Distance> {
         <Self as Fzf>::distance::<true>(self, query, candidate, ranges)
     }
+
+    #[inline]
+    fn distance_within(
+        &mut self,
+        query: FzfQuery<'_>,
+        candidate: &str,
+        max_distance: &FzfDistance,
+    ) -> Option<Self::Distance> {
+        <Self as Fzf>::distance_within(self, query, candidate, *max_distance)
+    }
+}
+
+impl Similarity for FzfV2 {
+    #[inline]
+    fn similarity(&self, query: FzfQuery<'_>, distance: &FzfDistance) -> f64 {
+        <Self as Fzf>::similarity(self, query, *distance)
+    }
 }
 
 impl Fzf for FzfV2 {
@@ -242,14 +418,19 @@ impl Fzf for FzfV2 {
     }
 
     #[inline(always)]
-    fn char_eq(&self, pattern: Pattern) -> utils::CharEq {
+    fn candidate_normalization(&self) -> bool {
+        self.candidate_normalization
+    }
+
+    #[inline(always)]
+    fn candidate_opts(&self, pattern: Pattern) -> CandidateOpts {
         let is_sensitive = match self.case_sensitivity {
             CaseSensitivity::Sensitive => true,
             CaseSensitivity::Insensitive => false,
             CaseSensitivity::Smart => pattern.has_uppercase,
         };
 
-        utils::char_eq(is_sensitive, self.candidate_normalization)
+        CandidateOpts::new(is_sensitive, self.candidate_normalization)
     }
 
     #[inline(always)]
@@ -257,6 +438,22 @@ impl Fzf for FzfV2 {
         &self.scoring_scheme
     }
 
+    #[inline(always)]
+    fn set_min_score(&mut self, min_score: Option<Score>) {
+        self.min_score = min_score;
+    }
+
+    #[inline(always)]
+    fn typo_budget(&self, pattern: Pattern) -> usize {
+        if pattern.is_inverse
+            || !matches!(pattern.match_type, MatchType::Fuzzy)
+        {
+            return 0;
+        }
+
+        self.max_typos.min(pattern.char_len().saturating_sub(1))
+    }
+
     #[inline(always)]
     fn fuzzy<const RANGES: bool>(
         &mut self,
@@ -264,27 +461,51 @@ impl Fzf for FzfV2 {
         candidate: Candidate,
         ranges: &mut MatchedRanges,
     ) -> Option<Score> {
-        let is_sensitive = match self.case_sensitivity {
-            CaseSensitivity::Sensitive => true,
-            CaseSensitivity::Insensitive => false,
-            CaseSensitivity::Smart => pattern.has_uppercase,
-        };
-
-        let opts =
-            CandidateOpts::new(is_sensitive, self.candidate_normalization);
+        let opts = self.candidate_opts(pattern);
 
         if pattern.char_len() == 1 {
+            // Scoring a single char looks at the char preceding every match,
+            // so we decode the whole candidate upfront.
+            let candidate = match candidate {
+                Candidate::Utf8(str) => {
+                    Candidate::Unicode(self.candidate_slab.alloc(str))
+                },
+                _ => candidate,
+            };
+
             return fuzzy_single_char::<RANGES>(
                 pattern.char(0),
                 candidate,
                 opts,
-                self.scheme(),
+                &self.scoring_scheme,
                 ranges,
             );
         }
 
-        let (match_offsets, last_match_offset) =
-            matches(&mut self.slab.matched_indices, pattern, candidate, opts)?;
+        // UTF-8 candidates would have to be decoded first, and not having to
+        // decode all of them is what makes them faster to score.
+        if let Some(min_score) =
+            self.min_score.filter(|_| !matches!(candidate, Candidate::Utf8(_)))
+        {
+            let max_span = max_span(
+                pattern,
+                max_pattern_score(pattern, &self.scoring_scheme) - min_score,
+            );
+
+            // If the candidate isn't a subsequence we still have to look for
+            // matches with typos.
+            if fits_in_span(pattern, candidate, opts, max_span) == Some(false)
+            {
+                return None;
+            }
+        }
+
+        let Some((match_offsets, last_match_offset)) =
+            matches(&mut self.slab.matched_indices, pattern, candidate, opts)
+        else {
+            return self
+                .fuzzy_with_typos::<RANGES>(pattern, candidate, opts, ranges);
+        };
 
         let first_offset = match_offsets[0];
 
@@ -297,8 +518,18 @@ impl Fzf for FzfV2 {
             char_class(candidate.char(first_offset - 1), &self.scoring_scheme)
         };
 
+        // Only the chars between the first and the last match take part in
+        // the scoring, so that's the only part of a UTF-8 candidate that we
+        // need to decode.
+        let window = match candidate.slice(first_offset..last_match_offset) {
+            Candidate::Utf8(str) => {
+                Candidate::Unicode(self.candidate_slab.alloc(str))
+            },
+            window => window,
+        };
+
         let mut candidate = CandidateV2::new(
-            candidate.slice(first_offset..last_match_offset),
+            window,
             &mut self.slab.bonus,
             initial_char_class,
             opts,
@@ -315,7 +546,8 @@ impl Fzf for FzfV2 {
             &mut candidate,
             match_offsets,
             &self.scoring_scheme,
-        );
+            self.min_score.unwrap_or(Score::MIN),
+        )?;
 
         if RANGES {
             matched_ranges(
@@ -332,53 +564,6 @@ impl Fzf for FzfV2 {
     }
 }
 
-/// TODO: docs
-#[inline]
-fn matches<'idx>(
-    indices_slab: &'idx mut MatchedIndicesSlab,
-    pattern: Pattern,
-    candidate: Candidate,
-    opts: CandidateOpts,
-) -> Option<(&'idx mut [usize], usize)> {
-    let match_offsets = indices_slab.alloc(pattern.char_len());
-
-    let mut pattern_char_idx = 0;
-
-    let mut last_match_offset = 0;
-
-    loop {
-        let pattern_char = pattern.char(pattern_char_idx);
-
-        last_match_offset = candidate.find_first_from(
-            last_match_offset,
-            pattern_char,
-            opts.is_case_sensitive,
-            opts.char_eq,
-        )?;
-
-        match_offsets[pattern_char_idx] = last_match_offset;
-
-        last_match_offset += 1;
-
-        if pattern_char_idx + 1 < pattern.char_len() {
-            pattern_char_idx += 1;
-        } else {
-            break;
-        }
-    }
-
-    let last_char_offset_inclusive = candidate
-        .find_last(
-            pattern.char(pattern_char_idx),
-            opts.is_case_sensitive,
-            opts.char_eq,
-        )
-        .unwrap()
-        + 1;
-
-    Some((match_offsets, last_char_offset_inclusive))
-}
-
 /// TODO: docs
 #[inline]
 fn score<'scoring, 'consecutive>(
@@ -388,8 +573,13 @@ fn score<'scoring, 'consecutive>(
     candidate: &mut CandidateV2,
     matches: &[usize],
     scheme: &Scheme,
-) -> (Matrix<'scoring, Score>, Matrix<'consecutive, usize>, Score, MatrixCell)
-{
+    min_score: Score,
+) -> Option<(
+    Matrix<'scoring, Score>,
+    Matrix<'consecutive, usize>,
+    Score,
+    MatrixCell,
+)> {
     let matrix_width = candidate.char_len();
 
     let matrix_height = pattern.char_len();
@@ -407,6 +597,14 @@ fn score<'scoring, 'consecutive>(
         scheme,
     );
 
+    let max_char_score = bonus::MATCH + max_bonus(scheme);
+
+    let start_column = next_start_column(
+        scoring_matrix.row_mut(0),
+        0,
+        min_row_score(min_score, max_score, 0, matrix_height, max_char_score),
+    )?;
+
     let (max_score, max_score_cell) = score_remaining_rows(
         &mut scoring_matrix,
         &mut consecutive_matrix,
@@ -416,9 +614,133 @@ fn score<'scoring, 'consecutive>(
         matches,
         max_score,
         max_score_cell,
-    );
+        start_column,
+        min_score,
+        max_char_score,
+    )?;
+
+    Some((scoring_matrix, consecutive_matrix, max_score, max_score_cell))
+}
+
+/// Returns the most characters of the candidate a match of the pattern can
+/// span while scoring at most `slack` points less than the highest score the
+/// pattern can get.
+///
+/// Every character of the span that's not matched is part of a gap, and the
+/// cheapest way to fit them is in a single gap.
+#[inline(always)]
+fn max_span(pattern: Pattern, slack: Score) -> usize {
+    let max_gap_len = if slack < penalty::GAP_START {
+        0
+    } else {
+        1 + (slack - penalty::GAP_START) / penalty::GAP_EXTENSION
+    };
+
+    pattern.char_len() + max_gap_len as usize
+}
+
+/// Returns whether the pattern is a subsequence of a part of the candidate
+/// spanning at most `max_span` characters, or `None` if it's not a
+/// subsequence of the candidate at all.
+///
+/// This goes through the shortest windows of the candidate containing the
+/// pattern from left to right, each one found by a forward pass followed by
+/// a backward one, like in [`FzfV1`].
+#[inline]
+fn fits_in_span(
+    pattern: Pattern,
+    candidate: Candidate,
+    opts: CandidateOpts,
+    max_span: usize,
+) -> Option<bool> {
+    let char_len = candidate.char_len();
+
+    let mut start = 0;
+
+    let mut is_subsequence = false;
+
+    while start < char_len {
+        let Some(last_match_offset) =
+            candidate.slice(start..char_len).find_subsequence(
+                pattern.chars(),
+                opts.is_case_sensitive,
+                opts.char_eq,
+                |_| {},
+            )
+        else {
+            break;
+        };
+
+        is_subsequence = true;
 
-    (scoring_matrix, consecutive_matrix, max_score, max_score_cell)
+        let end = start + last_match_offset + 1;
+
+        let window_start = candidate
+            .find_subsequence_rev(
+                end,
+                pattern.chars().rev(),
+                opts.is_case_sensitive,
+                opts.char_eq,
+                |_| {},
+            )
+            .expect("the forward pass found a match");
+
+        if end - window_start <= max_span {
+            return Some(true);
+        }
+
+        // The next window has to start after this one, or it would be at
+        // least as long.
+        start = window_start + 1;
+    }
+
+    is_subsequence.then_some(false)
+}
+
+/// Returns the lowest score a cell in the given row needs to have to still be
+/// able to lead to a cell scoring at least `min_score`, knowing that every
+/// row below it can add at most `max_char_score` to it.
+#[inline(always)]
+fn min_row_score(
+    min_score: Score,
+    max_score: Score,
+    row_idx: usize,
+    matrix_height: usize,
+    max_char_score: Score,
+) -> Score {
+    // The score of the candidate is the highest one in the whole matrix, so
+    // once a cell reaches `min_score` there's nothing left to prune.
+    if max_score >= min_score {
+        return Score::MIN;
+    }
+
+    let remaining_rows = (matrix_height - row_idx - 1) as Score;
+    min_score.saturating_sub(remaining_rows * max_char_score)
+}
+
+/// Returns the column the row after the given one can start being filled
+/// from, or `None` if none of its cells can lead to a cell scoring at least
+/// `min_score`.
+///
+/// The cells of the next row before the returned column are left unfilled,
+/// since their score can only come from cells of this row that can't reach
+/// `min_score` either.
+#[inline(always)]
+fn next_start_column(
+    row: &Row<Score>,
+    start_column: usize,
+    min_row_score: Score,
+) -> Option<usize> {
+    // The cells that are never filled are zero, so we can only skip cells if
+    // a zero can't reach `min_score` either.
+    if min_row_score <= 0 {
+        return Some(0);
+    }
+
+    row[start_column..]
+        .iter()
+        .position(|&score| score >= min_row_score)
+        .map(|offset| start_column + offset + 1)
 }
 
 /// TODO: docs
@@ -489,10 +811,15 @@ fn score_remaining_rows(
     matches: &[usize],
     mut max_score: Score,
     mut max_score_cell: MatrixCell,
-) -> (Score, MatrixCell) {
+    mut start_column: usize,
+    min_score: Score,
+    max_char_score: Score,
+) -> Option<(Score, MatrixCell)> {
     let matrix_width = scores.width();
 
-    for row_idx in 1..scores.height() {
+    let matrix_height = scores.height();
+
+    for row_idx in 1..matrix_height {
         let pattern_char = pattern.char(row_idx);
 
         let (prev_scores_row, scores_row) =
@@ -501,9 +828,16 @@ fn score_remaining_rows(
         let (prev_consecutives_row, consecutives_row) =
             consecutives.two_rows_mut(row_idx - 1, row_idx);
 
-        let first_match_offset = matches[row_idx];
-
-        let mut column = first_match_offset;
+        // The cells before `start_column` can't lead to a cell reaching the
+        // minimum score, so we can skip them. We still start right before it
+        // because the gap penalty after a match depends on whether the
+        // column before the match is also a match.
+        let (first_match_offset, mut column) =
+            if start_column > matches[row_idx] {
+                (start_column - 1, start_column - 2)
+            } else {
+                (matches[row_idx], matches[row_idx])
+            };
 
         let mut penalty = penalty::GAP_START;
 
@@ -564,9 +898,21 @@ fn score_remaining_rows(
             scores_row[col] = score;
             penalty = penalty::GAP_EXTENSION;
         }
+
+        start_column = next_start_column(
+            scores_row,
+            first_match_offset,
+            min_row_score(
+                min_score,
+                max_score,
+                row_idx,
+                matrix_height,
+                max_char_score,
+            ),
+        )?;
     }
 
-    (max_score, max_score_cell)
+    Some((max_score, max_score_cell))
 }
 
 /// TODO: docs
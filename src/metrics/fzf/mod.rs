@@ -6,6 +6,12 @@
 //! release of fzf. Any discrepancy between our implementation and fzf's should
 //! be considered a bug.
 //!
+//! It also contains the [`Skim`] metric, which understands the same queries
+//! but scores fuzzy patterns with the algorithm used by [skim], and the
+//! [`Acronym`] metric, which splits candidates into words at the same
+//! boundaries used by fzf's scoring and matches queries against their
+//! initials.
+//!
 //! ## Extended-search mode
 //!
 //! fzf's [extended-search mode][esm] is fully-supported by parsing the query
@@ -22,36 +28,65 @@
 //! [fzf's docs][esm] on it, or at the documentation of the [`FzfParser`].
 //!
 //! [fzf]: https://github.com/junegunn/fzf
+//! [skim]: https://github.com/lotabout/skim
 //! [esm]: https://github.com/junegunn/fzf#search-syntax
 
+#[cfg(feature = "acronym")]
+mod acronym;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 mod candidate;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
+mod char_mask;
 mod distance;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 mod fzf;
 #[cfg(feature = "fzf-v1")]
 mod fzf_v1;
 #[cfg(feature = "fzf-v2")]
 mod fzf_v2;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 mod parser;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 mod query;
 mod scheme;
 mod scoring;
+#[cfg(feature = "skim")]
+mod skim;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 mod slab;
+#[cfg(feature = "fzf-v2")]
+mod typos;
 
+#[cfg(feature = "acronym")]
+pub use acronym::Acronym;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 use candidate::*;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
+use char_mask::CharMask;
+#[cfg(feature = "acronym")]
+pub use distance::AcronymDistance;
 pub use distance::FzfDistance;
 use distance::*;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 use fzf::*;
 #[cfg(feature = "fzf-v1")]
 pub use fzf_v1::FzfV1;
 #[cfg(feature = "fzf-v2")]
 pub use fzf_v2::FzfV2;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 pub use parser::*;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 pub use query::FzfQuery;
 pub use scheme::FzfScheme;
 #[doc(hidden)]
 pub use scheme::Scheme;
 use scoring::*;
+#[cfg(feature = "skim")]
+pub use skim::Skim;
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 use slab::*;
+#[cfg(feature = "fzf-v2")]
+use typos::TypoSlab;
 
 #[doc(hidden)]
 pub mod bonus {
@@ -89,4 +124,7 @@ pub mod penalty {
 
     /// TODO: docs
     pub const GAP_EXTENSION: Score = 1;
+
+    /// The default penalty of every typo allowed in a pattern by `FzfV2`.
+    pub const TYPO: Score = bonus::MATCH;
 }
@@ -9,7 +9,13 @@ use crate::utils;
 /// [`parse`][FzfParser::parse]. If this is not desired, use
 /// [`parse_not_extended`][FzfParser::parse_not_extended] instead.
 ///
+/// It also implements the [`Parser`](crate::Parser) trait for [`FzfV1`],
+/// [`FzfV2`] and [`Skim`], where queries are parsed in extended-search mode.
+///
 /// [esm]: https://github.com/junegunn/fzf#search-syntax
+/// [`FzfV1`]: super::FzfV1
+/// [`FzfV2`]: super::FzfV2
+/// [`Skim`]: super::Skim
 #[derive(Clone)]
 pub struct FzfParser {
     /// TODO: docs
@@ -20,6 +26,10 @@ pub struct FzfParser {
 
     /// TODO: docs
     conditions: Vec<Condition<'static>>,
+
+    /// The same conditions as [`Self::conditions`], sorted by
+    /// [`Condition::evaluation_cost`].
+    evaluation_order: Vec<Condition<'static>>,
 }
 
 impl Default for FzfParser {
@@ -29,6 +39,7 @@ impl Default for FzfParser {
             chars: vec![char::default(); 64],
             patterns: vec![Pattern::default(); 64],
             conditions: vec![Condition::default(); 64],
+            evaluation_order: vec![Condition::default(); 64],
         }
     }
 }
@@ -132,7 +143,19 @@ impl FzfParser {
             num_conditions += 1;
         }
 
-        FzfQuery::new_extended(&self.conditions[..num_conditions])
+        if self.evaluation_order.len() < num_conditions {
+            self.evaluation_order.resize(num_conditions, Condition::default());
+        }
+
+        let conditions = &self.conditions[..num_conditions];
+
+        let evaluation_order = &mut self.evaluation_order[..num_conditions];
+
+        evaluation_order.copy_from_slice(conditions);
+
+        evaluation_order.sort_by_key(Condition::evaluation_cost);
+
+        FzfQuery::new_extended(conditions, evaluation_order)
     }
 
     /// Parses the given query string without using fzf's extended-search mode.
@@ -181,6 +204,30 @@ impl FzfParser {
     }
 }
 
+#[cfg(feature = "fzf-v1")]
+impl crate::Parser<super::FzfV1> for FzfParser {
+    #[inline(always)]
+    fn parse<'a>(&'a mut self, query: &'a str) -> FzfQuery<'a> {
+        self.parse(query)
+    }
+}
+
+#[cfg(feature = "fzf-v2")]
+impl crate::Parser<super::FzfV2> for FzfParser {
+    #[inline(always)]
+    fn parse<'a>(&'a mut self, query: &'a str) -> FzfQuery<'a> {
+        self.parse(query)
+    }
+}
+
+#[cfg(feature = "skim")]
+impl crate::Parser<super::Skim> for FzfParser {
+    #[inline(always)]
+    fn parse<'a>(&'a mut self, query: &'a str) -> FzfQuery<'a> {
+        self.parse(query)
+    }
+}
+
 const OR_BLOCK_SEPARATOR: &[char] = &['|'];
 
 /// TODO: docs
@@ -491,6 +538,31 @@ mod parse_tests {
         assert_eq!(pattern.match_type, MatchType::Exact);
         assert!(pattern.is_inverse);
     }
+
+    #[test]
+    fn parse_query_evaluation_order() {
+        let query = parse("foo 'ba | baz ^foobar$ 'a !'bar");
+
+        let order = query
+            .evaluation_order
+            .iter()
+            .map(|condition| {
+                let pattern = condition.or_patterns()[0];
+                (pattern.into_string(), pattern.match_type, pattern.is_inverse)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            order,
+            [
+                ("foobar".to_owned(), MatchType::EqualExact, false),
+                ("a".to_owned(), MatchType::Exact, false),
+                ("bar".to_owned(), MatchType::Fuzzy, true),
+                ("foo".to_owned(), MatchType::Fuzzy, false),
+                ("ba".to_owned(), MatchType::Exact, false),
+            ]
+        );
+    }
 }
 
 #[cfg(test)]
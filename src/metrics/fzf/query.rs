@@ -1,5 +1,7 @@
 use core::fmt::Write;
 
+use super::CharMask;
+
 /// A parsed fzf query.
 ///
 /// This struct is created by parsing a query string via the
@@ -7,6 +9,18 @@ use core::fmt::Write;
 #[derive(Clone, Copy)]
 pub struct FzfQuery<'a> {
     pub(super) search_mode: SearchMode<'a>,
+
+    /// The conditions of an extended query, sorted in the order they should
+    /// be evaluated in.
+    ///
+    /// Every condition has to match for the query to match, and the total
+    /// score is the sum of their scores, so the order in which they're
+    /// evaluated doesn't change the result. Evaluating the cheapest and most
+    /// selective conditions first however lets us reject most candidates
+    /// without running the more expensive ones.
+    ///
+    /// This is empty if the query is not extended.
+    pub(super) evaluation_order: &'a [Condition<'a>],
 }
 
 /// TODO: docs
@@ -47,9 +61,13 @@ impl<'a> FzfQuery<'a> {
 
     /// TODO: docs
     #[inline]
-    pub(super) fn new_extended(conditions: &'a [Condition<'a>]) -> Self {
+    pub(super) fn new_extended(
+        conditions: &'a [Condition<'a>],
+        evaluation_order: &'a [Condition<'a>],
+    ) -> Self {
         // If there's only one condition with a single pattern, and that
-        // pattern is fuzzy, then we can use the non-extended search mode.
+        // pattern is fuzzy and not inverse, then we can use the non-extended
+        // search mode.
         if conditions.len() == 1 {
             let mut patterns = conditions[0].iter();
 
@@ -59,20 +77,28 @@ impl<'a> FzfQuery<'a> {
 
             if patterns.next().is_none()
                 && matches!(first_pattern.match_type, MatchType::Fuzzy)
+                && !first_pattern.is_inverse
             {
                 return Self {
                     search_mode: SearchMode::NotExtended(first_pattern),
+                    evaluation_order: &[],
                 };
             }
         }
 
-        Self { search_mode: SearchMode::Extended(conditions) }
+        Self {
+            search_mode: SearchMode::Extended(conditions),
+            evaluation_order,
+        }
     }
 
     /// TODO: docs
     #[inline]
     pub(super) fn new_not_extended(chars: &'a [char]) -> Self {
-        Self { search_mode: SearchMode::NotExtended(Pattern::raw(chars)) }
+        Self {
+            search_mode: SearchMode::NotExtended(Pattern::raw(chars)),
+            evaluation_order: &[],
+        }
     }
 }
 
@@ -112,6 +138,39 @@ impl core::fmt::Debug for Condition<'_> {
 }
 
 impl<'a> Condition<'a> {
+    /// Returns a key that sorts conditions from the cheapest to evaluate to
+    /// the most expensive one.
+    ///
+    /// Conditions with a single pattern come before groups of OR-ed patterns,
+    /// which are both more likely to match and more expensive to evaluate.
+    /// Then exact patterns come before fuzzy ones, inverse patterns before
+    /// non-inverse ones, and longer patterns before shorter ones, since
+    /// they're less likely to match.
+    ///
+    /// Note that the patterns within a condition are never reordered: the
+    /// score of a condition is the one of its first matching pattern, so
+    /// changing their order could change the score.
+    #[inline]
+    pub(super) fn evaluation_cost(&self) -> impl Ord {
+        let max_cost = self
+            .iter()
+            .map(|pattern| pattern.match_type.evaluation_cost())
+            .max()
+            .unwrap_or_default();
+
+        let are_all_inverse = self.iter().all(|pattern| pattern.is_inverse);
+
+        let min_char_len =
+            self.iter().map(|pattern| pattern.char_len()).min().unwrap_or(0);
+
+        (
+            self.or_patterns.len(),
+            max_cost,
+            !are_all_inverse,
+            core::cmp::Reverse(min_char_len),
+        )
+    }
+
     #[cfg(test)]
     pub(super) fn or_patterns(&self) -> &'a [Pattern<'a>] {
         self.or_patterns
@@ -151,6 +210,9 @@ pub(super) struct Pattern<'a> {
 
     /// TODO: docs
     pub(super) trailing_spaces: usize,
+
+    /// The ASCII letters and digits contained in [`Self::text`].
+    pub(super) char_mask: CharMask,
 }
 
 impl core::fmt::Debug for Pattern<'_> {
@@ -199,7 +261,7 @@ impl<'a> Pattern<'a> {
 
     /// TODO: docs
     #[inline]
-    fn raw(text: &'a [char]) -> Self {
+    pub(super) fn raw(text: &'a [char]) -> Self {
         let leading_spaces = text.iter().take_while(|&&c| c == ' ').count();
 
         let trailing_spaces =
@@ -209,6 +271,7 @@ impl<'a> Pattern<'a> {
             leading_spaces,
             trailing_spaces,
             has_uppercase: text.iter().copied().any(char::is_uppercase),
+            char_mask: CharMask::from_chars(text),
             text,
             match_type: MatchType::Fuzzy,
             is_inverse: false,
@@ -268,6 +331,7 @@ impl<'a> Pattern<'a> {
             has_uppercase,
             leading_spaces,
             trailing_spaces,
+            char_mask: CharMask::from_chars(text),
         };
 
         Some(this)
@@ -310,6 +374,21 @@ pub(super) enum MatchType {
     EqualExact,
 }
 
+impl MatchType {
+    /// Returns how expensive it is to check whether a pattern of this type
+    /// matches a candidate, relative to the other match types.
+    #[inline]
+    fn evaluation_cost(self) -> u8 {
+        match self {
+            Self::EqualExact => 0,
+            Self::PrefixExact => 1,
+            Self::SuffixExact => 2,
+            Self::Exact => 3,
+            Self::Fuzzy => 4,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
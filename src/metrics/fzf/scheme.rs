@@ -21,6 +21,7 @@ pub enum FzfScheme {
     History,
 }
 
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
 impl FzfScheme {
     /// TODO: docs
     #[inline]
@@ -79,6 +80,7 @@ fn is_delimiter_default(ch: char) -> bool {
     matches!(ch, '/' | ',' | ':' | ';' | '|')
 }
 
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
 /// TODO: docs
 pub const PATH: Scheme = Scheme {
     bonus_boundary_white: bonus::BOUNDARY,
@@ -87,6 +89,7 @@ pub const PATH: Scheme = Scheme {
     is_delimiter: is_delimiter_path,
 };
 
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
 #[inline]
 fn is_delimiter_path(ch: char) -> bool {
     // Using `std::path::MAIN_SEPARATOR` would force us to depend on `std`
@@ -99,6 +102,7 @@ fn is_delimiter_path(ch: char) -> bool {
     ch == '/' || ch == os_path_separator
 }
 
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
 /// TODO: docs
 pub const HISTORY: Scheme = Scheme {
     bonus_boundary_white: bonus::BOUNDARY,
@@ -73,6 +73,7 @@ fn non_ascii_char_class(ch: char, scheme: &Scheme) -> CharClass {
     }
 }
 
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 /// TODO: docs
 #[inline]
 pub(super) fn compute_bonus(
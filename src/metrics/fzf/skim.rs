@@ -0,0 +1,509 @@
+use core::ops::Range;
+
+use super::{query::*, slab::*, *};
+use crate::*;
+
+/// The score of every matched character, before any bonus or penalty.
+const SCORE_MATCH: Score = 16;
+
+/// Added when a gap between two matched characters starts.
+const GAP_START: Score = -3;
+
+/// Added for every character a gap is extended by.
+const GAP_EXTENSION: Score = -1;
+
+/// The bonus of the first character of the pattern is multiplied by this.
+const BONUS_FIRST_CHAR_MULTIPLIER: Score = 2;
+
+/// The bonus of a match at the start of a word, i.e. at the start of the
+/// candidate or right after a [`CharType::HardSeparator`].
+const BONUS_HEAD: Score = SCORE_MATCH / 2;
+
+/// The bonus of a match right after a [`CharType::SoftSeparator`].
+const BONUS_BREAK: Score = SCORE_MATCH / 2 + GAP_EXTENSION;
+
+/// The bonus of an uppercase match right after a lowercase letter or a
+/// digit.
+const BONUS_CAMEL: Score = SCORE_MATCH / 2 + 2 * GAP_EXTENSION;
+
+/// Added to a match that directly follows the previous one.
+const BONUS_CONSECUTIVE: Score = -(GAP_START + GAP_EXTENSION);
+
+/// Added to a match whose characters are only equal after case folding or
+/// normalization.
+const PENALTY_CASE_MISMATCH: Score = 2 * GAP_EXTENSION;
+
+/// The score of a cell of the matrices that no alignment can reach.
+const UNREACHABLE: Score = Score::MIN;
+
+/// A metric that implements skim's v2 fuzzy matching algorithm.
+///
+/// skim is a fuzzy-finder inspired by fzf. It supports the same
+/// [extended-search syntax](super#extended-search-mode), so this metric works
+/// with the queries returned by [`FzfParser::parse`], but it scores fuzzy
+/// patterns with its own algorithm. Exact, prefix, suffix and equal patterns
+/// are matched and scored the same way as in [`FzfV2`](super::FzfV2).
+///
+/// Like [`FzfV2`](super::FzfV2), the algorithm looks for the best alignment
+/// of the pattern in the candidate using dynamic programming. Matched
+/// characters are rewarded, especially when they're at the start of a word,
+/// after a separator or at a camelCase boundary, or when they immediately
+/// follow the previous match. Gaps between matched characters are
+/// penalized, while leading and trailing characters are not. When matching
+/// case-insensitively, characters whose case differs from the pattern's get
+/// a small penalty.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::fzf::{FzfParser, Skim};
+/// # use norm::Metric;
+/// let mut skim = Skim::new();
+/// let mut parser = FzfParser::new();
+/// let mut ranges = Vec::new();
+///
+/// let query = parser.parse("fb");
+///
+/// let distance =
+///     skim.distance_and_ranges(query, "fooBar", &mut ranges).unwrap();
+///
+/// // The "B" is at a camelCase boundary.
+/// assert_eq!(ranges, [0..1, 3..4]);
+///
+/// // "foo_bar" is a better match since its "b" is after a separator.
+/// assert!(skim.distance(query, "foo_bar").unwrap() < distance);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "skim")))]
+#[derive(Clone, Default)]
+pub struct Skim {
+    /// Holds the decoded characters of non-ASCII candidates.
+    candidate_slab: CandidateSlab,
+
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// The scheme used to score the non-fuzzy patterns.
+    scoring_scheme: Scheme,
+
+    /// The char offsets of the first match of the fuzzy pattern being scored.
+    matched_indices: MatchedIndicesSlab,
+
+    /// The bonus of every character of the scored part of the candidate.
+    bonuses: Vec<Score>,
+
+    /// The best score of the pattern prefix whose last character is matched
+    /// at a given candidate character.
+    match_scores: Vec<Score>,
+
+    /// The best score of the pattern prefix whose last character is matched
+    /// strictly before a given candidate character, including the penalty
+    /// of the gap between the two.
+    gap_scores: Vec<Score>,
+}
+
+impl core::fmt::Debug for Skim {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Skim")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Skim {
+    /// Creates a new `Skim`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfParser, Skim};
+    /// # use norm::Metric;
+    /// let mut skim = Skim::new();
+    /// let mut parser = FzfParser::new();
+    ///
+    /// assert!(skim.distance(parser.parse("foo"), "ƒöö").is_none());
+    ///
+    /// skim.set_candidate_normalization(true);
+    ///
+    /// assert!(skim.distance(parser.parse("foo"), "ƒöö").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzf::{FzfParser, Skim};
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut skim = Skim::new();
+    /// let mut parser = FzfParser::new();
+    ///
+    /// assert!(skim.distance(parser.parse("abc"), "ABC").is_some());
+    ///
+    /// skim.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert!(skim.distance(parser.parse("abc"), "ABC").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+}
+
+impl Metric for Skim {
+    type Query<'a> = FzfQuery<'a>;
+
+    type Distance = FzfDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: FzfQuery<'_>,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let ranges = &mut Vec::new();
+        <Self as Fzf>::distance::<false>(self, query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_and_ranges(
+        &mut self,
+        query: FzfQuery<'_>,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        <Self as Fzf>::distance::<true>(self, query, candidate, ranges)
+    }
+}
+
+impl Fzf for Skim {
+    #[inline(always)]
+    fn alloc_chars<'a>(&mut self, s: &str) -> &'a [char] {
+        unsafe { core::mem::transmute(self.candidate_slab.alloc(s)) }
+    }
+
+    #[inline(always)]
+    fn candidate_normalization(&self) -> bool {
+        self.candidate_normalization
+    }
+
+    #[inline(always)]
+    fn candidate_opts(&self, pattern: Pattern) -> CandidateOpts {
+        let is_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => pattern.has_uppercase,
+        };
+
+        CandidateOpts::new(is_sensitive, self.candidate_normalization)
+    }
+
+    #[inline(always)]
+    fn scheme(&self) -> &Scheme {
+        &self.scoring_scheme
+    }
+
+    #[inline]
+    fn fuzzy<const RANGES: bool>(
+        &mut self,
+        pattern: Pattern,
+        candidate: Candidate,
+        ranges: &mut MatchedRanges,
+    ) -> Option<Score> {
+        let opts = self.candidate_opts(pattern);
+
+        let (match_offsets, last_match_offset) =
+            matches(&mut self.matched_indices, pattern, candidate, opts)?;
+
+        let first_offset = match_offsets[0];
+
+        let start_byte_offset =
+            if RANGES { candidate.to_byte_offset(first_offset) } else { 0 };
+
+        let prev_char =
+            (first_offset > 0).then(|| candidate.char(first_offset - 1));
+
+        // No alignment of the pattern can start before the first match of
+        // its first character, or end after the last match of its last
+        // character, so we only score the chars in between.
+        let window = match candidate.slice(first_offset..last_match_offset) {
+            Candidate::Utf8(str) => {
+                Candidate::Unicode(self.candidate_slab.alloc(str))
+            },
+            window => window,
+        };
+
+        let (score, last_col) = score(
+            pattern,
+            window,
+            prev_char,
+            opts,
+            &mut self.bonuses,
+            &mut self.match_scores,
+            &mut self.gap_scores,
+        )?;
+
+        if RANGES {
+            matched_ranges(
+                pattern.char_len(),
+                window,
+                last_col,
+                &self.match_scores,
+                &self.gap_scores,
+                start_byte_offset,
+                ranges,
+            );
+        }
+
+        Some(score)
+    }
+}
+
+/// The type of a character, used to compute the bonus of the character that
+/// follows it.
+///
+/// Only ASCII characters are classified: every non-ASCII character, including
+/// uppercase letters, digits, whitespace and punctuation, is treated as
+/// [`Lower`](Self::Lower). This means that e.g. the `'É'` in `"fooÉtat"`
+/// doesn't get the camel-case bonus, and that the character following a
+/// non-breaking space doesn't get the bonus for the start of a word.
+#[derive(Clone, Copy)]
+enum CharType {
+    /// The start of the candidate.
+    Empty,
+
+    /// A character that separates words, like a space or a path separator.
+    HardSeparator,
+
+    /// Any other ASCII punctuation, like `_`, `-` or `.`.
+    SoftSeparator,
+
+    /// An ASCII digit.
+    Number,
+
+    /// An ASCII uppercase letter.
+    Upper,
+
+    /// Any other character.
+    Lower,
+}
+
+impl CharType {
+    #[inline(always)]
+    fn of(ch: char) -> Self {
+        match ch {
+            ' ' | '/' | '\\' | '|' | '(' | ')' | '[' | ']' | '{' | '}' => {
+                Self::HardSeparator
+            },
+            _ if ch.is_ascii_punctuation() => Self::SoftSeparator,
+            '0'..='9' => Self::Number,
+            'A'..='Z' => Self::Upper,
+            _ => Self::Lower,
+        }
+    }
+}
+
+/// Returns the bonus of a character given the type of the character that
+/// precedes it.
+#[inline(always)]
+fn bonus(prev_type: CharType, ch_type: CharType) -> Score {
+    match (prev_type, ch_type) {
+        // Start of a word.
+        (CharType::Empty | CharType::HardSeparator, _) => BONUS_HEAD,
+
+        (CharType::SoftSeparator, _) => BONUS_BREAK,
+
+        (CharType::Lower | CharType::Number, CharType::Upper) => BONUS_CAMEL,
+
+        _ => 0,
+    }
+}
+
+/// Fills the score matrices of the given pattern and candidate, returning
+/// the score of the best alignment together with the column of its last
+/// matched character.
+///
+/// Both matrices have one row per pattern character and one column per
+/// candidate character.
+#[inline]
+fn score(
+    pattern: Pattern,
+    candidate: Candidate,
+    prev_char: Option<char>,
+    opts: CandidateOpts,
+    bonuses: &mut Vec<Score>,
+    match_scores: &mut Vec<Score>,
+    gap_scores: &mut Vec<Score>,
+) -> Option<(Score, usize)> {
+    let width = candidate.char_len();
+
+    let height = pattern.char_len();
+
+    bonuses.clear();
+
+    let mut prev_type = prev_char.map_or(CharType::Empty, CharType::of);
+
+    for ch in candidate.chars() {
+        let ch_type = CharType::of(ch);
+        bonuses.push(bonus(prev_type, ch_type));
+        prev_type = ch_type;
+    }
+
+    match_scores.clear();
+    match_scores.resize(width * height, UNREACHABLE);
+
+    gap_scores.clear();
+    gap_scores.resize(width * height, UNREACHABLE);
+
+    for (row, pattern_ch) in pattern.chars().enumerate() {
+        let row_start = row * width;
+
+        for (col, candidate_ch) in candidate.chars().enumerate() {
+            let cell = row_start + col;
+
+            if col > 0 {
+                gap_scores[cell] = extend(match_scores[cell - 1], GAP_START)
+                    .max(extend(gap_scores[cell - 1], GAP_EXTENSION));
+            }
+
+            if !(opts.char_eq)(pattern_ch, candidate_ch) {
+                continue;
+            }
+
+            let mut match_score = SCORE_MATCH;
+
+            match_score += if row == 0 {
+                bonuses[col] * BONUS_FIRST_CHAR_MULTIPLIER
+            } else {
+                bonuses[col]
+            };
+
+            if pattern_ch != candidate_ch {
+                match_score += PENALTY_CASE_MISMATCH;
+            }
+
+            match_scores[cell] = if row == 0 {
+                match_score
+            } else if col == 0 {
+                UNREACHABLE
+            } else {
+                let prev_cell = cell - width - 1;
+
+                let prev_score =
+                    extend(match_scores[prev_cell], BONUS_CONSECUTIVE)
+                        .max(gap_scores[prev_cell]);
+
+                extend(prev_score, match_score)
+            };
+        }
+    }
+
+    let last_row = &match_scores[(height - 1) * width..];
+
+    let (last_col, &score) = last_row
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|&(_, &score)| score)
+        .expect("the candidate is not empty");
+
+    (score != UNREACHABLE).then_some((score, last_col))
+}
+
+/// Adds `delta` to `score`, unless no alignment can reach the score.
+#[inline(always)]
+fn extend(score: Score, delta: Score) -> Score {
+    if score == UNREACHABLE {
+        UNREACHABLE
+    } else {
+        score + delta
+    }
+}
+
+/// Walks back the score matrices from the cell of the best alignment,
+/// inserting the byte ranges of the matched characters.
+#[inline]
+fn matched_ranges(
+    height: usize,
+    candidate: Candidate,
+    last_col: usize,
+    match_scores: &[Score],
+    gap_scores: &[Score],
+    start_byte_offset: usize,
+    ranges: &mut MatchedRanges,
+) {
+    let width = candidate.char_len();
+
+    let mut insert = |col: usize| {
+        let range = candidate.to_byte_range(col..col + 1);
+        ranges.insert(
+            start_byte_offset + range.start..start_byte_offset + range.end,
+        );
+    };
+
+    let mut col = last_col;
+
+    insert(col);
+
+    for row in (1..height).rev() {
+        let prev_row_start = (row - 1) * width;
+
+        let prev_col = col - 1;
+
+        let consecutive =
+            extend(match_scores[prev_row_start + prev_col], BONUS_CONSECUTIVE);
+
+        if consecutive != UNREACHABLE
+            && consecutive >= gap_scores[prev_row_start + prev_col]
+        {
+            col = prev_col;
+        } else {
+            // Follow the gap back to the character it started from.
+            col = prev_col;
+
+            loop {
+                let cell = prev_row_start + col;
+
+                let from_match = extend(match_scores[cell - 1], GAP_START);
+
+                col -= 1;
+
+                if from_match != UNREACHABLE
+                    && from_match
+                        >= extend(gap_scores[cell - 1], GAP_EXTENSION)
+                {
+                    break;
+                }
+            }
+        }
+
+        insert(col);
+    }
+}
@@ -0,0 +1,346 @@
+use super::{query::*, *};
+use crate::utils::CharEq;
+use crate::{Candidate, MatchedRanges};
+
+/// A score lower than any reachable one, used for the cells of the typo
+/// matrix that can't be reached by any alignment.
+const UNREACHABLE: Score = Score::MIN / 2;
+
+/// The last step of the best alignment ending in a cell of the typo matrix.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Step {
+    /// The cell can't be reached.
+    #[default]
+    Unreachable,
+
+    /// No character of the pattern has been aligned yet.
+    Start,
+
+    /// The pattern character was matched to the candidate character.
+    Match,
+
+    /// The pattern character was aligned to a different candidate character.
+    Substitution,
+
+    /// The last two characters of the pattern were aligned to the last two
+    /// characters of the candidate in reverse order.
+    Transposition,
+
+    /// The pattern character was skipped without consuming any character of
+    /// the candidate.
+    Insertion,
+
+    /// The candidate character was skipped.
+    Gap,
+}
+
+impl Step {
+    /// Returns `true` if the step consumed the candidate character of its
+    /// cell, in which case skipping the next one starts a new gap.
+    #[inline(always)]
+    fn is_ending(self) -> bool {
+        matches!(self, Self::Match | Self::Substitution | Self::Transposition)
+    }
+}
+
+/// A cell of the matrix filled when matching with typos, with one layer of
+/// cells per number of typos.
+#[derive(Clone, Copy, Default)]
+struct TypoCell {
+    /// The best score of an alignment of the first `i` characters of the
+    /// pattern within the first `j` characters of the candidate.
+    score: Score,
+
+    /// The last step of the alignment that got the best score, used to walk it
+    /// back when computing the ranges.
+    step: Step,
+
+    /// The number of consecutive matches ending in this cell, or zero if its
+    /// step is not a [`Step::Match`].
+    consecutive: usize,
+}
+
+/// The three-dimensional matrix used by [`fuzzy`], with a layer for every
+/// number of typos, a row for every prefix of the pattern and a column for
+/// every prefix of the candidate that an alignment can end at. Only the last
+/// two rows of every layer are kept if the matched ranges are not needed.
+#[derive(Clone, Default)]
+pub(super) struct TypoSlab {
+    cells: Vec<TypoCell>,
+}
+
+/// Scores a fuzzy pattern that is not a subsequence of the candidate by
+/// allowing up to `max_typos` substitutions, insertions or transpositions in
+/// the pattern, each one subtracting `typo_penalty` from the score.
+///
+/// Matched characters are scored like in the rest of fzf's v2 algorithm,
+/// while the characters of the candidate that were aligned to a typo don't
+/// earn any bonus and are not part of the matched ranges. The two characters
+/// of a transposition still earn the score of a match, since they both
+/// appear in the candidate.
+#[inline]
+pub(super) fn fuzzy<const RANGES: bool>(
+    slab: &mut TypoSlab,
+    pattern: Pattern,
+    mut candidate: CandidateV2,
+    char_eq: CharEq,
+    max_typos: usize,
+    typo_penalty: Score,
+    scheme: &Scheme,
+    ranges: &mut MatchedRanges,
+) -> Option<Score> {
+    let height = pattern.char_len() + 1;
+
+    let is_pattern_char = |candidate_ch: char| {
+        pattern.chars().any(|pattern_ch| char_eq(pattern_ch, candidate_ch))
+    };
+
+    let mut pattern_char_offsets = (0..candidate.char_len())
+        .filter(|&char_offset| is_pattern_char(candidate.char(char_offset)));
+
+    // The columns before the first character of the candidate that's in the
+    // pattern can only be reached by typos, so they all look the same, and
+    // the alignments ending after the last one can't beat the ones ending
+    // right after it. We only fill the columns in between.
+    let (first_col, last_col) = match pattern_char_offsets.next() {
+        Some(first) => {
+            let last = pattern_char_offsets.next_back().unwrap_or(first);
+            (first, last + 1)
+        },
+        None => (0, 0),
+    };
+
+    let width = last_col - first_col + 1;
+
+    // We only need the whole matrix to backtrack the matched ranges,
+    // otherwise every row only depends on the two previous ones.
+    let num_rows = if RANGES { height } else { 2 };
+
+    let idx = |typos: usize, row: usize, col: usize| {
+        let row = if RANGES { row } else { row % 2 };
+        (typos * num_rows + row) * width + col - first_col
+    };
+
+    slab.cells.clear();
+    slab.cells.resize((max_typos + 1) * num_rows * width, TypoCell::default());
+
+    let cells = &mut slab.cells;
+
+    for row in 0..height {
+        // A transposition looks two rows up in the previous layer, so we go
+        // through the layers backwards to read that row before overwriting
+        // it.
+        for typos in (0..=max_typos).rev() {
+            if row == 0 {
+                for col in first_col..=last_col {
+                    cells[idx(typos, 0, col)] = TypoCell {
+                        score: 0,
+                        step: Step::Start,
+                        consecutive: 0,
+                    };
+                }
+
+                continue;
+            }
+
+            // Every pattern character up to this row is a typo in the first
+            // column, a substitution if there are characters to substitute
+            // and an insertion otherwise.
+            cells[idx(typos, row, first_col)] = if typos >= row {
+                TypoCell {
+                    score: -(row as Score) * typo_penalty,
+                    step: if first_col > 0 {
+                        Step::Substitution
+                    } else {
+                        Step::Insertion
+                    },
+                    consecutive: 0,
+                }
+            } else {
+                TypoCell {
+                    score: UNREACHABLE,
+                    step: Step::Unreachable,
+                    consecutive: 0,
+                }
+            };
+
+            let pattern_ch = pattern.char(row - 1);
+
+            for col in first_col + 1..=last_col {
+                let mut best = TypoCell {
+                    score: UNREACHABLE,
+                    step: Step::Unreachable,
+                    consecutive: 0,
+                };
+
+                let mut consider = |score: Score, step, consecutive| {
+                    if score > best.score {
+                        best = TypoCell { score, step, consecutive };
+                    }
+                };
+
+                let candidate_ch = candidate.char(col - 1);
+
+                let is_match = char_eq(pattern_ch, candidate_ch);
+
+                let up_left = cells[idx(typos, row - 1, col - 1)];
+
+                if is_match && up_left.score > UNREACHABLE {
+                    let mut bonus = candidate.bonus_at(col - 1, scheme);
+
+                    let mut consecutive = up_left.consecutive + 1;
+
+                    if consecutive > 1 {
+                        let fb = candidate.bonus_at(col - consecutive, scheme);
+
+                        if bonus >= bonus::BOUNDARY && bonus > fb {
+                            consecutive = 1;
+                        } else {
+                            bonus = bonus::CONSECUTIVE.max(fb).max(bonus);
+                        }
+                    }
+
+                    if row == 1 {
+                        bonus *= bonus::FIRST_QUERY_CHAR_MULTIPLIER;
+                    }
+
+                    consider(
+                        up_left.score + bonus::MATCH + bonus,
+                        Step::Match,
+                        consecutive,
+                    );
+                }
+
+                if typos > 0 && !is_match {
+                    let prev = cells[idx(typos - 1, row - 1, col - 1)];
+
+                    if prev.score > UNREACHABLE {
+                        consider(
+                            prev.score - typo_penalty,
+                            Step::Substitution,
+                            0,
+                        );
+                    }
+
+                    // The character before the first column is not in the
+                    // pattern, so a transposition can't start there.
+                    let is_transposition = row > 1
+                        && col > first_col + 1
+                        && char_eq(pattern.char(row - 2), candidate_ch)
+                        && char_eq(pattern_ch, candidate.char(col - 2));
+
+                    if is_transposition {
+                        let prev = cells[idx(typos - 1, row - 2, col - 2)];
+
+                        // Both characters are in the candidate, just in the
+                        // wrong order, so they still count as matches.
+                        if prev.score > UNREACHABLE {
+                            consider(
+                                prev.score + 2 * bonus::MATCH - typo_penalty,
+                                Step::Transposition,
+                                0,
+                            );
+                        }
+                    }
+                }
+
+                let left = cells[idx(typos, row, col - 1)];
+
+                if left.score > UNREACHABLE {
+                    let penalty = if left.step.is_ending() {
+                        penalty::GAP_START
+                    } else {
+                        penalty::GAP_EXTENSION
+                    };
+
+                    consider(left.score - penalty, Step::Gap, 0);
+                }
+
+                if typos > 0 {
+                    let up = cells[idx(typos - 1, row - 1, col)];
+
+                    if up.score > UNREACHABLE {
+                        consider(up.score - typo_penalty, Step::Insertion, 0);
+                    }
+                }
+
+                cells[idx(typos, row, col)] = best;
+            }
+        }
+    }
+
+    // Keep the alignment with the highest score, where every typo has
+    // already been paid for with its penalty. Since we only replace the best
+    // alignment with a strictly better one, ties go to the alignment with
+    // fewer typos, and then to the one ending earlier in the candidate.
+    let mut best: Option<(Score, usize, usize)> = None;
+
+    for typos in 0..=max_typos {
+        for col in first_col..=last_col {
+            let score = cells[idx(typos, height - 1, col)].score;
+
+            if score > UNREACHABLE
+                && best.is_none_or(|(best_score, ..)| score > best_score)
+            {
+                best = Some((score, typos, col));
+            }
+        }
+    }
+
+    let (score, mut typos, mut col) = best?;
+
+    if RANGES {
+        let candidate = candidate.into_base();
+
+        let mut row = height - 1;
+
+        // The first column doesn't have any matches.
+        while col > first_col {
+            match cells[idx(typos, row, col)].step {
+                Step::Start => break,
+
+                Step::Match => {
+                    insert_char(candidate, col - 1, ranges);
+                    row -= 1;
+                    col -= 1;
+                },
+
+                Step::Substitution => {
+                    typos -= 1;
+                    row -= 1;
+                    col -= 1;
+                },
+
+                Step::Transposition => {
+                    typos -= 1;
+                    row -= 2;
+                    col -= 2;
+                },
+
+                Step::Insertion => {
+                    typos -= 1;
+                    row -= 1;
+                },
+
+                Step::Gap => col -= 1,
+
+                Step::Unreachable => unreachable!(),
+            }
+        }
+    }
+
+    Some(score)
+}
+
+/// Adds the byte range of the character at the given char offset to the
+/// ranges.
+#[inline(always)]
+fn insert_char(
+    candidate: Candidate,
+    char_offset: usize,
+    ranges: &mut MatchedRanges,
+) {
+    let start = candidate.to_byte_offset(char_offset);
+    let end = start + candidate.char(char_offset).len_utf8();
+    ranges.insert(start..end);
+}
@@ -0,0 +1,67 @@
+use core::cmp::Ordering;
+
+pub(super) type Score = f64;
+
+/// The fzy distance type.
+///
+/// This struct is returned by [`Fzy`](super::Fzy)'s
+/// [`Metric`](crate::Metric) implementation.
+#[derive(Debug, Clone, Copy)]
+pub struct FzyDistance(Score);
+
+impl PartialEq for FzyDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FzyDistance {}
+
+impl PartialOrd for FzyDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FzyDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // This makes the type act like a distance and not like a score.
+        other.0.total_cmp(&self.0)
+    }
+}
+
+impl Default for FzyDistance {
+    #[inline]
+    fn default() -> Self {
+        Self::from_score(0.0)
+    }
+}
+
+impl FzyDistance {
+    /// Creates a new [`FzyDistance`] from a score.
+    #[inline(always)]
+    pub(super) fn from_score(score: Score) -> Self {
+        Self(score)
+    }
+
+    /// Returns a score representation of the distance.
+    ///
+    /// This is not part of the public API and should not be relied upon.
+    ///
+    /// It's only used internally for testing and debugging purposes.
+    #[cfg(any(feature = "__into-score", feature = "__tests"))]
+    #[inline(always)]
+    pub fn into_score(self) -> Score {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for FzyDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        self.0
+    }
+}
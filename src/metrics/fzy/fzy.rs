@@ -0,0 +1,530 @@
+use core::ops::Range;
+
+use super::{score::*, *};
+use crate::utils::{self, CharEq};
+use crate::*;
+
+/// A metric that implements fzy's algorithm.
+///
+/// The [`Metric`] implementation of this struct produces the same scores and
+/// the same matched positions as fzy's `match` and `match_positions`.
+///
+/// A candidate matches the query if it contains all the characters of the
+/// query in the same order. Matches are then scored by finding the alignment
+/// of the query that maximizes the bonuses given to characters following a
+/// slash, a word separator or a dot, to capital letters following a
+/// lowercase one, and to consecutive characters, while penalizing the gaps
+/// between matched characters.
+///
+/// Just like in fzy, candidates that are equal to the query get the maximum
+/// score, while an empty query matches every candidate with the minimum
+/// score. Candidates longer than 1024 bytes are also matched with the
+/// minimum score, and no ranges are reported for them.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::fzy::Fzy;
+/// # use norm::Metric;
+/// let mut fzy = Fzy::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance =
+///     fzy.distance_and_ranges("amo", "app/models/foo", &mut ranges).unwrap();
+///
+/// assert_eq!(ranges, [0..1, 4..6]);
+///
+/// // "app/m/foo" is a worse match than "app/models/foo" since the "mo" in
+/// // "models" is consecutive.
+/// assert!(distance < fzy.distance("amo", "app/m/foo").unwrap());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "fzy")))]
+#[derive(Clone)]
+pub struct Fzy {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// The characters of the last query.
+    query_chars: Vec<char>,
+
+    /// The characters of the last non-ASCII candidate.
+    candidate_chars: Vec<char>,
+
+    /// The bonus of every character of the candidate.
+    bonus: Vec<Score>,
+
+    /// The best score of the query prefix ending with a match at a given
+    /// candidate character, also known as `D` in fzy.
+    match_scores: Vec<Score>,
+
+    /// The best score of the query prefix up to a given candidate
+    /// character, also known as `M` in fzy.
+    best_scores: Vec<Score>,
+
+    /// The matched char offset of every character of the query.
+    positions: Vec<usize>,
+}
+
+impl core::fmt::Debug for Fzy {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fzy")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Fzy {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            candidate_normalization: false,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            query_chars: Vec::new(),
+            candidate_chars: Vec::new(),
+            bonus: Vec::new(),
+            match_scores: Vec::new(),
+            best_scores: Vec::new(),
+            positions: Vec::new(),
+        }
+    }
+}
+
+impl Fzy {
+    /// Creates a new `Fzy`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzy::Fzy;
+    /// # use norm::Metric;
+    /// let mut fzy = Fzy::new();
+    ///
+    /// assert!(fzy.distance("foo", "ƒöö").is_none());
+    ///
+    /// fzy.set_candidate_normalization(true);
+    ///
+    /// assert!(fzy.distance("foo", "ƒöö").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is
+    /// [`CaseSensitivity::Insensitive`], which is what fzy uses.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::fzy::Fzy;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut fzy = Fzy::new();
+    ///
+    /// assert!(fzy.distance("Abc", "abc").is_some());
+    ///
+    /// fzy.set_case_sensitivity(CaseSensitivity::Smart);
+    ///
+    /// assert!(fzy.distance("Abc", "abc").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Scores the candidate like fzy's `match_positions`, returning `None` if
+    /// the query is not a subsequence of it. The byte ranges of the matched
+    /// characters are also inserted into `ranges` if `RANGES` is true.
+    #[inline]
+    fn distance<const RANGES: bool>(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<FzyDistance> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let char_eq =
+            utils::char_eq(is_case_sensitive, self.candidate_normalization);
+
+        // fzy's `has_match`.
+        let has_match = |candidate: Candidate| {
+            candidate
+                .find_subsequence(
+                    query.chars(),
+                    is_case_sensitive,
+                    char_eq,
+                    |_| {},
+                )
+                .is_some()
+        };
+
+        if query.is_empty() {
+            return Some(FzyDistance::from_score(MIN));
+        }
+
+        let candidate_byte_len = candidate.len();
+
+        let candidate = if candidate.is_ascii() {
+            let candidate = Candidate::Ascii(candidate.as_bytes());
+
+            if !has_match(candidate) {
+                return None;
+            }
+
+            candidate
+        } else {
+            // Without normalization we can filter out non-matches before
+            // decoding the candidate.
+            if !self.candidate_normalization
+                && !has_match(Candidate::Utf8(candidate))
+            {
+                return None;
+            }
+
+            self.candidate_chars.clear();
+            self.candidate_chars.extend(candidate.chars());
+
+            let candidate = Candidate::Unicode(&self.candidate_chars);
+
+            if self.candidate_normalization && !has_match(candidate) {
+                return None;
+            }
+
+            candidate
+        };
+
+        if candidate_byte_len > MATCH_MAX_LEN {
+            return Some(FzyDistance::from_score(MIN));
+        }
+
+        self.query_chars.clear();
+        self.query_chars.extend(query.chars());
+
+        let query_len = self.query_chars.len();
+
+        let candidate_len = candidate.char_len();
+
+        let ranges = &mut ranges.into();
+
+        if query_len == candidate_len {
+            if RANGES {
+                insert_ranges(0..candidate_len, candidate, ranges);
+            }
+
+            return Some(FzyDistance::from_score(MAX));
+        }
+
+        let score = compute_score::<RANGES>(
+            &self.query_chars,
+            candidate,
+            char_eq,
+            &mut self.bonus,
+            &mut self.match_scores,
+            &mut self.best_scores,
+        );
+
+        if RANGES {
+            positions(
+                query_len,
+                candidate_len,
+                &self.match_scores,
+                &self.best_scores,
+                &mut self.positions,
+            );
+
+            insert_ranges(self.positions.iter().copied(), candidate, ranges);
+        }
+
+        Some(FzyDistance::from_score(score))
+    }
+}
+
+impl Metric for Fzy {
+    type Query<'a> = &'a str;
+
+    type Distance = FzyDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let ranges = &mut Vec::new();
+        self.distance::<false>(query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        self.distance::<true>(query, candidate, ranges)
+    }
+}
+
+/// Returns the bonus of a candidate character given the character that
+/// precedes it, which is `'/'` for the first character of the candidate.
+///
+/// This is equivalent to fzy's `COMPUTE_BONUS`.
+#[inline(always)]
+fn bonus(prev_ch: char, ch: char) -> Score {
+    let separator_bonus = match prev_ch {
+        '/' => MATCH_SLASH,
+        '-' | '_' | ' ' => MATCH_WORD,
+        '.' => MATCH_DOT,
+        _ => 0.0,
+    };
+
+    match ch {
+        'a'..='z' | '0'..='9' => separator_bonus,
+        'A'..='Z' if prev_ch.is_ascii_lowercase() => MATCH_CAPITAL,
+        'A'..='Z' => separator_bonus,
+        _ => 0.0,
+    }
+}
+
+/// Fills the `D` and `M` matrices of fzy's algorithm, returning the score of
+/// the best alignment of the query in the candidate.
+///
+/// If `RANGES` is false only the last two rows of the matrices are kept,
+/// otherwise the whole matrices are kept to reconstruct the alignment.
+#[inline]
+fn compute_score<const RANGES: bool>(
+    query: &[char],
+    candidate: Candidate,
+    char_eq: CharEq,
+    bonus_buf: &mut Vec<Score>,
+    match_scores: &mut Vec<Score>,
+    best_scores: &mut Vec<Score>,
+) -> Score {
+    let width = candidate.char_len();
+
+    let height = if RANGES { query.len() } else { 2 };
+
+    bonus_buf.clear();
+
+    let mut prev_ch = '/';
+
+    for ch in candidate.chars() {
+        bonus_buf.push(bonus(prev_ch, ch));
+        prev_ch = ch;
+    }
+
+    match_scores.clear();
+    match_scores.resize(width * height, MIN);
+
+    best_scores.clear();
+    best_scores.resize(width * height, MIN);
+
+    for (row_idx, &query_ch) in query.iter().enumerate() {
+        let (row, prev_row) = if RANGES {
+            (row_idx, row_idx.saturating_sub(1))
+        } else {
+            (row_idx % 2, (row_idx + 1) % 2)
+        };
+
+        let (curr_d, last_d) = rows(match_scores, width, row, prev_row);
+
+        let (curr_m, last_m) = rows(best_scores, width, row, prev_row);
+
+        match_row(
+            query_ch,
+            row_idx,
+            row_idx == query.len() - 1,
+            candidate,
+            char_eq,
+            bonus_buf,
+            curr_d,
+            curr_m,
+            last_d,
+            last_m,
+        );
+    }
+
+    let last_row =
+        if RANGES { query.len() - 1 } else { (query.len() - 1) % 2 };
+
+    best_scores[last_row * width + width - 1]
+}
+
+/// Returns the `row`-th row of the matrix as a mutable slice, together with
+/// the `prev_row`-th row, which is empty if the two are the same.
+#[inline(always)]
+fn rows(
+    matrix: &mut [Score],
+    width: usize,
+    row: usize,
+    prev_row: usize,
+) -> (&mut [Score], &[Score]) {
+    if row == prev_row {
+        (&mut matrix[row * width..(row + 1) * width], &[])
+    } else if prev_row < row {
+        let (before, after) = matrix.split_at_mut(row * width);
+        (&mut after[..width], &before[prev_row * width..][..width])
+    } else {
+        let (before, after) = matrix.split_at_mut(prev_row * width);
+        (&mut before[row * width..][..width], &after[..width])
+    }
+}
+
+/// Computes a row of the `D` and `M` matrices from the previous one.
+///
+/// This is equivalent to fzy's `match_row`.
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+fn match_row(
+    query_ch: char,
+    row_idx: usize,
+    is_last_row: bool,
+    candidate: Candidate,
+    char_eq: CharEq,
+    bonus: &[Score],
+    curr_d: &mut [Score],
+    curr_m: &mut [Score],
+    last_d: &[Score],
+    last_m: &[Score],
+) {
+    let mut prev_score = MIN;
+
+    let gap_score = if is_last_row { GAP_TRAILING } else { GAP_INNER };
+
+    let mut prev_d = MIN;
+
+    let mut prev_m = MIN;
+
+    for (col_idx, candidate_ch) in candidate.chars().enumerate() {
+        if char_eq(query_ch, candidate_ch) {
+            let mut score = MIN;
+
+            if row_idx == 0 {
+                score = (col_idx as Score * GAP_LEADING) + bonus[col_idx];
+            } else if col_idx > 0 {
+                score = Score::max(
+                    prev_m + bonus[col_idx],
+                    // A consecutive match doesn't stack with the bonus.
+                    prev_d + MATCH_CONSECUTIVE,
+                );
+            }
+
+            curr_d[col_idx] = score;
+            prev_score = Score::max(score, prev_score + gap_score);
+            curr_m[col_idx] = prev_score;
+        } else {
+            curr_d[col_idx] = MIN;
+            prev_score += gap_score;
+            curr_m[col_idx] = prev_score;
+        }
+
+        if row_idx > 0 {
+            prev_d = last_d[col_idx];
+            prev_m = last_m[col_idx];
+        }
+    }
+}
+
+/// Walks back the full `D` and `M` matrices to find the char offsets of the
+/// candidate characters matched by the best alignment.
+///
+/// When there are multiple optimal alignments this picks the one whose
+/// characters come latest in the candidate, just like fzy's
+/// `match_positions`.
+#[inline]
+fn positions(
+    height: usize,
+    width: usize,
+    match_scores: &[Score],
+    best_scores: &[Score],
+    positions: &mut Vec<usize>,
+) {
+    positions.clear();
+    positions.resize(height, 0);
+
+    let d = |row: usize, col: usize| match_scores[row * width + col];
+
+    let m = |row: usize, col: usize| best_scores[row * width + col];
+
+    let mut is_match_required = false;
+
+    let mut col = width;
+
+    for row in (0..height).rev() {
+        while col > 0 {
+            col -= 1;
+
+            if d(row, col) != MIN
+                && (is_match_required || d(row, col) == m(row, col))
+            {
+                // If this score was determined using the consecutive bonus,
+                // the previous character must be a match as well.
+                is_match_required = row > 0
+                    && col > 0
+                    && m(row, col) == d(row - 1, col - 1) + MATCH_CONSECUTIVE;
+
+                positions[row] = col;
+
+                break;
+            }
+        }
+    }
+}
+
+/// Inserts the byte ranges of the given sorted char offsets, merging
+/// consecutive offsets into a single range.
+#[inline]
+fn insert_ranges<I>(
+    char_offsets: I,
+    candidate: Candidate,
+    ranges: &mut MatchedRanges,
+) where
+    I: IntoIterator<Item = usize>,
+{
+    let mut current: Option<Range<usize>> = None;
+
+    for offset in char_offsets {
+        match &mut current {
+            Some(range) if range.end == offset => range.end += 1,
+
+            _ => {
+                if let Some(range) = current.take() {
+                    ranges.insert(candidate.to_byte_range(range));
+                }
+                current = Some(offset..offset + 1);
+            },
+        }
+    }
+
+    if let Some(range) = current {
+        ranges.insert(candidate.to_byte_range(range));
+    }
+}
@@ -0,0 +1,58 @@
+//! A metric implementing fzy's algorithm.
+//!
+//! This module contains the [`Fzy`] metric, which was ported from [fzy], a
+//! fuzzy-finder for the terminal whose scoring is also used by many editor
+//! plugins. Any discrepancy between our implementation and fzy's should be
+//! considered a bug.
+//!
+//! [fzy]: https://github.com/jhawthorn/fzy
+
+mod distance;
+mod fzy;
+
+pub use distance::FzyDistance;
+use distance::*;
+pub use fzy::Fzy;
+
+#[doc(hidden)]
+pub mod score {
+    //! The scores used by fzy, as defined in its `config.def.h`.
+
+    use super::*;
+
+    /// Added for every candidate character before the first match.
+    pub const GAP_LEADING: Score = -0.005;
+
+    /// Added for every candidate character after the last match.
+    pub const GAP_TRAILING: Score = -0.005;
+
+    /// Added for every candidate character between two matches.
+    pub const GAP_INNER: Score = -0.01;
+
+    /// The bonus of a match right after another match.
+    pub const MATCH_CONSECUTIVE: Score = 1.0;
+
+    /// The bonus of a match right after a `/`.
+    pub const MATCH_SLASH: Score = 0.9;
+
+    /// The bonus of a match right after a `-`, a `_` or a space.
+    pub const MATCH_WORD: Score = 0.8;
+
+    /// The bonus of an uppercase match right after a lowercase character.
+    pub const MATCH_CAPITAL: Score = 0.7;
+
+    /// The bonus of a match right after a `.`.
+    pub const MATCH_DOT: Score = 0.6;
+
+    /// The score of a candidate that matches the query, but that fzy doesn't
+    /// score, i.e. every candidate if the query is empty and candidates that
+    /// are longer than [`MATCH_MAX_LEN`](super::MATCH_MAX_LEN).
+    pub const MIN: Score = Score::NEG_INFINITY;
+
+    /// The score of a candidate that's equal to the query.
+    pub const MAX: Score = Score::INFINITY;
+}
+
+/// The maximum length in bytes of a candidate that fzy will score.
+#[doc(hidden)]
+pub const MATCH_MAX_LEN: usize = 1024;
@@ -0,0 +1,32 @@
+/// The Hamming distance type.
+///
+/// This struct is returned by [`Hamming`](super::Hamming)'s
+/// [`Metric`](crate::Metric) implementation.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct HammingDistance(usize);
+
+impl HammingDistance {
+    /// Creates a new [`HammingDistance`] from a number of mismatches.
+    #[inline(always)]
+    pub(super) fn from_mismatches(mismatches: usize) -> Self {
+        Self(mismatches)
+    }
+
+    /// Returns the number of characters of the query that differ from the
+    /// character at the same position in the best window of the candidate.
+    #[inline(always)]
+    pub fn mismatches(self) -> usize {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for HammingDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        // Goes from 1.0 for an exact window towards 0.0 as the number of
+        // mismatches grows.
+        1.0 / (1 + self.0) as f64
+    }
+}
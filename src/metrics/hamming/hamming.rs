@@ -0,0 +1,294 @@
+use core::ops::Range;
+
+use super::*;
+use crate::utils::{self, CharEq};
+use crate::{CaseSensitivity, MatchedRanges, Metric};
+
+/// A metric that slides the query over the candidate and computes the
+/// [Hamming distance][hamming] between the query and every window of the
+/// candidate having the same length.
+///
+/// The Hamming distance between two strings of the same length is the number
+/// of positions at which their characters differ. The distance between a
+/// query and a candidate is the minimum Hamming distance between the query
+/// and any window of the candidate, which makes this metric well suited for
+/// strings like commit hashes, serial numbers or license keys, where the
+/// characters of the query are expected to appear at fixed positions.
+///
+/// Candidates shorter than the query never match. The range returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) is the one of the
+/// best window, and if more than one window has the minimum distance the
+/// leftmost one is chosen.
+///
+/// ```rust
+/// # use norm::hamming::Hamming;
+/// # use norm::Metric;
+/// let mut hamming = Hamming::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance = hamming
+///     .distance_and_ranges("1a2b3c", "commit 1a2b4c9", &mut ranges)
+///     .unwrap();
+///
+/// assert_eq!(distance.mismatches(), 1);
+///
+/// assert_eq!(ranges, [7..13]);
+///
+/// assert!(hamming.distance("1a2b3c", "1a2b").is_none());
+/// ```
+///
+/// [hamming]: https://en.wikipedia.org/wiki/Hamming_distance
+#[cfg_attr(docsrs, doc(cfg(feature = "hamming")))]
+#[derive(Clone, Default)]
+pub struct Hamming {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// The maximum number of mismatches in the best window of a candidate, or
+    /// `None` if there's no limit.
+    max_mismatches: Option<usize>,
+
+    /// The characters of the last query.
+    query_chars: Vec<char>,
+
+    /// The characters of the last candidate.
+    candidate_chars: Vec<char>,
+}
+
+impl core::fmt::Debug for Hamming {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Hamming")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .field("max_mismatches", &self.max_mismatches)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Hamming {
+    /// Creates a new `Hamming`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::hamming::Hamming;
+    /// # use norm::Metric;
+    /// let mut hamming = Hamming::new();
+    ///
+    /// assert_eq!(hamming.distance("foo", "ƒöö").unwrap().mismatches(), 3);
+    ///
+    /// hamming.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(hamming.distance("foo", "ƒöö").unwrap().mismatches(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::hamming::Hamming;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut hamming = Hamming::new();
+    ///
+    /// assert_eq!(hamming.distance("abc", "ABC").unwrap().mismatches(), 0);
+    ///
+    /// hamming.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert_eq!(hamming.distance("abc", "ABC").unwrap().mismatches(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets the maximum number of mismatches the best window of a candidate
+    /// can have to be considered a match. The default is `None`, i.e. every
+    /// candidate at least as long as the query is a match.
+    ///
+    /// Setting a maximum also speeds up the distance calculation, since a
+    /// window can be discarded as soon as it has too many mismatches.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::hamming::Hamming;
+    /// # use norm::Metric;
+    /// let mut hamming = Hamming::new();
+    ///
+    /// assert!(hamming.distance("karolin", "kathrin").is_some());
+    ///
+    /// hamming.set_max_mismatches(Some(2));
+    ///
+    /// assert!(hamming.distance("karolin", "kathrin").is_none());
+    /// assert!(hamming.distance("karolin", "kerstin").is_none());
+    /// assert!(hamming.distance("karolin", "karoline").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_max_mismatches(
+        &mut self,
+        max_mismatches: Option<usize>,
+    ) -> &mut Self {
+        self.max_mismatches = max_mismatches;
+        self
+    }
+
+    /// Returns the minimum number of mismatches between the query and a
+    /// window of the candidate, together with the char offset of the first
+    /// window with that number of mismatches.
+    #[inline]
+    fn best_window(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<(HammingDistance, usize)> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let char_eq =
+            utils::char_eq(is_case_sensitive, self.candidate_normalization);
+
+        self.query_chars.clear();
+        self.query_chars.extend(query.chars());
+
+        self.candidate_chars.clear();
+        self.candidate_chars.extend(candidate.chars());
+
+        let query_len = self.query_chars.len();
+
+        // An empty query matches every candidate, including empty ones which
+        // have no windows at all.
+        if query_len == 0 {
+            return Some((HammingDistance::from_mismatches(0), 0));
+        }
+
+        if self.candidate_chars.len() < query_len {
+            return None;
+        }
+
+        let max_mismatches = self.max_mismatches.unwrap_or(usize::MAX);
+
+        // The number of mismatches a window must stay under to be better
+        // than the best one found so far.
+        let mut limit = max_mismatches.saturating_add(1);
+
+        let mut best = None;
+
+        for (window_idx, window) in
+            self.candidate_chars.windows(query_len).enumerate()
+        {
+            let mismatches =
+                mismatches(&self.query_chars, window, char_eq, limit);
+
+            if mismatches < limit {
+                best = Some((mismatches, window_idx));
+
+                if mismatches == 0 {
+                    break;
+                }
+
+                limit = mismatches;
+            }
+        }
+
+        best.map(|(mismatches, window_idx)| {
+            (HammingDistance::from_mismatches(mismatches), window_idx)
+        })
+    }
+}
+
+impl Metric for Hamming {
+    type Query<'a> = &'a str;
+
+    type Distance = HammingDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.best_window(query, candidate).map(|(distance, _)| distance)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let (distance, window_idx) = self.best_window(query, candidate)?;
+
+        let query_len = self.query_chars.len();
+
+        if query_len == 0 {
+            return Some(distance);
+        }
+
+        let mut char_offsets =
+            candidate.char_indices().map(|(offset, _)| offset);
+
+        let start = char_offsets.nth(window_idx).unwrap_or(candidate.len());
+
+        let end = char_offsets.nth(query_len - 1).unwrap_or(candidate.len());
+
+        MatchedRanges::from(ranges).insert(start..end);
+
+        Some(distance)
+    }
+}
+
+/// Counts the positions at which the characters of the query and of the
+/// window differ, stopping as soon as the count reaches `limit`.
+#[inline(always)]
+fn mismatches(
+    query: &[char],
+    window: &[char],
+    char_eq: CharEq,
+    limit: usize,
+) -> usize {
+    let mut mismatches = 0;
+
+    for (&query_ch, &candidate_ch) in query.iter().zip(window) {
+        if !char_eq(query_ch, candidate_ch) {
+            mismatches += 1;
+
+            if mismatches == limit {
+                break;
+            }
+        }
+    }
+
+    mismatches
+}
@@ -0,0 +1,9 @@
+//! The Hamming distance metric.
+//!
+//! See the documentation of [`Hamming`] for more information.
+
+mod distance;
+mod hamming;
+
+pub use distance::HammingDistance;
+pub use hamming::Hamming;
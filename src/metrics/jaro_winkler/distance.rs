@@ -0,0 +1,57 @@
+use core::cmp::Ordering;
+
+/// The Jaro-Winkler distance type.
+///
+/// This struct is returned by [`JaroWinkler`](super::JaroWinkler)'s
+/// [`Metric`](crate::Metric) implementation. It wraps a similarity between
+/// `0.0` and `1.0`, and it's ordered by descending similarity so that the most
+/// similar candidates come first when sorting.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JaroWinklerDistance(f64);
+
+impl PartialEq for JaroWinklerDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for JaroWinklerDistance {}
+
+impl PartialOrd for JaroWinklerDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JaroWinklerDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // This makes the type act like a distance and not like a similarity.
+        other.0.total_cmp(&self.0)
+    }
+}
+
+impl JaroWinklerDistance {
+    /// Creates a new [`JaroWinklerDistance`] from a similarity.
+    #[inline(always)]
+    pub(super) fn from_similarity(similarity: f64) -> Self {
+        debug_assert!((0.0..=1.0).contains(&similarity));
+        Self(similarity)
+    }
+
+    /// Returns the Jaro-Winkler similarity between the query and the
+    /// candidate, from `0.0` (nothing in common) to `1.0` (identical).
+    #[inline(always)]
+    pub fn similarity(self) -> f64 {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for JaroWinklerDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        self.0
+    }
+}
@@ -0,0 +1,405 @@
+use core::ops::Range;
+
+use super::*;
+use crate::utils::{self, CharEq};
+use crate::{CaseSensitivity, MatchedRanges, Metric};
+
+/// The maximum number of characters of the common prefix that can boost the
+/// similarity, as in Winkler's original definition.
+const MAX_PREFIX_LEN: usize = 4;
+
+/// A metric that implements the [Jaro-Winkler similarity][jw].
+///
+/// The Jaro similarity between a query and a candidate is based on the
+/// number of characters they have in common, i.e. equal characters that are
+/// not further apart than half the length of the longer string, and on how
+/// many of those characters appear in a different order. The Winkler
+/// variant then boosts the similarity of strings sharing a common prefix of
+/// up to 4 characters, which works well for short strings like names, where
+/// typos are less likely to be found at the start.
+///
+/// A candidate doesn't match the query if they have no characters in
+/// common, and the ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// characters of the candidate that were matched to a character of the
+/// query.
+///
+/// ```rust
+/// # use norm::jaro_winkler::JaroWinkler;
+/// # use norm::Metric;
+/// let mut jw = JaroWinkler::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance =
+///     jw.distance_and_ranges("martha", "marhta", &mut ranges).unwrap();
+///
+/// assert!((distance.similarity() - 0.961).abs() < 0.001);
+///
+/// assert_eq!(ranges, [0..6]);
+///
+/// // Sorting by distance puts the most similar candidates first.
+/// let dwayne = jw.distance("dwayne", "duane").unwrap();
+/// let dixon = jw.distance("dwayne", "dixon").unwrap();
+///
+/// assert!(dwayne < dixon);
+/// ```
+///
+/// [jw]: https://en.wikipedia.org/wiki/Jaro%E2%80%93Winkler_distance
+#[cfg_attr(docsrs, doc(cfg(feature = "jaro-winkler")))]
+#[derive(Clone)]
+pub struct JaroWinkler {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// How much the similarity is boosted for every character of the common
+    /// prefix, between `0.0` and `0.25`.
+    prefix_scale: f64,
+
+    /// The Jaro similarity a candidate has to exceed for its common prefix with
+    /// the query to boost the similarity.
+    boost_threshold: f64,
+
+    /// The characters of the last query.
+    query_chars: Vec<char>,
+
+    /// The characters of the last candidate.
+    candidate_chars: Vec<char>,
+
+    /// Whether each character of the last query was matched to a character
+    /// of the candidate.
+    query_matches: Vec<bool>,
+
+    /// Whether each character of the last candidate was matched to a
+    /// character of the query.
+    candidate_matches: Vec<bool>,
+}
+
+impl core::fmt::Debug for JaroWinkler {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("JaroWinkler")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .field("prefix_scale", &self.prefix_scale)
+            .field("boost_threshold", &self.boost_threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for JaroWinkler {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            candidate_normalization: false,
+            case_sensitivity: CaseSensitivity::default(),
+            prefix_scale: 0.1,
+            boost_threshold: 0.7,
+            query_chars: Vec::new(),
+            candidate_chars: Vec::new(),
+            query_matches: Vec::new(),
+            candidate_matches: Vec::new(),
+        }
+    }
+}
+
+impl JaroWinkler {
+    /// Creates a new `JaroWinkler`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::jaro_winkler::JaroWinkler;
+    /// # use norm::Metric;
+    /// let mut jw = JaroWinkler::new();
+    ///
+    /// assert!(jw.distance("foo", "ƒöö").is_none());
+    ///
+    /// jw.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(jw.distance("foo", "ƒöö").unwrap().similarity(), 1.0);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::jaro_winkler::JaroWinkler;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut jw = JaroWinkler::new();
+    ///
+    /// assert_eq!(jw.distance("abc", "ABC").unwrap().similarity(), 1.0);
+    ///
+    /// jw.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert!(jw.distance("abc", "ABC").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets how much the similarity is boosted for every character of the
+    /// common prefix between the query and the candidate, up to a maximum of
+    /// 4 characters. The default is `0.1`, and setting it to `0.0` turns the
+    /// metric into the plain Jaro similarity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scale is not between `0.0` and `0.25`, since higher
+    /// values could result in a similarity greater than `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::jaro_winkler::JaroWinkler;
+    /// # use norm::Metric;
+    /// let mut jw = JaroWinkler::new();
+    ///
+    /// let winkler = jw.distance("martha", "marhta").unwrap().similarity();
+    ///
+    /// jw.set_prefix_scale(0.0);
+    ///
+    /// let jaro = jw.distance("martha", "marhta").unwrap().similarity();
+    ///
+    /// assert!(jaro < winkler);
+    /// ```
+    #[inline(always)]
+    pub fn set_prefix_scale(&mut self, prefix_scale: f64) -> &mut Self {
+        assert!(
+            (0.0..=0.25).contains(&prefix_scale),
+            "the prefix scale must be between 0.0 and 0.25, got \
+             {prefix_scale}"
+        );
+        self.prefix_scale = prefix_scale;
+        self
+    }
+
+    /// Sets the Jaro similarity above which the common prefix starts
+    /// boosting the similarity. The default is `0.7`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::jaro_winkler::JaroWinkler;
+    /// # use norm::Metric;
+    /// let mut jw = JaroWinkler::new();
+    ///
+    /// let boosted = jw.distance("martha", "marhta").unwrap().similarity();
+    ///
+    /// jw.set_boost_threshold(1.0);
+    ///
+    /// let not_boosted = jw.distance("martha", "marhta").unwrap().similarity();
+    ///
+    /// assert!(not_boosted < boosted);
+    /// ```
+    #[inline(always)]
+    pub fn set_boost_threshold(&mut self, boost_threshold: f64) -> &mut Self {
+        self.boost_threshold = boost_threshold;
+        self
+    }
+
+    /// Computes the Jaro-Winkler similarity between the query and the
+    /// candidate, leaving which characters of the candidate were matched in
+    /// `self.candidate_matches`.
+    #[inline]
+    fn similarity(&mut self, query: &str, candidate: &str) -> Option<f64> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let char_eq =
+            utils::char_eq(is_case_sensitive, self.candidate_normalization);
+
+        self.query_chars.clear();
+        self.query_chars.extend(query.chars());
+
+        self.candidate_chars.clear();
+        self.candidate_chars.extend(candidate.chars());
+
+        if self.query_chars.is_empty() && self.candidate_chars.is_empty() {
+            self.candidate_matches.clear();
+            return Some(1.0);
+        }
+
+        let matches = find_matches(
+            &self.query_chars,
+            &self.candidate_chars,
+            char_eq,
+            &mut self.query_matches,
+            &mut self.candidate_matches,
+        );
+
+        if matches == 0 {
+            return None;
+        }
+
+        let transpositions = count_transpositions(
+            &self.query_chars,
+            &self.candidate_chars,
+            char_eq,
+            &self.query_matches,
+            &self.candidate_matches,
+        );
+
+        let m = matches as f64;
+
+        let jaro = (m / self.query_chars.len() as f64
+            + m / self.candidate_chars.len() as f64
+            + (matches - transpositions) as f64 / m)
+            / 3.0;
+
+        if jaro <= self.boost_threshold {
+            return Some(jaro);
+        }
+
+        let prefix_len = self
+            .query_chars
+            .iter()
+            .zip(&self.candidate_chars)
+            .take(MAX_PREFIX_LEN)
+            .take_while(|&(&query_ch, &candidate_ch)| {
+                char_eq(query_ch, candidate_ch)
+            })
+            .count();
+
+        let similarity =
+            jaro + prefix_len as f64 * self.prefix_scale * (1.0 - jaro);
+
+        // Guard against rounding errors pushing the similarity above 1.
+        Some(similarity.min(1.0))
+    }
+}
+
+impl Metric for JaroWinkler {
+    type Query<'a> = &'a str;
+
+    type Distance = JaroWinklerDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.similarity(query, candidate)
+            .map(JaroWinklerDistance::from_similarity)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let similarity = self.similarity(query, candidate)?;
+
+        let mut ranges = MatchedRanges::from(ranges);
+
+        for ((offset, ch), &is_match) in
+            candidate.char_indices().zip(&self.candidate_matches)
+        {
+            if is_match {
+                ranges.insert(offset..offset + ch.len_utf8());
+            }
+        }
+
+        Some(JaroWinklerDistance::from_similarity(similarity))
+    }
+}
+
+/// Matches every character of the query to the first unmatched equal
+/// character of the candidate that's within the match window, returning the
+/// number of matched characters.
+#[inline]
+fn find_matches(
+    query: &[char],
+    candidate: &[char],
+    char_eq: CharEq,
+    query_matches: &mut Vec<bool>,
+    candidate_matches: &mut Vec<bool>,
+) -> usize {
+    query_matches.clear();
+    query_matches.resize(query.len(), false);
+
+    candidate_matches.clear();
+    candidate_matches.resize(candidate.len(), false);
+
+    let window = (query.len().max(candidate.len()) / 2).saturating_sub(1);
+
+    let mut matches = 0;
+
+    for (query_idx, &query_ch) in query.iter().enumerate() {
+        let start = query_idx.saturating_sub(window);
+
+        let end = (query_idx + window + 1).min(candidate.len());
+
+        for candidate_idx in start..end {
+            if !candidate_matches[candidate_idx]
+                && char_eq(query_ch, candidate[candidate_idx])
+            {
+                query_matches[query_idx] = true;
+                candidate_matches[candidate_idx] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+
+    matches
+}
+
+/// Returns half the number of matched characters that appear in a different
+/// order in the query and in the candidate.
+#[inline]
+fn count_transpositions(
+    query: &[char],
+    candidate: &[char],
+    char_eq: CharEq,
+    query_matches: &[bool],
+    candidate_matches: &[bool],
+) -> usize {
+    let matched_candidate_chars = candidate
+        .iter()
+        .zip(candidate_matches)
+        .filter_map(|(&ch, &is_match)| is_match.then_some(ch));
+
+    let out_of_order = query
+        .iter()
+        .zip(query_matches)
+        .filter_map(|(&ch, &is_match)| is_match.then_some(ch))
+        .zip(matched_candidate_chars)
+        .filter(|&(query_ch, candidate_ch)| !char_eq(query_ch, candidate_ch))
+        .count();
+
+    out_of_order / 2
+}
@@ -0,0 +1,9 @@
+//! The Jaro-Winkler similarity metric.
+//!
+//! See the documentation of [`JaroWinkler`] for more information.
+
+mod distance;
+mod jaro_winkler;
+
+pub use distance::JaroWinklerDistance;
+pub use jaro_winkler::JaroWinkler;
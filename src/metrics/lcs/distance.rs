@@ -0,0 +1,119 @@
+use core::cmp::Ordering;
+
+/// The ways an [`LcsDistance`] can be computed from the length of the longest
+/// common subsequence.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LcsMode {
+    /// Candidates are ordered by the length of their longest common
+    /// subsequence with the query, with longer subsequences coming first.
+    #[default]
+    Length,
+
+    /// Candidates are ordered by `1 - 2 * LCS / (|query| + |candidate|)`,
+    /// where the lengths are measured in characters. This is `0.0` for
+    /// identical strings and `1.0` for strings with nothing in common, and it
+    /// favors candidates whose length is closer to the query's.
+    Normalized,
+}
+
+/// The LCS distance type.
+///
+/// This struct is returned by [`Lcs`](super::Lcs)'s
+/// [`Metric`](crate::Metric) implementation. How two distances are ordered
+/// depends on the [`LcsMode`] of the metric that produced them, and it only
+/// makes sense to compare distances produced with the same mode. Comparing
+/// distances produced with different modes is still consistent, but the
+/// result is meaningless.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LcsDistance {
+    /// The length of the longest common subsequence, in characters.
+    lcs_len: usize,
+
+    /// The sum of the character lengths of the query and the candidate.
+    total_len: usize,
+
+    /// The mode of the metric that produced this distance.
+    mode: LcsMode,
+
+    /// The value distances are ordered by, computed from the other fields
+    /// according to the mode when the distance is created: the negated
+    /// length of the LCS in [`LcsMode::Length`] (so that longer subsequences
+    /// come first), and the [normalized](Self::normalized) distance in
+    /// [`LcsMode::Normalized`].
+    key: f64,
+}
+
+impl PartialEq for LcsDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for LcsDistance {}
+
+impl PartialOrd for LcsDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LcsDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.total_cmp(&other.key)
+    }
+}
+
+impl LcsDistance {
+    /// Creates a new [`LcsDistance`].
+    #[inline(always)]
+    pub(super) fn new(
+        lcs_len: usize,
+        total_len: usize,
+        mode: LcsMode,
+    ) -> Self {
+        debug_assert!(2 * lcs_len <= total_len);
+
+        let mut this = Self { lcs_len, total_len, mode, key: 0.0 };
+
+        this.key = match mode {
+            // This makes the type act like a distance and not like a length.
+            // Subtracting from `0.0` avoids a `-0.0` key, which `total_cmp`
+            // would order before the `0.0` of a default distance.
+            LcsMode::Length => 0.0 - lcs_len as f64,
+            LcsMode::Normalized => this.normalized(),
+        };
+
+        this
+    }
+
+    /// Returns the length of the longest common subsequence between the
+    /// query and the candidate, in characters.
+    #[inline(always)]
+    pub fn lcs_len(self) -> usize {
+        self.lcs_len
+    }
+
+    /// Returns `1 - 2 * LCS / (|query| + |candidate|)`, going from `0.0` for
+    /// identical strings to `1.0` for strings with nothing in common.
+    #[inline(always)]
+    pub fn normalized(self) -> f64 {
+        if self.total_len == 0 {
+            return 0.0;
+        }
+
+        1.0 - (2 * self.lcs_len) as f64 / self.total_len as f64
+    }
+}
+
+impl crate::combinators::Relevance for LcsDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        match self.mode {
+            LcsMode::Length => self.lcs_len as f64,
+            LcsMode::Normalized => 1.0 - self.normalized(),
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use core::ops::Range;
+
+use super::*;
+use crate::matrix::{Matrix, MatrixCell, MatrixSlab};
+use crate::utils::{self, CharEq};
+use crate::{CaseSensitivity, MatchedRanges, Metric};
+
+/// A metric that implements the [longest common subsequence][lcs].
+///
+/// The longest common subsequence between a query and a candidate is the
+/// longest sequence of characters that appears in both of them in the same
+/// order, but not necessarily contiguously. For example, the longest common
+/// subsequence between `"abcde"` and `"ace"` is `"ace"`, while the one between
+/// `"abc"` and `"bca"` is `"bc"`.
+///
+/// The distance can either be the length of the subsequence, or the
+/// normalized `1 - 2 * LCS / (|query| + |candidate|)`, depending on the
+/// [`LcsMode`] set with [`set_mode`](Lcs::set_mode).
+///
+/// A candidate doesn't match a non-empty query if they have no characters in
+/// common, and the ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// characters of the candidate that are part of the subsequence.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::lcs::Lcs;
+/// # use norm::Metric;
+/// let mut lcs = Lcs::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance =
+///     lcs.distance_and_ranges("ace", "abcde", &mut ranges).unwrap();
+///
+/// assert_eq!(distance.lcs_len(), 3);
+///
+/// assert_eq!(ranges, [0..1, 2..3, 4..5]);
+///
+/// // Sorting by distance puts the longest subsequences first.
+/// assert!(distance < lcs.distance("ace", "abd").unwrap());
+/// ```
+///
+/// [lcs]: https://en.wikipedia.org/wiki/Longest_common_subsequence
+#[cfg_attr(docsrs, doc(cfg(feature = "lcs")))]
+#[derive(Clone)]
+pub struct Lcs {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// How the length of the longest common subsequence is turned into a
+    /// distance.
+    mode: LcsMode,
+
+    /// The characters of the last query.
+    query_chars: Vec<char>,
+
+    /// The characters of the last candidate.
+    candidate_chars: Vec<char>,
+
+    /// The slab backing the dynamic programming table.
+    matrix_slab: MatrixSlab<usize>,
+
+    /// The char offsets of the characters of the candidate that are part of
+    /// the last subsequence, in ascending order.
+    matched_indices: Vec<usize>,
+}
+
+impl core::fmt::Debug for Lcs {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Lcs")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Lcs {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            candidate_normalization: false,
+            case_sensitivity: CaseSensitivity::default(),
+            mode: LcsMode::default(),
+            query_chars: Vec::new(),
+            candidate_chars: Vec::new(),
+            matrix_slab: MatrixSlab::default(),
+            matched_indices: Vec::new(),
+        }
+    }
+}
+
+impl Lcs {
+    /// Creates a new `Lcs`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::lcs::Lcs;
+    /// # use norm::Metric;
+    /// let mut lcs = Lcs::new();
+    ///
+    /// assert!(lcs.distance("foo", "ƒöö").is_none());
+    ///
+    /// lcs.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(lcs.distance("foo", "ƒöö").unwrap().lcs_len(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::lcs::Lcs;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut lcs = Lcs::new();
+    ///
+    /// assert_eq!(lcs.distance("abc", "ABC").unwrap().lcs_len(), 3);
+    ///
+    /// lcs.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert!(lcs.distance("abc", "ABC").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets how the distance is computed from the length of the longest
+    /// common subsequence. The default is [`LcsMode::Length`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::lcs::{Lcs, LcsMode};
+    /// # use norm::Metric;
+    /// let mut lcs = Lcs::new();
+    ///
+    /// // Both candidates share "abc" with the query.
+    /// assert_eq!(lcs.distance("abc", "abc"), lcs.distance("abc", "abcdef"));
+    ///
+    /// lcs.set_mode(LcsMode::Normalized);
+    ///
+    /// // The shorter candidate is now a better match.
+    /// assert!(lcs.distance("abc", "abc") < lcs.distance("abc", "abcdef"));
+    ///
+    /// let distance = lcs.distance("abc", "abcdef").unwrap();
+    ///
+    /// assert!((distance.normalized() - 1.0 / 3.0).abs() < 1e-9);
+    /// ```
+    #[inline(always)]
+    pub fn set_mode(&mut self, mode: LcsMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Computes the length of the longest common subsequence between the
+    /// query and the candidate, also filling `self.matched_indices` if
+    /// `RANGES` is true.
+    #[inline]
+    fn distance<const RANGES: bool>(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<LcsDistance> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let char_eq =
+            utils::char_eq(is_case_sensitive, self.candidate_normalization);
+
+        self.query_chars.clear();
+        self.query_chars.extend(query.chars());
+
+        self.candidate_chars.clear();
+        self.candidate_chars.extend(candidate.chars());
+
+        let total_len = self.query_chars.len() + self.candidate_chars.len();
+
+        if self.query_chars.is_empty() || self.candidate_chars.is_empty() {
+            return self
+                .query_chars
+                .is_empty()
+                .then(|| LcsDistance::new(0, total_len, self.mode));
+        }
+
+        let width = self.candidate_chars.len() + 1;
+
+        let height = if RANGES { self.query_chars.len() + 1 } else { 2 };
+
+        let mut matrix = self.matrix_slab.alloc(width, height);
+
+        let lcs_len = fill_matrix::<RANGES>(
+            &self.query_chars,
+            &self.candidate_chars,
+            char_eq,
+            &mut matrix,
+        );
+
+        if lcs_len == 0 {
+            return None;
+        }
+
+        if RANGES {
+            matched_indices(
+                &self.query_chars,
+                &self.candidate_chars,
+                char_eq,
+                &matrix,
+                &mut self.matched_indices,
+            );
+
+            let mut ranges = MatchedRanges::from(ranges);
+
+            let mut matched_indices = self.matched_indices.iter().peekable();
+
+            for (char_idx, (offset, ch)) in
+                candidate.char_indices().enumerate()
+            {
+                let Some(&&matched_idx) = matched_indices.peek() else {
+                    break;
+                };
+
+                if char_idx == matched_idx {
+                    ranges.insert(offset..offset + ch.len_utf8());
+                    matched_indices.next();
+                }
+            }
+        }
+
+        Some(LcsDistance::new(lcs_len, total_len, self.mode))
+    }
+}
+
+impl Metric for Lcs {
+    type Query<'a> = &'a str;
+
+    type Distance = LcsDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let ranges = &mut Vec::new();
+        self.distance::<false>(query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        self.distance::<true>(query, candidate, ranges)
+    }
+}
+
+/// Fills the dynamic programming table, where the cell at row `i` and column
+/// `j` holds the length of the longest common subsequence between the first
+/// `i` characters of the query and the first `j` characters of the candidate.
+///
+/// If `RANGES` is false the matrix only has two rows which are used
+/// alternately, otherwise it has a row for every character of the query plus
+/// one, and the whole table is kept to reconstruct the subsequence.
+///
+/// Returns the length of the longest common subsequence.
+#[inline]
+fn fill_matrix<const RANGES: bool>(
+    query: &[char],
+    candidate: &[char],
+    char_eq: CharEq,
+    matrix: &mut Matrix<'_, usize>,
+) -> usize {
+    let mut last_row = 0;
+
+    for (row_idx, &query_ch) in query.iter().enumerate() {
+        let (prev_row, row) = if RANGES {
+            matrix.two_rows_mut(row_idx, row_idx + 1)
+        } else if row_idx % 2 == 0 {
+            matrix.two_rows_mut(0, 1)
+        } else {
+            let (row, prev_row) = matrix.two_rows_mut(0, 1);
+            (prev_row, row)
+        };
+
+        for (col_idx, &candidate_ch) in candidate.iter().enumerate() {
+            row[col_idx + 1] = if char_eq(query_ch, candidate_ch) {
+                prev_row[col_idx] + 1
+            } else {
+                prev_row[col_idx + 1].max(row[col_idx])
+            };
+        }
+
+        last_row = if RANGES { row_idx + 1 } else { (row_idx + 1) % 2 };
+    }
+
+    matrix.row_mut(last_row)[candidate.len()]
+}
+
+/// Walks the full dynamic programming table back from the bottom-right cell,
+/// filling `matched_indices` with the char offsets of the characters of the
+/// candidate that are part of the longest common subsequence.
+#[inline]
+fn matched_indices(
+    query: &[char],
+    candidate: &[char],
+    char_eq: CharEq,
+    matrix: &Matrix<'_, usize>,
+    matched_indices: &mut Vec<usize>,
+) {
+    matched_indices.clear();
+
+    let mut row_idx = query.len();
+
+    let mut col_idx = candidate.len();
+
+    let mut cell = MatrixCell(row_idx * matrix.width() + col_idx);
+
+    while row_idx > 0 && col_idx > 0 {
+        let up_left = matrix.up_left(cell);
+
+        if char_eq(query[row_idx - 1], candidate[col_idx - 1])
+            && matrix[cell] == matrix[up_left] + 1
+        {
+            matched_indices.push(col_idx - 1);
+            cell = up_left;
+            row_idx -= 1;
+            col_idx -= 1;
+        } else if matrix[up(matrix, cell)] >= matrix[matrix.left(cell)] {
+            cell = up(matrix, cell);
+            row_idx -= 1;
+        } else {
+            cell = matrix.left(cell);
+            col_idx -= 1;
+        }
+    }
+
+    matched_indices.reverse();
+}
+
+/// Returns the cell right above the given one.
+#[inline(always)]
+fn up(matrix: &Matrix<'_, usize>, cell: MatrixCell) -> MatrixCell {
+    MatrixCell(cell.0 - matrix.width())
+}
@@ -0,0 +1,9 @@
+//! The longest common subsequence metric.
+//!
+//! See the documentation of [`Lcs`] for more information.
+
+mod distance;
+mod lcs;
+
+pub use distance::{LcsDistance, LcsMode};
+pub use lcs::Lcs;
@@ -0,0 +1,192 @@
+use core::ops::Range;
+
+use super::*;
+use crate::{CaseSensitivity, Metric};
+
+/// A metric that implements the [optimal string alignment distance][osa], a
+/// restricted form of the Damerau-Levenshtein distance.
+///
+/// It's like the [`Levenshtein`](super::Levenshtein) distance, except that
+/// swapping two adjacent characters also counts as a single edit. This makes
+/// it a better fit for queries with typos, which are often transpositions:
+/// `"teh"` is one edit away from `"the"` instead of two.
+///
+/// The "restricted" part means that a substring can't be edited more than
+/// once, so `"ca"` is three edits away from `"abc"` rather than two (`"ca" ->
+/// "ac" -> "abc"`).
+///
+/// The ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// characters of the candidate that were left untouched by the edits.
+/// Transposed characters are not included.
+///
+/// ```rust
+/// # use norm::levenshtein::DamerauLevenshtein;
+/// # use norm::Metric;
+/// let mut osa = DamerauLevenshtein::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance =
+///     osa.distance_and_ranges("teh cat", "the cat", &mut ranges).unwrap();
+///
+/// assert_eq!(distance.edits(), 1);
+///
+/// assert_eq!(ranges, [0..1, 3..7]);
+/// ```
+///
+/// Queries of up to 64 characters are handled by Hyyrö's extension of
+/// [Myers' bit-parallel algorithm][myers]. Longer queries fall back to the
+/// classic `O(len(query) * len(candidate))` algorithm.
+///
+/// [osa]: https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance
+/// [myers]: https://doi.org/10.1145/316542.316550
+#[cfg_attr(docsrs, doc(cfg(feature = "damerau-levenshtein")))]
+#[derive(Clone, Default)]
+pub struct DamerauLevenshtein {
+    /// The options and buffers shared with the Levenshtein metric.
+    edit_distance: EditDistance,
+}
+
+impl core::fmt::Debug for DamerauLevenshtein {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DamerauLevenshtein")
+            .field(
+                "candidate_normalization",
+                &self.edit_distance.candidate_normalization,
+            )
+            .field("case_sensitivity", &self.edit_distance.case_sensitivity)
+            .field("max_distance", &self.edit_distance.max_distance)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DamerauLevenshtein {
+    /// Creates a new `DamerauLevenshtein`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::DamerauLevenshtein;
+    /// # use norm::Metric;
+    /// let mut osa = DamerauLevenshtein::new();
+    ///
+    /// assert_eq!(osa.distance("foo", "ƒöö").unwrap().edits(), 3);
+    ///
+    /// osa.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(osa.distance("foo", "ƒöö").unwrap().edits(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.edit_distance.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::DamerauLevenshtein;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut osa = DamerauLevenshtein::new();
+    ///
+    /// assert_eq!(osa.distance("abc", "ABC").unwrap().edits(), 0);
+    ///
+    /// osa.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert_eq!(osa.distance("abc", "ABC").unwrap().edits(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.edit_distance.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets the maximum distance a candidate can have from the query to be
+    /// considered a match. The default is `None`, i.e. every candidate is a
+    /// match.
+    ///
+    /// Setting a maximum distance also speeds up the distance calculation,
+    /// since most candidates can be rejected without computing their exact
+    /// distance.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::DamerauLevenshtein;
+    /// # use norm::Metric;
+    /// let mut osa = DamerauLevenshtein::new();
+    ///
+    /// assert!(osa.distance("form", "from").is_some());
+    ///
+    /// osa.set_max_distance(Some(1));
+    ///
+    /// assert!(osa.distance("form", "from").is_some());
+    /// assert!(osa.distance("form", "farm!").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_max_distance(
+        &mut self,
+        max_distance: Option<usize>,
+    ) -> &mut Self {
+        self.edit_distance.max_distance = max_distance;
+        self
+    }
+}
+
+impl Metric for DamerauLevenshtein {
+    type Query<'a> = &'a str;
+
+    type Distance = LevenshteinDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let ranges = &mut Vec::new();
+        self.edit_distance.distance::<false, true>(query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        self.edit_distance.distance::<true, true>(query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_within(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        max_distance: &Self::Distance,
+    ) -> Option<Self::Distance> {
+        self.edit_distance.distance_within::<true>(
+            query,
+            candidate,
+            max_distance.edits(),
+        )
+    }
+}
@@ -0,0 +1,33 @@
+/// The Levenshtein distance type.
+///
+/// This struct is returned by [`Levenshtein`](super::Levenshtein) and
+/// [`DamerauLevenshtein`](super::DamerauLevenshtein)'s
+/// [`Metric`](crate::Metric) implementations.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct LevenshteinDistance(usize);
+
+impl LevenshteinDistance {
+    /// Creates a new [`LevenshteinDistance`] from a number of edits.
+    #[inline(always)]
+    pub(super) fn from_edits(edits: usize) -> Self {
+        Self(edits)
+    }
+
+    /// Returns the minimum number of edits needed to turn the query into the
+    /// candidate.
+    #[inline(always)]
+    pub fn edits(self) -> usize {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for LevenshteinDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        // Goes from 1.0 for identical strings towards 0.0 as the number of
+        // edits grows.
+        1.0 / (1 + self.0) as f64
+    }
+}
@@ -0,0 +1,629 @@
+use core::ops::Range;
+
+use super::peq::MAX_BIT_PARALLEL_LEN;
+use super::*;
+use crate::utils::CharEq;
+use crate::{CaseSensitivity, MatchedRanges};
+
+/// The state and the algorithms shared by the edit distance metrics.
+///
+/// `TRANSPOSITIONS` is a const generic parameter of the algorithms rather
+/// than a field so that the Levenshtein distance doesn't pay for the extra
+/// checks needed to count a transposition of two adjacent characters as a
+/// single edit.
+#[derive(Clone, Default)]
+pub(super) struct EditDistance {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    pub(super) candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    pub(super) case_sensitivity: CaseSensitivity,
+
+    /// The maximum number of edits a candidate can be away from the query
+    /// before it's rejected, or `None` if there's no limit.
+    pub(super) max_distance: Option<usize>,
+
+    /// The pattern-match masks of the last query.
+    peq: Peq,
+
+    /// The vertical deltas of every column computed by Myers' algorithm,
+    /// used to reconstruct the alignment when computing the ranges.
+    columns: Vec<(u64, u64)>,
+
+    /// The dynamic programming matrix used for queries that are too long for
+    /// Myers' algorithm.
+    matrix: Vec<usize>,
+}
+
+impl EditDistance {
+    /// Computes the number of edits needed to turn the query into the
+    /// candidate, or `None` if there are more than
+    /// [`max_distance`](Self::max_distance).
+    ///
+    /// Swapping two adjacent characters counts as a single edit if
+    /// `TRANSPOSITIONS` is true, and the ranges of the candidate's characters
+    /// that are kept by the edits are inserted into `ranges` if `RANGES` is
+    /// true.
+    #[inline]
+    pub(super) fn distance<const RANGES: bool, const TRANSPOSITIONS: bool>(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<LevenshteinDistance> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        self.peq.update(
+            query,
+            is_case_sensitive,
+            self.candidate_normalization,
+        );
+
+        let query_len = self.peq.chars().len();
+
+        let candidate_len = if candidate.is_ascii() {
+            candidate.len()
+        } else {
+            candidate.chars().count()
+        };
+
+        let max_distance = self.max_distance.unwrap_or(usize::MAX);
+
+        // We need at least one edit for every character of difference in
+        // length.
+        if query_len.abs_diff(candidate_len) > max_distance {
+            return None;
+        }
+
+        let ranges = &mut ranges.into();
+
+        let edits = if query_len == 0 {
+            candidate_len
+        } else if query_len <= MAX_BIT_PARALLEL_LEN {
+            let edits = myers::<RANGES, TRANSPOSITIONS>(
+                &self.peq,
+                candidate,
+                candidate_len,
+                max_distance,
+                &mut self.columns,
+            )?;
+
+            if RANGES {
+                let columns = &self.columns;
+
+                matched_ranges::<_, TRANSPOSITIONS>(
+                    self.peq.chars(),
+                    candidate,
+                    candidate_len,
+                    self.peq.char_eq(),
+                    |i, j| {
+                        let (pv, mv) = columns[j];
+                        let mask = low_bits(i);
+                        j + (pv & mask).count_ones() as usize
+                            - (mv & mask).count_ones() as usize
+                    },
+                    ranges,
+                );
+            }
+
+            edits
+        } else {
+            let edits = wagner_fischer::<RANGES, TRANSPOSITIONS>(
+                self.peq.chars(),
+                candidate,
+                candidate_len,
+                self.peq.char_eq(),
+                max_distance,
+                &mut self.matrix,
+            )?;
+
+            if RANGES {
+                let matrix = &self.matrix;
+
+                let height = query_len + 1;
+
+                matched_ranges::<_, TRANSPOSITIONS>(
+                    self.peq.chars(),
+                    candidate,
+                    candidate_len,
+                    self.peq.char_eq(),
+                    |i, j| matrix[j * height + i],
+                    ranges,
+                );
+            }
+
+            edits
+        };
+
+        Some(LevenshteinDistance::from_edits(edits))
+    }
+
+    /// Same as [`Self::distance`], except that it returns `None` for the
+    /// candidates whose distance is greater than `max_edits`.
+    #[inline]
+    pub(super) fn distance_within<const TRANSPOSITIONS: bool>(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        max_edits: usize,
+    ) -> Option<LevenshteinDistance> {
+        let max_distance = self.max_distance;
+
+        self.max_distance =
+            Some(max_distance.map_or(max_edits, |max| max.min(max_edits)));
+
+        let distance = self.distance::<false, TRANSPOSITIONS>(
+            query,
+            candidate,
+            &mut Vec::new(),
+        );
+
+        self.max_distance = max_distance;
+
+        distance
+    }
+}
+
+/// Returns a mask with the lowest `n` bits set.
+#[inline(always)]
+fn low_bits(n: usize) -> u64 {
+    if n >= MAX_BIT_PARALLEL_LEN {
+        u64::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Computes the edit distance between a query of at most 64 characters and
+/// a candidate using Myers' bit-parallel algorithm, in the formulation given
+/// by Hyyrö for the edit distance between two whole strings. If
+/// `TRANSPOSITIONS` is true it also uses Hyyrö's extension to the optimal
+/// string alignment distance.
+///
+/// Bit `i` of `pv` (`mv`) is set if the value in row `i + 1` of the current
+/// column is one more (less) than the value in row `i`. Knowing these deltas
+/// and the value in the first row, which is simply the column index, is
+/// enough to reconstruct the whole column.
+///
+/// If `RANGES` is true the deltas of every column are stored in `columns`.
+#[inline]
+fn myers<const RANGES: bool, const TRANSPOSITIONS: bool>(
+    peq: &Peq,
+    candidate: &str,
+    candidate_len: usize,
+    max_distance: usize,
+    columns: &mut Vec<(u64, u64)>,
+) -> Option<usize> {
+    let query_len = peq.chars().len();
+
+    let last_row = 1 << (query_len - 1);
+
+    // In the first column the value of row `i` is `i`.
+    let mut pv = low_bits(query_len);
+
+    let mut mv = 0;
+
+    // Bit `i` is set if the value in row `i + 1` is the same as the one in
+    // row `i` of the previous column, i.e. if the diagonal delta is zero.
+    let mut d0 = 0;
+
+    let mut prev_eq = 0;
+
+    let mut edits = query_len;
+
+    if RANGES {
+        columns.clear();
+        columns.push((pv, mv));
+    }
+
+    for (idx, ch) in candidate.chars().enumerate() {
+        let eq = peq.mask(ch);
+
+        let transpositions =
+            if TRANSPOSITIONS { ((!d0 & eq) << 1) & prev_eq } else { 0 };
+
+        d0 = ((eq & pv).wrapping_add(pv) ^ pv) | eq | mv | transpositions;
+
+        let mut ph = mv | !(d0 | pv);
+
+        let mut mh = d0 & pv;
+
+        if ph & last_row != 0 {
+            edits += 1;
+        } else if mh & last_row != 0 {
+            edits -= 1;
+        }
+
+        // The value in the first row grows by one in every column.
+        ph = (ph << 1) | 1;
+
+        mh <<= 1;
+
+        pv = mh | !(d0 | ph);
+
+        mv = ph & d0;
+
+        if TRANSPOSITIONS {
+            prev_eq = eq;
+        }
+
+        if RANGES {
+            columns.push((pv, mv));
+        }
+
+        // Every remaining character of the candidate can decrease the
+        // distance by at most one.
+        let remaining = candidate_len - idx - 1;
+
+        if edits.saturating_sub(remaining) > max_distance {
+            return None;
+        }
+    }
+
+    (edits <= max_distance).then_some(edits)
+}
+
+/// Computes the edit distance between a query and a candidate using the
+/// Wagner-Fischer algorithm.
+///
+/// The matrix is stored column by column, with one column for every
+/// character of the candidate plus one. If `RANGES` is false only the last
+/// few columns needed to compute the next one are kept.
+///
+/// Reaching the cell in row `i` and column `j` takes at least `|i - j|`
+/// edits, so only the cells within `max_distance` of the diagonal are
+/// computed. The cells bordering that band are set to `max_distance + 1`,
+/// which is enough to tell that the paths crossing them are too expensive.
+#[inline]
+fn wagner_fischer<const RANGES: bool, const TRANSPOSITIONS: bool>(
+    query: &[char],
+    candidate: &str,
+    candidate_len: usize,
+    char_eq: CharEq,
+    max_distance: usize,
+    matrix: &mut Vec<usize>,
+) -> Option<usize> {
+    let height = query.len() + 1;
+
+    let kept_columns = if TRANSPOSITIONS { 3 } else { 2 };
+
+    let num_columns = if RANGES { candidate_len + 1 } else { kept_columns };
+
+    // The distance is never greater than the length of the longest string,
+    // so a wider band would just be the whole matrix.
+    let band = max_distance.min(query.len().max(candidate_len));
+
+    matrix.clear();
+
+    matrix.resize(num_columns * height, band + 1);
+
+    for row in 0..height {
+        matrix[row] = row;
+    }
+
+    let column_start =
+        |idx: usize| if RANGES { idx } else { idx % kept_columns } * height;
+
+    let mut prev_candidate_ch = None;
+
+    let mut prev_column_min = 0;
+
+    for (idx, candidate_ch) in candidate.chars().enumerate() {
+        let prev = column_start(idx);
+
+        let this = column_start(idx + 1);
+
+        matrix[this] = idx + 1;
+
+        let mut column_min = idx + 1;
+
+        let first_row = (idx + 1).saturating_sub(band).clamp(1, height);
+
+        let last_row = (idx + 1 + band).min(height - 1);
+
+        if first_row > 1 {
+            matrix[this + first_row - 1] = band + 1;
+        }
+
+        if last_row + 1 < height {
+            matrix[this + last_row + 1] = band + 1;
+        }
+
+        for row in first_row..=last_row {
+            let query_ch = query[row - 1];
+
+            let substitution = matrix[prev + row - 1]
+                + !char_eq(query_ch, candidate_ch) as usize;
+
+            let deletion = matrix[this + row - 1] + 1;
+
+            let insertion = matrix[prev + row] + 1;
+
+            let mut value = substitution.min(deletion).min(insertion);
+
+            if TRANSPOSITIONS && row > 1 {
+                if let Some(prev_candidate_ch) = prev_candidate_ch {
+                    if char_eq(query_ch, prev_candidate_ch)
+                        && char_eq(query[row - 2], candidate_ch)
+                    {
+                        let prev_prev = column_start(idx - 1);
+                        value = value.min(matrix[prev_prev + row - 2] + 1);
+                    }
+                }
+            }
+
+            matrix[this + row] = value;
+
+            column_min = column_min.min(value);
+        }
+
+        // Every path to the bottom-right corner crosses this column, and
+        // values never decrease along a path. A transposition can skip a
+        // column, but never two consecutive ones.
+        let min = if TRANSPOSITIONS {
+            column_min.max(prev_column_min)
+        } else {
+            column_min
+        };
+
+        if min > max_distance {
+            return None;
+        }
+
+        prev_column_min = column_min;
+
+        prev_candidate_ch = Some(candidate_ch);
+    }
+
+    let edits = matrix[column_start(candidate_len) + height - 1];
+
+    (edits <= max_distance).then_some(edits)
+}
+
+/// Walks back an optimal alignment from the bottom-right corner of the
+/// matrix, inserting the byte ranges of the candidate characters that were
+/// matched to a query character.
+///
+/// Transposed characters are edits, so their ranges are not included.
+///
+/// `value_at(i, j)` returns the value in row `i` and column `j` of the
+/// matrix.
+#[inline]
+fn matched_ranges<F, const TRANSPOSITIONS: bool>(
+    query: &[char],
+    candidate: &str,
+    candidate_len: usize,
+    char_eq: CharEq,
+    value_at: F,
+    ranges: &mut MatchedRanges,
+) where
+    F: Fn(usize, usize) -> usize,
+{
+    let mut candidate_chars = candidate.char_indices().rev();
+
+    let mut row = query.len();
+
+    let mut col = candidate_len;
+
+    while row > 0 && col > 0 {
+        let mut next_chars = candidate_chars.clone();
+
+        let (byte_offset, candidate_ch) =
+            next_chars.next().expect("there's a char for every column");
+
+        let value = value_at(row, col);
+
+        let up_left = value_at(row - 1, col - 1);
+
+        if char_eq(query[row - 1], candidate_ch) && up_left == value {
+            ranges.insert(byte_offset..byte_offset + candidate_ch.len_utf8());
+            row -= 1;
+            col -= 1;
+            candidate_chars.next();
+        } else if up_left + 1 == value {
+            row -= 1;
+            col -= 1;
+            candidate_chars.next();
+        } else if value_at(row - 1, col) + 1 == value {
+            row -= 1;
+        } else if value_at(row, col - 1) + 1 == value {
+            col -= 1;
+            candidate_chars.next();
+        } else {
+            // The only edit left is a transposition.
+            debug_assert!(TRANSPOSITIONS);
+            row -= 2;
+            col -= 2;
+            candidate_chars.nth(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The textbook implementation, used as a reference.
+    fn naive(query: &str, candidate: &str, transpositions: bool) -> usize {
+        let query = query.chars().collect::<Vec<_>>();
+
+        let candidate = candidate.chars().collect::<Vec<_>>();
+
+        let mut matrix = vec![vec![0; candidate.len() + 1]; query.len() + 1];
+
+        for i in 0..=query.len() {
+            for j in 0..=candidate.len() {
+                matrix[i][j] = if i == 0 {
+                    j
+                } else if j == 0 {
+                    i
+                } else {
+                    (matrix[i - 1][j - 1]
+                        + (query[i - 1] != candidate[j - 1]) as usize)
+                        .min(matrix[i - 1][j] + 1)
+                        .min(matrix[i][j - 1] + 1)
+                };
+
+                if transpositions
+                    && i > 1
+                    && j > 1
+                    && query[i - 1] == candidate[j - 2]
+                    && query[i - 2] == candidate[j - 1]
+                {
+                    matrix[i][j] = matrix[i][j].min(matrix[i - 2][j - 2] + 1);
+                }
+            }
+        }
+
+        matrix[query.len()][candidate.len()]
+    }
+
+    fn same_as_naive<const TRANSPOSITIONS: bool>() {
+        let mut rng = crate::utils::Xorshift::new(0x0123_4567_89ab_cdef);
+
+        let alphabet = ['a', 'b', 'c', 'é', 'ö', ' '];
+
+        let mut random_string = |max_len: usize| {
+            let len = rng.next(max_len + 1);
+            (0..len)
+                .map(|_| alphabet[rng.next(alphabet.len())])
+                .collect::<String>()
+        };
+
+        let mut edit_distance = EditDistance {
+            case_sensitivity: CaseSensitivity::Sensitive,
+            ..Default::default()
+        };
+
+        let mut ranges = Vec::new();
+
+        let mut distance = |edit_distance: &mut EditDistance,
+                            query: &str,
+                            candidate: &str,
+                            with_ranges: bool| {
+            ranges.clear();
+
+            let distance = if with_ranges {
+                edit_distance.distance::<true, TRANSPOSITIONS>(
+                    query,
+                    candidate,
+                    &mut ranges,
+                )
+            } else {
+                edit_distance.distance::<false, TRANSPOSITIONS>(
+                    query,
+                    candidate,
+                    &mut ranges,
+                )
+            };
+
+            let matched = ranges
+                .iter()
+                .map(|range| candidate[range.clone()].chars().count())
+                .sum::<usize>();
+
+            (distance.map(LevenshteinDistance::edits), matched)
+        };
+
+        for iteration in 0..5_000 {
+            // Make sure we also test queries longer than 64 characters.
+            let query =
+                random_string(if iteration % 4 == 0 { 100 } else { 10 });
+
+            let candidate = random_string(100);
+
+            let expected = naive(&query, &candidate, TRANSPOSITIONS);
+
+            let (edits, matched) =
+                distance(&mut edit_distance, &query, &candidate, true);
+
+            assert_eq!(edits, Some(expected), "{query:?} vs {candidate:?}");
+
+            assert_eq!(
+                distance(&mut edit_distance, &query, &candidate, false).0,
+                Some(expected),
+                "{query:?} vs {candidate:?}"
+            );
+
+            // Every character of the longer string that's not matched costs
+            // at least one edit, or half an edit if it's transposed.
+            let longest = query.chars().count().max(candidate.chars().count());
+
+            let unmatched = longest - matched;
+
+            if TRANSPOSITIONS {
+                assert!(unmatched <= 2 * expected);
+            } else {
+                assert!(unmatched <= expected);
+            }
+
+            // With the exact distance as the maximum we should still get a
+            // match, but not with one less.
+            edit_distance.max_distance = Some(expected);
+
+            for with_ranges in [true, false] {
+                assert_eq!(
+                    distance(
+                        &mut edit_distance,
+                        &query,
+                        &candidate,
+                        with_ranges
+                    )
+                    .0,
+                    Some(expected),
+                    "{query:?} vs {candidate:?}"
+                );
+            }
+
+            if expected > 0 {
+                edit_distance.max_distance = Some(expected - 1);
+
+                for with_ranges in [true, false] {
+                    assert_eq!(
+                        distance(
+                            &mut edit_distance,
+                            &query,
+                            &candidate,
+                            with_ranges
+                        )
+                        .0,
+                        None,
+                        "{query:?} vs {candidate:?}"
+                    );
+                }
+            }
+
+            edit_distance.max_distance = None;
+
+            let within = |edit_distance: &mut EditDistance, max_edits| {
+                edit_distance
+                    .distance_within::<TRANSPOSITIONS>(
+                        &query, &candidate, max_edits,
+                    )
+                    .map(LevenshteinDistance::edits)
+            };
+
+            assert_eq!(within(&mut edit_distance, expected), Some(expected));
+
+            if expected > 0 {
+                assert_eq!(within(&mut edit_distance, expected - 1), None);
+            }
+        }
+    }
+
+    #[test]
+    fn levenshtein_same_as_naive() {
+        same_as_naive::<false>();
+    }
+
+    #[test]
+    fn osa_same_as_naive() {
+        same_as_naive::<true>();
+    }
+}
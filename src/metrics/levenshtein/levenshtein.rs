@@ -0,0 +1,189 @@
+use core::ops::Range;
+
+use super::*;
+use crate::{CaseSensitivity, Metric};
+
+/// A metric that implements the [Levenshtein distance][lev].
+///
+/// The distance between a query and a candidate is the minimum number of
+/// single-character insertions, deletions and substitutions needed to turn
+/// the query into the candidate. For example, the distance between
+/// `"kitten"` and `"sitting"` is 3: two substitutions (`k -> s` and
+/// `e -> i`) and one insertion (`g`).
+///
+/// The ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// characters of the candidate that were left untouched by the edits, i.e.
+/// the ones that an optimal alignment matched to a character of the query.
+///
+/// ```rust
+/// # use norm::levenshtein::Levenshtein;
+/// # use norm::Metric;
+/// let mut lev = Levenshtein::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance =
+///     lev.distance_and_ranges("kitten", "sitting", &mut ranges).unwrap();
+///
+/// assert_eq!(distance.edits(), 3);
+///
+/// assert_eq!(ranges, [1..4, 5..6]);
+/// ```
+///
+/// Queries of up to 64 characters are handled by [Myers' bit-parallel
+/// algorithm][myers], which computes a whole column of the dynamic
+/// programming matrix with a handful of bitwise operations. Longer queries
+/// fall back to the classic `O(len(query) * len(candidate))` algorithm.
+///
+/// [lev]: https://en.wikipedia.org/wiki/Levenshtein_distance
+/// [myers]: https://doi.org/10.1145/316542.316550
+#[cfg_attr(docsrs, doc(cfg(feature = "levenshtein")))]
+#[derive(Clone, Default)]
+pub struct Levenshtein {
+    /// The options and buffers shared with the Damerau-Levenshtein metric.
+    edit_distance: EditDistance,
+}
+
+impl core::fmt::Debug for Levenshtein {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Levenshtein")
+            .field(
+                "candidate_normalization",
+                &self.edit_distance.candidate_normalization,
+            )
+            .field("case_sensitivity", &self.edit_distance.case_sensitivity)
+            .field("max_distance", &self.edit_distance.max_distance)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Levenshtein {
+    /// Creates a new `Levenshtein`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::Levenshtein;
+    /// # use norm::Metric;
+    /// let mut lev = Levenshtein::new();
+    ///
+    /// assert_eq!(lev.distance("foo", "ƒöö").unwrap().edits(), 3);
+    ///
+    /// lev.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(lev.distance("foo", "ƒöö").unwrap().edits(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.edit_distance.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::Levenshtein;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut lev = Levenshtein::new();
+    ///
+    /// assert_eq!(lev.distance("abc", "ABC").unwrap().edits(), 0);
+    ///
+    /// lev.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert_eq!(lev.distance("abc", "ABC").unwrap().edits(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.edit_distance.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets the maximum distance a candidate can have from the query to be
+    /// considered a match. The default is `None`, i.e. every candidate is a
+    /// match.
+    ///
+    /// Setting a maximum distance also speeds up the distance calculation,
+    /// since most candidates can be rejected without computing their exact
+    /// distance.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::levenshtein::Levenshtein;
+    /// # use norm::Metric;
+    /// let mut lev = Levenshtein::new();
+    ///
+    /// assert!(lev.distance("kitten", "sitting").is_some());
+    ///
+    /// lev.set_max_distance(Some(2));
+    ///
+    /// assert!(lev.distance("kitten", "sitting").is_none());
+    /// assert!(lev.distance("kitten", "mitten").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_max_distance(
+        &mut self,
+        max_distance: Option<usize>,
+    ) -> &mut Self {
+        self.edit_distance.max_distance = max_distance;
+        self
+    }
+}
+
+impl Metric for Levenshtein {
+    type Query<'a> = &'a str;
+
+    type Distance = LevenshteinDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let ranges = &mut Vec::new();
+        self.edit_distance.distance::<false, false>(query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        self.edit_distance.distance::<true, false>(query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_within(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        max_distance: &Self::Distance,
+    ) -> Option<Self::Distance> {
+        self.edit_distance.distance_within::<false>(
+            query,
+            candidate,
+            max_distance.edits(),
+        )
+    }
+}
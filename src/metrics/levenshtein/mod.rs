@@ -0,0 +1,27 @@
+//! Metrics based on the edit distance between the query and the candidate.
+//!
+//! This module contains two metrics: [`Levenshtein`], which counts the
+//! minimum number of single-character insertions, deletions and
+//! substitutions needed to turn the query into the candidate, and
+//! [`DamerauLevenshtein`], which also counts swapping two adjacent
+//! characters as a single edit.
+//!
+//! Unlike the [fzf](crate::fzf) metrics the query is compared to the whole
+//! candidate, so these metrics are better suited for finding near-duplicates
+//! or correcting typos than for interactive fuzzy-finding.
+
+#[cfg(feature = "damerau-levenshtein")]
+mod damerau_levenshtein;
+mod distance;
+mod edit_distance;
+#[cfg(feature = "levenshtein")]
+mod levenshtein;
+mod peq;
+
+#[cfg(feature = "damerau-levenshtein")]
+pub use damerau_levenshtein::DamerauLevenshtein;
+pub use distance::LevenshteinDistance;
+use edit_distance::EditDistance;
+#[cfg(feature = "levenshtein")]
+pub use levenshtein::Levenshtein;
+use peq::Peq;
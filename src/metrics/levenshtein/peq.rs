@@ -0,0 +1,147 @@
+use crate::normalize::normalize;
+use crate::utils::{self, CharEq};
+
+/// The maximum number of query characters that fit in a bitmask.
+pub(super) const MAX_BIT_PARALLEL_LEN: usize = u64::BITS as usize;
+
+/// The pattern-match bitmasks of a query.
+///
+/// Bit `i` of the mask of a character is set if that character matches the
+/// `i`-th character of the query. Bit-parallel algorithms use these masks to
+/// process an entire column of the dynamic programming matrix at once, which
+/// is why they only work on queries of at most [`MAX_BIT_PARALLEL_LEN`]
+/// characters.
+///
+/// Building the masks is not free, so they're cached and only rebuilt when
+/// the query or the matching options change.
+#[derive(Clone)]
+pub(super) struct Peq {
+    /// The masks of the ASCII characters, indexed by their byte value.
+    ascii: [u64; 128],
+
+    /// The masks of the non-ASCII characters contained in the query.
+    unicode: Vec<(char, u64)>,
+
+    /// The characters of the query.
+    chars: Vec<char>,
+
+    /// The query the masks were built for.
+    query: String,
+
+    /// How the characters of the query are compared to the ones of the
+    /// candidate, computed from the two flags below.
+    char_eq: CharEq,
+
+    /// Whether the masks were built for a case-sensitive comparison.
+    is_case_sensitive: bool,
+
+    /// Whether the masks were built for candidates whose characters are
+    /// normalized before being compared.
+    normalize_candidate: bool,
+}
+
+impl Default for Peq {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            ascii: [0; 128],
+            unicode: Vec::new(),
+            chars: Vec::new(),
+            query: String::new(),
+            char_eq: utils::char_eq(true, false),
+            is_case_sensitive: true,
+            normalize_candidate: false,
+        }
+    }
+}
+
+impl Peq {
+    /// Returns the characters of the query.
+    #[inline(always)]
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Returns the function used to compare a query character to a candidate
+    /// character.
+    #[inline(always)]
+    pub fn char_eq(&self) -> CharEq {
+        self.char_eq
+    }
+
+    /// Returns the mask of the given candidate character.
+    #[inline(always)]
+    pub fn mask(&self, ch: char) -> u64 {
+        if ch.is_ascii() {
+            return self.ascii[ch as usize];
+        }
+
+        let mut mask = 0;
+
+        for &(query_ch, query_mask) in &self.unicode {
+            if (self.char_eq)(query_ch, ch) {
+                mask |= query_mask;
+            }
+        }
+
+        if self.normalize_candidate {
+            // ASCII characters are always normalized, so they match the
+            // normalized version of the candidate character.
+            let normalized = normalize(ch);
+
+            if normalized.is_ascii() {
+                mask |= self.ascii[normalized as usize];
+            }
+        }
+
+        mask
+    }
+
+    /// Rebuilds the masks if any of the arguments differs from the ones used
+    /// to build the current masks.
+    #[inline]
+    pub fn update(
+        &mut self,
+        query: &str,
+        is_case_sensitive: bool,
+        normalize_candidate: bool,
+    ) {
+        if self.query == query
+            && self.is_case_sensitive == is_case_sensitive
+            && self.normalize_candidate == normalize_candidate
+        {
+            return;
+        }
+
+        self.query.clear();
+        self.query.push_str(query);
+        self.chars.clear();
+        self.chars.extend(query.chars());
+        self.char_eq = utils::char_eq(is_case_sensitive, normalize_candidate);
+        self.is_case_sensitive = is_case_sensitive;
+        self.normalize_candidate = normalize_candidate;
+        self.ascii = [0; 128];
+        self.unicode.clear();
+
+        let chars = &self.chars[..self.chars.len().min(MAX_BIT_PARALLEL_LEN)];
+
+        for (idx, &ch) in chars.iter().enumerate() {
+            let bit = 1 << idx;
+
+            if ch.is_ascii() {
+                self.ascii[ch as usize] |= bit;
+
+                if !is_case_sensitive && ch.is_ascii_alphabetic() {
+                    let flipped = utils::ascii_letter_flip_case(ch as u8);
+                    self.ascii[flipped as usize] |= bit;
+                }
+            } else if let Some((_, mask)) =
+                self.unicode.iter_mut().find(|(query_ch, _)| *query_ch == ch)
+            {
+                *mask |= bit;
+            } else {
+                self.unicode.push((ch, bit));
+            }
+        }
+    }
+}
@@ -1,3 +1,59 @@
-#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
-#[cfg_attr(docsrs, doc(cfg(any(feature = "fzf-v1", feature = "fzf-v2"))))]
+#[cfg(any(
+    feature = "acronym",
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "skim"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "acronym",
+        feature = "fzf-v1",
+        feature = "fzf-v2",
+        feature = "skim"
+    )))
+)]
 pub mod fzf;
+
+#[cfg(feature = "fzy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fzy")))]
+pub mod fzy;
+
+#[cfg(feature = "hamming")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hamming")))]
+pub mod hamming;
+
+#[cfg(feature = "jaro-winkler")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jaro-winkler")))]
+pub mod jaro_winkler;
+
+#[cfg(feature = "lcs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lcs")))]
+pub mod lcs;
+
+#[cfg(any(feature = "levenshtein", feature = "damerau-levenshtein"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "levenshtein", feature = "damerau-levenshtein")))
+)]
+pub mod levenshtein;
+
+#[cfg(feature = "ngram")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ngram")))]
+pub mod ngram;
+
+#[cfg(feature = "phonetic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "phonetic")))]
+pub mod phonetic;
+
+#[cfg(feature = "ratcliff-obershelp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ratcliff-obershelp")))]
+pub mod ratcliff_obershelp;
+
+#[cfg(feature = "token-ratio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "token-ratio")))]
+pub mod token_ratio;
+
+#[cfg(feature = "vscode")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vscode")))]
+pub mod vscode;
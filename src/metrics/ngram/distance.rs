@@ -0,0 +1,62 @@
+use core::cmp::Ordering;
+
+/// The n-gram distance type.
+///
+/// This struct is returned by [`NGram`](super::NGram)'s
+/// [`Metric`](crate::Metric) implementation. It wraps a similarity between
+/// `0.0` and `1.0`, and it's ordered by descending similarity so that the most
+/// similar candidates come first when sorting.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NGramDistance(f64);
+
+impl PartialEq for NGramDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for NGramDistance {}
+
+impl PartialOrd for NGramDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NGramDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // This makes the type act like a distance and not like a similarity.
+        other.0.total_cmp(&self.0)
+    }
+}
+
+impl NGramDistance {
+    /// Creates a new [`NGramDistance`] from a similarity.
+    #[inline(always)]
+    pub(super) fn from_similarity(similarity: f64) -> Self {
+        debug_assert!((0.0..=1.0).contains(&similarity));
+        Self(similarity)
+    }
+
+    /// Returns the similarity between the n-grams of the query and the ones
+    /// of the candidate, from `0.0` (no n-grams in common) to `1.0` (same
+    /// n-grams).
+    ///
+    /// How it's computed depends on the
+    /// [`NGramSimilarity`](super::NGramSimilarity) of the metric that produced
+    /// this distance.
+    #[inline(always)]
+    pub fn similarity(self) -> f64 {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for NGramDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        self.0
+    }
+}
@@ -0,0 +1,9 @@
+//! The character n-gram similarity metric.
+//!
+//! See the documentation of [`NGram`] for more information.
+
+mod distance;
+mod ngram;
+
+pub use distance::NGramDistance;
+pub use ngram::{NGram, NGramSimilarity};
@@ -0,0 +1,607 @@
+use core::ops::Range;
+
+use super::*;
+use crate::normalize::normalize;
+use crate::{CaseSensitivity, MatchedRanges, Metric};
+
+/// The character used to pad the words when padding is enabled. It can never
+/// clash with a character of a word since words are split on whitespace.
+const PAD: char = ' ';
+
+/// The ways an [`NGram`] can compute the similarity between the n-grams of
+/// the query and the ones of the candidate.
+///
+/// In the following, `Q` and `C` are the multisets of n-grams of the query
+/// and of the candidate, respectively.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NGramSimilarity {
+    /// The [Sørensen–Dice coefficient][dice], i.e. `2 * |Q ∩ C| / (|Q| +
+    /// |C|)`.
+    ///
+    /// [dice]: https://en.wikipedia.org/wiki/Dice-S%C3%B8rensen_coefficient
+    #[default]
+    Dice,
+
+    /// The [Jaccard index][jaccard], i.e. `|Q ∩ C| / |Q ∪ C|`.
+    ///
+    /// [jaccard]: https://en.wikipedia.org/wiki/Jaccard_index
+    Jaccard,
+
+    /// The [cosine similarity][cosine] between the vectors counting how many
+    /// times each n-gram appears in the query and in the candidate.
+    ///
+    /// [cosine]: https://en.wikipedia.org/wiki/Cosine_similarity
+    Cosine,
+}
+
+/// A metric that compares the character [n-grams][ngram] of the query and of
+/// the candidate.
+///
+/// The n-grams of a string are all its substrings made of `n` consecutive
+/// characters, e.g. the 3-grams (or trigrams) of `"hello"` are `"hel"`,
+/// `"ell"` and `"llo"`. Strings with fewer than `n` characters have a single
+/// n-gram made of all their characters.
+///
+/// The similarity between the two multisets of n-grams is computed by one of
+/// the functions in [`NGramSimilarity`], and it goes from `0.0` (no n-grams
+/// in common) to `1.0` (same n-grams). Since n-grams ignore where they appear
+/// in a string, this metric is quite tolerant of words being swapped around.
+///
+/// A candidate doesn't match the query if they don't share any n-gram, and
+/// the ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// characters of the candidate covered by an n-gram that's also in the query.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::ngram::NGram;
+/// # use norm::Metric;
+/// let mut ngram = NGram::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance =
+///     ngram.distance_and_ranges("fuzzy", "buzzy", &mut ranges).unwrap();
+///
+/// // The trigrams in common are "uzz" and "zzy".
+/// assert_eq!(distance.similarity(), 4.0 / 6.0);
+///
+/// assert_eq!(ranges, [1..5]);
+/// ```
+///
+/// [ngram]: https://en.wikipedia.org/wiki/N-gram
+#[cfg_attr(docsrs, doc(cfg(feature = "ngram")))]
+#[derive(Clone)]
+pub struct NGram {
+    /// Whether multi-byte latin characters are normalized to ASCII before both
+    /// strings are split into n-grams.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the n-grams of the query and the
+    /// candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// The number of characters in each n-gram, which is never zero.
+    n: usize,
+
+    /// Whether every word is padded with `n - 1` spaces on both sides, so
+    /// that its first and last characters appear in as many n-grams as the
+    /// others.
+    padding: bool,
+
+    /// How the shared n-grams are turned into a similarity.
+    similarity: NGramSimilarity,
+
+    /// The n-grams of the last query.
+    query_grams: Grams,
+
+    /// The n-grams of the last candidate.
+    candidate_grams: Grams,
+
+    /// Whether each character of the last candidate is covered by an n-gram
+    /// shared with the query.
+    covered: Vec<bool>,
+}
+
+impl core::fmt::Debug for NGram {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NGram")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .field("n", &self.n)
+            .field("padding", &self.padding)
+            .field("similarity", &self.similarity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for NGram {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            candidate_normalization: false,
+            case_sensitivity: CaseSensitivity::default(),
+            n: 3,
+            padding: false,
+            similarity: NGramSimilarity::default(),
+            query_grams: Grams::default(),
+            candidate_grams: Grams::default(),
+            covered: Vec::new(),
+        }
+    }
+}
+
+impl NGram {
+    /// Creates a new `NGram`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters should be normalized to ASCII
+    /// before splitting the strings into n-grams. The default is `false`.
+    ///
+    /// The query goes through the same mapping as the candidate. N-grams are
+    /// only ever compared as a whole, so a query gram like `"röt"` could
+    /// never be equal to the `"rot"` the candidate's `"röt"` is turned into.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ngram::NGram;
+    /// # use norm::Metric;
+    /// let mut ngram = NGram::new();
+    ///
+    /// assert!(ngram.distance("foo", "ƒöö").is_none());
+    ///
+    /// ngram.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(ngram.distance("foo", "ƒöö").unwrap().similarity(), 1.0);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the n-grams of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ngram::NGram;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut ngram = NGram::new();
+    ///
+    /// assert_eq!(ngram.distance("abc", "ABC").unwrap().similarity(), 1.0);
+    ///
+    /// ngram.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert!(ngram.distance("abc", "ABC").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets the number of characters in each n-gram. The default is `3`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ngram::NGram;
+    /// # use norm::Metric;
+    /// let mut ngram = NGram::new();
+    ///
+    /// // "abc" and "cab" don't share any trigram..
+    /// assert!(ngram.distance("abc", "cab").is_none());
+    ///
+    /// ngram.set_n(2);
+    ///
+    /// // ..but they share the "ab" bigram.
+    /// assert_eq!(ngram.distance("abc", "cab").unwrap().similarity(), 0.5);
+    /// ```
+    #[inline(always)]
+    pub fn set_n(&mut self, n: usize) -> &mut Self {
+        assert!(n > 0, "the n-grams must have at least one character");
+        self.n = n;
+        self
+    }
+
+    /// Sets whether every whitespace-separated word should be padded with
+    /// `n - 1` spaces on both sides before being split into n-grams. The
+    /// default is `false`.
+    ///
+    /// Padding gives more weight to the start and the end of each word, and
+    /// it keeps n-grams from spanning multiple words.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ngram::NGram;
+    /// # use norm::Metric;
+    /// let mut ngram = NGram::new();
+    ///
+    /// ngram.set_n(2);
+    ///
+    /// // "ab" is the only bigram of the query.
+    /// assert_eq!(ngram.distance("ab", "xaby").unwrap().similarity(), 0.5);
+    ///
+    /// ngram.set_padding(true);
+    ///
+    /// // The bigrams of the query are now " a", "ab" and "b ".
+    /// assert_eq!(ngram.distance("ab", "xaby").unwrap().similarity(), 0.25);
+    /// ```
+    #[inline(always)]
+    pub fn set_padding(&mut self, padding: bool) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the function used to compute the similarity between the n-grams
+    /// of the query and the ones of the candidate. The default is
+    /// [`NGramSimilarity::Dice`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ngram::{NGram, NGramSimilarity};
+    /// # use norm::Metric;
+    /// let mut ngram = NGram::new();
+    ///
+    /// ngram.set_n(2);
+    ///
+    /// // {"ab", "bc"} and {"bc", "cd"}.
+    /// assert_eq!(ngram.distance("abc", "bcd").unwrap().similarity(), 0.5);
+    ///
+    /// ngram.set_similarity(NGramSimilarity::Jaccard);
+    ///
+    /// let similarity = ngram.distance("abc", "bcd").unwrap().similarity();
+    ///
+    /// assert!((similarity - 1.0 / 3.0).abs() < 1e-9);
+    /// ```
+    #[inline(always)]
+    pub fn set_similarity(
+        &mut self,
+        similarity: NGramSimilarity,
+    ) -> &mut Self {
+        self.similarity = similarity;
+        self
+    }
+
+    /// Computes the similarity between the n-grams of the query and the ones
+    /// of the candidate, also filling `self.covered` if `RANGES` is true.
+    #[inline]
+    fn similarity<const RANGES: bool>(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<f64> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let key = |ch: char| {
+            let ch =
+                if self.candidate_normalization { normalize(ch) } else { ch };
+
+            if is_case_sensitive {
+                ch
+            } else {
+                ch.to_ascii_lowercase()
+            }
+        };
+
+        self.query_grams.fill(query, self.n, self.padding, key);
+
+        self.candidate_grams.fill(candidate, self.n, self.padding, key);
+
+        let query_grams = &self.query_grams;
+
+        let candidate_grams = &self.candidate_grams;
+
+        if query_grams.is_empty() && candidate_grams.is_empty() {
+            if RANGES {
+                self.covered.clear();
+            }
+            return Some(1.0);
+        }
+
+        if RANGES {
+            self.covered.clear();
+            self.covered.resize(candidate_grams.chars.len(), false);
+        }
+
+        let mut query_iter = query_grams.groups().peekable();
+
+        let mut candidate_iter = candidate_grams.groups().peekable();
+
+        let mut counts = Counts::default();
+
+        loop {
+            let (query_count, candidate_group) =
+                match (query_iter.peek(), candidate_iter.peek()) {
+                    (Some(query_group), Some(candidate_group)) => {
+                        match query_grams.gram(query_group[0].clone()).cmp(
+                            candidate_grams.gram(candidate_group[0].clone()),
+                        ) {
+                            core::cmp::Ordering::Less => {
+                                (query_iter.next().unwrap().len(), &[][..])
+                            },
+                            core::cmp::Ordering::Greater => {
+                                (0, candidate_iter.next().unwrap())
+                            },
+                            core::cmp::Ordering::Equal => (
+                                query_iter.next().unwrap().len(),
+                                candidate_iter.next().unwrap(),
+                            ),
+                        }
+                    },
+                    (Some(_), None) => {
+                        (query_iter.next().unwrap().len(), &[][..])
+                    },
+                    (None, Some(_)) => (0, candidate_iter.next().unwrap()),
+                    (None, None) => break,
+                };
+
+            counts.add(query_count, candidate_group.len());
+
+            if RANGES && query_count > 0 {
+                for gram in candidate_group {
+                    self.covered[gram.clone()].fill(true);
+                }
+            }
+        }
+
+        if counts.intersection == 0 {
+            return None;
+        }
+
+        Some(counts.similarity(self.similarity))
+    }
+}
+
+impl Metric for NGram {
+    type Query<'a> = &'a str;
+
+    type Distance = NGramDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.similarity::<false>(query, candidate)
+            .map(NGramDistance::from_similarity)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let similarity = self.similarity::<true>(query, candidate)?;
+
+        let mut ranges = MatchedRanges::from(ranges);
+
+        let offsets = &self.candidate_grams.offsets;
+
+        for (&is_covered, offset) in self.covered.iter().zip(offsets) {
+            if let (true, Some(offset)) = (is_covered, offset) {
+                ranges.insert(offset.clone());
+            }
+        }
+
+        Some(NGramDistance::from_similarity(similarity))
+    }
+}
+
+/// The n-grams of a string.
+#[derive(Clone, Default)]
+struct Grams {
+    /// The characters of the string after they've been normalized and
+    /// case-folded, including the padding.
+    chars: Vec<char>,
+
+    /// The byte range in the original string of each character in `chars`,
+    /// or `None` for the padding.
+    offsets: Vec<Option<Range<usize>>>,
+
+    /// The n-grams, as ranges of indices into `chars`, sorted by their
+    /// characters.
+    grams: Vec<Range<usize>>,
+}
+
+impl Grams {
+    /// Splits the string into n-grams, replacing the previous ones.
+    #[inline]
+    fn fill(
+        &mut self,
+        s: &str,
+        n: usize,
+        padding: bool,
+        key: impl Fn(char) -> char,
+    ) {
+        self.chars.clear();
+        self.offsets.clear();
+        self.grams.clear();
+
+        if padding {
+            let mut word_start = None;
+
+            for (offset, ch) in s.char_indices() {
+                if ch.is_whitespace() {
+                    if let Some(start) = word_start.take() {
+                        self.push_word(s, start..offset, n - 1, n, &key);
+                    }
+                } else if word_start.is_none() {
+                    word_start = Some(offset);
+                }
+            }
+
+            if let Some(start) = word_start {
+                self.push_word(s, start..s.len(), n - 1, n, &key);
+            }
+        } else {
+            self.push_word(s, 0..s.len(), 0, n, &key);
+        }
+
+        let chars = &self.chars;
+
+        self.grams.sort_unstable_by(|lhs, rhs| {
+            chars[lhs.clone()].cmp(&chars[rhs.clone()])
+        });
+    }
+
+    /// Pushes the n-grams of the word at `s[word]`, padded with `pad_len`
+    /// characters on both sides.
+    #[inline]
+    fn push_word(
+        &mut self,
+        s: &str,
+        word: Range<usize>,
+        pad_len: usize,
+        n: usize,
+        key: impl Fn(char) -> char,
+    ) {
+        let start = self.chars.len();
+
+        self.push_padding(pad_len);
+
+        for (offset, ch) in s[word.clone()].char_indices() {
+            let offset = word.start + offset;
+            self.chars.push(key(ch));
+            self.offsets.push(Some(offset..offset + ch.len_utf8()));
+        }
+
+        self.push_padding(pad_len);
+
+        self.push_grams(start, n);
+    }
+
+    /// Pushes `len` padding characters.
+    #[inline(always)]
+    fn push_padding(&mut self, len: usize) {
+        for _ in 0..len {
+            self.chars.push(PAD);
+            self.offsets.push(None);
+        }
+    }
+
+    /// Pushes the n-grams of the characters from `start` to the end of
+    /// `self.chars`.
+    #[inline]
+    fn push_grams(&mut self, start: usize, n: usize) {
+        let end = self.chars.len();
+
+        if end - start <= n {
+            if end > start {
+                self.grams.push(start..end);
+            }
+            return;
+        }
+
+        self.grams.extend((start..=end - n).map(|idx| idx..idx + n));
+    }
+
+    /// Returns the characters of the given n-gram.
+    #[inline(always)]
+    fn gram(&self, gram: Range<usize>) -> &[char] {
+        &self.chars[gram]
+    }
+
+    /// Returns an iterator over the groups of equal n-grams.
+    #[inline]
+    fn groups(&self) -> impl Iterator<Item = &[Range<usize>]> + '_ {
+        self.grams.chunk_by(|lhs, rhs| {
+            self.gram(lhs.clone()) == self.gram(rhs.clone())
+        })
+    }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.grams.is_empty()
+    }
+}
+
+/// The counts needed to compute every [`NGramSimilarity`].
+#[derive(Default)]
+struct Counts {
+    /// The number of n-grams of the query.
+    query_len: usize,
+
+    /// The number of n-grams of the candidate.
+    candidate_len: usize,
+
+    /// The sum of the minimum number of times each n-gram appears in the
+    /// query and in the candidate.
+    intersection: usize,
+
+    /// The sum of the maximum number of times each n-gram appears in the
+    /// query and in the candidate.
+    union: usize,
+
+    /// The dot product of the count vectors of the query and the candidate.
+    dot: usize,
+
+    /// The squared norm of the count vector of the query.
+    query_norm: usize,
+
+    /// The squared norm of the count vector of the candidate.
+    candidate_norm: usize,
+}
+
+impl Counts {
+    /// Adds an n-gram that appears `query_count` times in the query and
+    /// `candidate_count` times in the candidate.
+    #[inline(always)]
+    fn add(&mut self, query_count: usize, candidate_count: usize) {
+        self.query_len += query_count;
+        self.candidate_len += candidate_count;
+        self.intersection += query_count.min(candidate_count);
+        self.union += query_count.max(candidate_count);
+        self.dot += query_count * candidate_count;
+        self.query_norm += query_count * query_count;
+        self.candidate_norm += candidate_count * candidate_count;
+    }
+
+    #[inline]
+    fn similarity(&self, similarity: NGramSimilarity) -> f64 {
+        let similarity = match similarity {
+            NGramSimilarity::Dice => {
+                (2 * self.intersection) as f64
+                    / (self.query_len + self.candidate_len) as f64
+            },
+            NGramSimilarity::Jaccard => {
+                self.intersection as f64 / self.union as f64
+            },
+            NGramSimilarity::Cosine => {
+                self.dot as f64
+                    / ((self.query_norm as f64).sqrt()
+                        * (self.candidate_norm as f64).sqrt())
+            },
+        };
+
+        // Rounding errors could make the cosine similarity slightly greater
+        // than 1.
+        similarity.min(1.0)
+    }
+}
@@ -0,0 +1,53 @@
+/// The phonetic distance type.
+///
+/// This struct is returned by [`Phonetic`](super::Phonetic)'s
+/// [`Metric`](crate::Metric) implementation. Distances are ordered first by
+/// the number of words of the candidate that didn't match any word of the
+/// query, and then by the number of words of the query that only matched a
+/// word of the candidate spelled differently.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PhoneticDistance {
+    /// The number of words of the candidate that didn't match any word of
+    /// the query.
+    unmatched_words: usize,
+
+    /// The number of words of the query that only matched words of the
+    /// candidate that are spelled differently.
+    inexact_words: usize,
+}
+
+impl PhoneticDistance {
+    /// Creates a new [`PhoneticDistance`].
+    #[inline(always)]
+    pub(super) fn new(unmatched_words: usize, inexact_words: usize) -> Self {
+        Self { unmatched_words, inexact_words }
+    }
+
+    /// Returns the number of words of the candidate whose code didn't match
+    /// the code of any word of the query.
+    #[inline(always)]
+    pub fn unmatched_words(self) -> usize {
+        self.unmatched_words
+    }
+
+    /// Returns the number of words of the query that only matched words of
+    /// the candidate that sound the same but are spelled differently, e.g.
+    /// `"Smyth"` for `"Smith"`.
+    #[inline(always)]
+    pub fn inexact_words(self) -> usize {
+        self.inexact_words
+    }
+}
+
+impl crate::combinators::Relevance for PhoneticDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        // Inexact words are mapped to `[0, 1)` so that they only break the
+        // ties between distances with the same number of unmatched words.
+        let inexact = self.inexact_words as f64;
+
+        let key = self.unmatched_words as f64 + inexact / (1.0 + inexact);
+
+        1.0 / (1.0 + key)
+    }
+}
@@ -0,0 +1,872 @@
+//! A port of the [Double Metaphone][dm] implementation of Apache Commons
+//! Codec.
+//!
+//! [dm]: https://en.wikipedia.org/wiki/Metaphone#Double_Metaphone
+
+/// Encodes a word with the Double Metaphone algorithm, returning its primary
+/// and alternate codes, each made of at most `4` characters.
+///
+/// The alternate code accounts for words of non-English origin that can be
+/// pronounced in more than one way, and it's equal to the primary code for
+/// most English words. For example, `"Smith"` is encoded as `("SM0",
+/// "XMT")`, which matches the codes of `"Schmidt"`, `("XMT", "SMT")`.
+///
+/// The letters `Ç` and `Ñ` are also supported, while any other non-ASCII
+/// letters and non-letter characters are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::phonetic::double_metaphone;
+/// assert_eq!(double_metaphone("Smith"), ("SM0".into(), "XMT".into()));
+/// assert_eq!(double_metaphone("Schmidt"), ("XMT".into(), "SMT".into()));
+/// assert_eq!(double_metaphone("Catherine"), ("K0RN".into(), "KTRN".into()));
+/// ```
+#[inline]
+pub fn double_metaphone(word: &str) -> (String, String) {
+    let word = word.chars().map(uppercase).collect::<Vec<_>>();
+    let mut primary = String::new();
+    let mut alternate = String::new();
+    encode(
+        &word,
+        super::MAX_CODE_LEN,
+        &mut Vec::new(),
+        &mut primary,
+        &mut alternate,
+    );
+    (primary, alternate)
+}
+
+/// Returns the uppercase version of the given character if it's an ASCII
+/// letter, `ç` or `ñ`, and the character itself otherwise.
+#[inline(always)]
+pub(super) fn uppercase(ch: char) -> char {
+    match ch {
+        'ç' => 'Ç',
+        'ñ' => 'Ñ',
+        _ => ch.to_ascii_uppercase(),
+    }
+}
+
+/// Appends the primary and alternate Double Metaphone codes of the given
+/// uppercase word to `primary` and `alternate`, using `value` as a scratch
+/// buffer.
+#[inline]
+pub(super) fn encode(
+    word: &[char],
+    max_len: usize,
+    value: &mut Vec<char>,
+    primary: &mut String,
+    alternate: &mut String,
+) {
+    value.clear();
+    value.extend(word.iter().copied().filter(|&ch| is_letter(ch)));
+
+    let mut encoder = Encoder {
+        value,
+        slavo_germanic: false,
+        primary,
+        primary_len: 0,
+        alternate,
+        alternate_len: 0,
+        max_len,
+    };
+
+    encoder.encode();
+}
+
+#[inline(always)]
+fn is_letter(ch: char) -> bool {
+    ch.is_ascii_uppercase() || matches!(ch, 'Ç' | 'Ñ')
+}
+
+#[inline(always)]
+fn is_vowel(ch: char) -> bool {
+    matches!(ch, 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+}
+
+struct Encoder<'a> {
+    /// The letters of the word.
+    value: &'a [char],
+
+    /// Whether the word looks Slavic or Germanic.
+    slavo_germanic: bool,
+
+    /// The primary code.
+    primary: &'a mut String,
+
+    /// The number of characters appended to `primary`.
+    primary_len: usize,
+
+    /// The alternate code.
+    alternate: &'a mut String,
+
+    /// The number of characters appended to `alternate`.
+    alternate_len: usize,
+
+    /// The maximum length of both codes.
+    max_len: usize,
+}
+
+impl Encoder<'_> {
+    #[inline]
+    fn encode(&mut self) {
+        if self.value.is_empty() {
+            return;
+        }
+
+        self.slavo_germanic = self.value.contains(&'W')
+            || self.value.contains(&'K')
+            || self.value.windows(2).any(|w| w == ['C', 'Z']);
+
+        let mut index: isize =
+            if self.contains(0, 2, &["GN", "KN", "PN", "WR", "PS"]) {
+                1
+            } else {
+                0
+            };
+
+        let len = self.len();
+
+        while !self.is_complete() && index < len {
+            index = match self.at(index) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if index == 0 {
+                        self.add('A');
+                    }
+                    index + 1
+                },
+
+                'B' => {
+                    self.add('P');
+                    self.skip_double(index, 'B')
+                },
+
+                'Ç' => {
+                    self.add('S');
+                    index + 1
+                },
+
+                'C' => self.handle_c(index),
+
+                'D' => self.handle_d(index),
+
+                'F' => {
+                    self.add('F');
+                    self.skip_double(index, 'F')
+                },
+
+                'G' => self.handle_g(index),
+
+                'H' => self.handle_h(index),
+
+                'J' => self.handle_j(index),
+
+                'K' => {
+                    self.add('K');
+                    self.skip_double(index, 'K')
+                },
+
+                'L' => self.handle_l(index),
+
+                'M' => {
+                    self.add('M');
+                    if self.condition_m0(index) {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                },
+
+                'N' => {
+                    self.add('N');
+                    self.skip_double(index, 'N')
+                },
+
+                'Ñ' => {
+                    self.add('N');
+                    index + 1
+                },
+
+                'P' => self.handle_p(index),
+
+                'Q' => {
+                    self.add('K');
+                    self.skip_double(index, 'Q')
+                },
+
+                'R' => self.handle_r(index),
+
+                'S' => self.handle_s(index),
+
+                'T' => self.handle_t(index),
+
+                'V' => {
+                    self.add('F');
+                    self.skip_double(index, 'V')
+                },
+
+                'W' => self.handle_w(index),
+
+                'X' => self.handle_x(index),
+
+                'Z' => self.handle_z(index),
+
+                _ => index + 1,
+            };
+        }
+    }
+
+    #[inline]
+    fn handle_c(&mut self, mut index: isize) -> isize {
+        if self.condition_c0(index) {
+            self.add('K');
+            index += 2;
+        } else if index == 0 && self.contains(index, 6, &["CAESAR"]) {
+            self.add('S');
+            index += 2;
+        } else if self.contains(index, 2, &["CH"]) {
+            index = self.handle_ch(index);
+        } else if self.contains(index, 2, &["CZ"])
+            && !self.contains(index - 2, 4, &["WICZ"])
+        {
+            // "Czerny".
+            self.add_both('S', 'X');
+            index += 2;
+        } else if self.contains(index + 1, 3, &["CIA"]) {
+            // "focaccia".
+            self.add('X');
+            index += 3;
+        } else if self.contains(index, 2, &["CC"])
+            && !(index == 1 && self.at(0) == 'M')
+        {
+            // Double "cc" but not "McClelland".
+            return self.handle_cc(index);
+        } else if self.contains(index, 2, &["CK", "CG", "CQ"]) {
+            self.add('K');
+            index += 2;
+        } else if self.contains(index, 2, &["CI", "CE", "CY"]) {
+            // Italian vs. English.
+            if self.contains(index, 3, &["CIO", "CIE", "CIA"]) {
+                self.add_both('S', 'X');
+            } else {
+                self.add('S');
+            }
+            index += 2;
+        } else {
+            self.add('K');
+            if self.contains(index + 1, 2, &[" C", " Q", " G"]) {
+                // "Mac Caffrey", "Mac Gregor".
+                index += 3;
+            } else if self.contains(index + 1, 1, &["C", "K", "Q"])
+                && !self.contains(index + 1, 2, &["CE", "CI"])
+            {
+                index += 2;
+            } else {
+                index += 1;
+            }
+        }
+        index
+    }
+
+    #[inline]
+    fn handle_cc(&mut self, mut index: isize) -> isize {
+        if self.contains(index + 2, 1, &["I", "E", "H"])
+            && !self.contains(index + 2, 2, &["HU"])
+        {
+            // "bellocchio" but not "bacchus".
+            if (index == 1 && self.at(index - 1) == 'A')
+                || self.contains(index - 1, 5, &["UCCEE", "UCCES"])
+            {
+                // "accident", "accede", "succeed".
+                self.add_str("KS", "KS");
+            } else {
+                // "bacci", "bertucci", other Italian.
+                self.add('X');
+            }
+            index += 3;
+        } else {
+            // Pierce's rule.
+            self.add('K');
+            index += 2;
+        }
+        index
+    }
+
+    #[inline]
+    fn handle_ch(&mut self, index: isize) -> isize {
+        if index > 0 && self.contains(index, 4, &["CHAE"]) {
+            // "Michael".
+            self.add_both('K', 'X');
+        } else if self.condition_ch0(index) {
+            // Greek roots, e.g. "chemistry", "chorus".
+            self.add('K');
+        } else if self.condition_ch1(index) {
+            // Germanic, Greek, or otherwise "ch" for "kh" sound.
+            self.add('K');
+        } else if index > 0 {
+            if self.contains(0, 2, &["MC"]) {
+                self.add('K');
+            } else {
+                self.add_both('X', 'K');
+            }
+        } else {
+            self.add('X');
+        }
+        index + 2
+    }
+
+    #[inline]
+    fn handle_d(&mut self, index: isize) -> isize {
+        if self.contains(index, 2, &["DG"]) {
+            if self.contains(index + 2, 1, &["I", "E", "Y"]) {
+                // "edge".
+                self.add('J');
+                index + 3
+            } else {
+                // "Edgar".
+                self.add_str("TK", "TK");
+                index + 2
+            }
+        } else if self.contains(index, 2, &["DT", "DD"]) {
+            self.add('T');
+            index + 2
+        } else {
+            self.add('T');
+            index + 1
+        }
+    }
+
+    #[inline]
+    fn handle_g(&mut self, mut index: isize) -> isize {
+        let slavo_germanic = self.slavo_germanic;
+
+        if self.at(index + 1) == 'H' {
+            index = self.handle_gh(index);
+        } else if self.at(index + 1) == 'N' {
+            if index == 1 && is_vowel(self.at(0)) && !slavo_germanic {
+                self.add_str("KN", "N");
+            } else if !self.contains(index + 2, 2, &["EY"])
+                && self.at(index + 1) != 'Y'
+                && !slavo_germanic
+            {
+                self.add_str("N", "KN");
+            } else {
+                self.add_str("KN", "KN");
+            }
+            index += 2;
+        } else if self.contains(index + 1, 2, &["LI"]) && !slavo_germanic {
+            self.add_str("KL", "L");
+            index += 2;
+        } else if index == 0
+            && (self.at(index + 1) == 'Y'
+                || self.contains(
+                    index + 1,
+                    2,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE",
+                        "EI", "ER",
+                    ],
+                ))
+        {
+            // "-ges-", "-gep-", "-gel-", "-gie-" at the beginning.
+            self.add_both('K', 'J');
+            index += 2;
+        } else if (self.contains(index + 1, 2, &["ER"])
+            || self.at(index + 1) == 'Y')
+            && !self.contains(0, 6, &["DANGER", "RANGER", "MANGER"])
+            && !self.contains(index - 1, 1, &["E", "I"])
+            && !self.contains(index - 1, 3, &["RGY", "OGY"])
+        {
+            // "-ger-", "-gy-".
+            self.add_both('K', 'J');
+            index += 2;
+        } else if self.contains(index + 1, 1, &["E", "I", "Y"])
+            || self.contains(index - 1, 4, &["AGGI", "OGGI"])
+        {
+            // Italian "biaggi".
+            if self.contains(0, 4, &["VAN ", "VON "])
+                || self.contains(0, 3, &["SCH"])
+                || self.contains(index + 1, 2, &["ET"])
+            {
+                // Obvious Germanic.
+                self.add('K');
+            } else if self.contains(index + 1, 3, &["IER"]) {
+                self.add('J');
+            } else {
+                self.add_both('J', 'K');
+            }
+            index += 2;
+        } else if self.at(index + 1) == 'G' {
+            self.add('K');
+            index += 2;
+        } else {
+            self.add('K');
+            index += 1;
+        }
+        index
+    }
+
+    #[inline]
+    fn handle_gh(&mut self, index: isize) -> isize {
+        if index > 0 && !is_vowel(self.at(index - 1)) {
+            self.add('K');
+        } else if index == 0 {
+            if self.at(index + 2) == 'I' {
+                self.add('J');
+            } else {
+                self.add('K');
+            }
+        } else if (index > 1 && self.contains(index - 2, 1, &["B", "H", "D"]))
+            || (index > 2 && self.contains(index - 3, 1, &["B", "H", "D"]))
+            || (index > 3 && self.contains(index - 4, 1, &["B", "H"]))
+        {
+            // Parker's rule (with some further refinements), e.g. "hugh".
+        } else if index > 2
+            && self.at(index - 1) == 'U'
+            && self.contains(index - 3, 1, &["C", "G", "L", "R", "T"])
+        {
+            // "laugh", "McLaughlin", "cough", "gough", "rough", "tough".
+            self.add('F');
+        } else if index > 0 && self.at(index - 1) != 'I' {
+            self.add('K');
+        }
+        index + 2
+    }
+
+    #[inline]
+    fn handle_h(&mut self, index: isize) -> isize {
+        // Only keep if first & before vowel or between 2 vowels.
+        if (index == 0 || is_vowel(self.at(index - 1)))
+            && is_vowel(self.at(index + 1))
+        {
+            self.add('H');
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    #[inline]
+    fn handle_j(&mut self, index: isize) -> isize {
+        if self.contains(index, 4, &["JOSE"]) || self.contains(0, 4, &["SAN "])
+        {
+            // Obvious Spanish, "Jose", "San Jacinto".
+            if (index == 0 && self.at(index + 4) == ' ')
+                || self.len() == 4
+                || self.contains(0, 4, &["SAN "])
+            {
+                self.add('H');
+            } else {
+                self.add_both('J', 'H');
+            }
+            return index + 1;
+        }
+
+        if index == 0 {
+            self.add_both('J', 'A');
+        } else if is_vowel(self.at(index - 1))
+            && !self.slavo_germanic
+            && matches!(self.at(index + 1), 'A' | 'O')
+        {
+            self.add_both('J', 'H');
+        } else if index == self.len() - 1 {
+            self.add_both('J', ' ');
+        } else if !self.contains(
+            index + 1,
+            1,
+            &["L", "T", "K", "S", "N", "M", "B", "Z"],
+        ) && !self.contains(index - 1, 1, &["S", "K", "L"])
+        {
+            self.add('J');
+        }
+
+        self.skip_double(index, 'J')
+    }
+
+    #[inline]
+    fn handle_l(&mut self, index: isize) -> isize {
+        if self.at(index + 1) == 'L' {
+            if self.condition_l0(index) {
+                self.add_primary("L");
+            } else {
+                self.add('L');
+            }
+            index + 2
+        } else {
+            self.add('L');
+            index + 1
+        }
+    }
+
+    #[inline]
+    fn handle_p(&mut self, index: isize) -> isize {
+        if self.at(index + 1) == 'H' {
+            self.add('F');
+            index + 2
+        } else {
+            self.add('P');
+            if self.contains(index + 1, 1, &["P", "B"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    #[inline]
+    fn handle_r(&mut self, index: isize) -> isize {
+        if index == self.len() - 1
+            && !self.slavo_germanic
+            && self.contains(index - 2, 2, &["IE"])
+            && !self.contains(index - 4, 2, &["ME", "MA"])
+        {
+            self.add_alternate("R");
+        } else {
+            self.add('R');
+        }
+        self.skip_double(index, 'R')
+    }
+
+    #[inline]
+    fn handle_s(&mut self, index: isize) -> isize {
+        if self.contains(index - 1, 3, &["ISL", "YSL"]) {
+            // Special cases "island", "isle", "carlisle", "carlysle".
+            index + 1
+        } else if index == 0 && self.contains(index, 5, &["SUGAR"]) {
+            // Special case "sugar-".
+            self.add_both('X', 'S');
+            index + 1
+        } else if self.contains(index, 2, &["SH"]) {
+            if self.contains(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                // Germanic.
+                self.add('S');
+            } else {
+                self.add('X');
+            }
+            index + 2
+        } else if self.contains(index, 3, &["SIO", "SIA"])
+            || self.contains(index, 4, &["SIAN"])
+        {
+            // Italian and Armenian.
+            if self.slavo_germanic {
+                self.add('S');
+            } else {
+                self.add_both('S', 'X');
+            }
+            index + 3
+        } else if (index == 0
+            && self.contains(index + 1, 1, &["M", "N", "L", "W"]))
+            || self.contains(index + 1, 1, &["Z"])
+        {
+            // German and anglicisations, e.g. "smith" matches "schmidt" and
+            // "snider" matches "schneider". Also, "-sz-" in Slavic languages
+            // although in Hungarian it's pronounced "s".
+            self.add_both('S', 'X');
+            if self.contains(index + 1, 1, &["Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        } else if self.contains(index, 2, &["SC"]) {
+            self.handle_sc(index)
+        } else {
+            if index == self.len() - 1
+                && self.contains(index - 2, 2, &["AI", "OI"])
+            {
+                // French, e.g. "resnais", "artois".
+                self.add_alternate("S");
+            } else {
+                self.add('S');
+            }
+            if self.contains(index + 1, 1, &["S", "Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    #[inline]
+    fn handle_sc(&mut self, index: isize) -> isize {
+        if self.at(index + 2) == 'H' {
+            // Schlesinger's rule.
+            if self.contains(
+                index + 3,
+                2,
+                &["OO", "ER", "EN", "UY", "ED", "EM"],
+            ) {
+                // Dutch origin, e.g. "school", "schooner".
+                if self.contains(index + 3, 2, &["ER", "EN"]) {
+                    // "schermerhorn", "schenker".
+                    self.add_str("X", "SK");
+                } else {
+                    self.add_str("SK", "SK");
+                }
+            } else if index == 0 && !is_vowel(self.at(3)) && self.at(3) != 'W'
+            {
+                self.add_both('X', 'S');
+            } else {
+                self.add('X');
+            }
+        } else if self.contains(index + 2, 1, &["I", "E", "Y"]) {
+            self.add('S');
+        } else {
+            self.add_str("SK", "SK");
+        }
+        index + 3
+    }
+
+    #[inline]
+    fn handle_t(&mut self, index: isize) -> isize {
+        if self.contains(index, 4, &["TION"])
+            || self.contains(index, 3, &["TIA", "TCH"])
+        {
+            self.add('X');
+            index + 3
+        } else if self.contains(index, 2, &["TH"])
+            || self.contains(index, 3, &["TTH"])
+        {
+            if self.contains(index + 2, 2, &["OM", "AM"])
+                // Special case "thomas", "thames" or Germanic.
+                || self.contains(0, 4, &["VAN ", "VON "])
+                || self.contains(0, 3, &["SCH"])
+            {
+                self.add('T');
+            } else {
+                self.add_both('0', 'T');
+            }
+            index + 2
+        } else {
+            self.add('T');
+            if self.contains(index + 1, 1, &["T", "D"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    #[inline]
+    fn handle_w(&mut self, index: isize) -> isize {
+        if self.contains(index, 2, &["WR"]) {
+            // Can also be in the middle of a word.
+            self.add('R');
+            index + 2
+        } else if index == 0
+            && (is_vowel(self.at(index + 1))
+                || self.contains(index, 2, &["WH"]))
+        {
+            if is_vowel(self.at(index + 1)) {
+                // "Wasserman" should match "Vasserman".
+                self.add_both('A', 'F');
+            } else {
+                // Need "Uomo" to match "Womo".
+                self.add('A');
+            }
+            index + 1
+        } else if (index == self.len() - 1 && is_vowel(self.at(index - 1)))
+            || self.contains(
+                index - 1,
+                5,
+                &["EWSKI", "EWSKY", "OWSKI", "OWSKY"],
+            )
+            || self.contains(0, 3, &["SCH"])
+        {
+            // "Arnow" should match "Arnoff".
+            self.add_alternate("F");
+            index + 1
+        } else if self.contains(index, 4, &["WICZ", "WITZ"]) {
+            // Polish, e.g. "filipowicz".
+            self.add_str("TS", "FX");
+            index + 4
+        } else {
+            index + 1
+        }
+    }
+
+    #[inline]
+    fn handle_x(&mut self, index: isize) -> isize {
+        if index == 0 {
+            self.add('S');
+            return index + 1;
+        }
+
+        let is_french = index == self.len() - 1
+            && (self.contains(index - 3, 3, &["IAU", "EAU"])
+                || self.contains(index - 2, 2, &["AU", "OU"]));
+
+        // The final "x" is silent in French, e.g. "breaux".
+        if !is_french {
+            self.add_str("KS", "KS");
+        }
+
+        if self.contains(index + 1, 1, &["C", "X"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    #[inline]
+    fn handle_z(&mut self, index: isize) -> isize {
+        if self.at(index + 1) == 'H' {
+            // Chinese pinyin, e.g. "zhao".
+            self.add('J');
+            return index + 2;
+        }
+
+        if self.contains(index + 1, 2, &["ZO", "ZI", "ZA"])
+            || (self.slavo_germanic && index > 0 && self.at(index - 1) != 'T')
+        {
+            self.add_str("S", "TS");
+        } else {
+            self.add('S');
+        }
+
+        self.skip_double(index, 'Z')
+    }
+
+    #[inline]
+    fn condition_c0(&self, index: isize) -> bool {
+        if self.contains(index, 4, &["CHIA"]) {
+            true
+        } else if index <= 1
+            || is_vowel(self.at(index - 2))
+            || !self.contains(index - 1, 3, &["ACH"])
+        {
+            false
+        } else {
+            let ch = self.at(index + 2);
+            (ch != 'I' && ch != 'E')
+                || self.contains(index - 2, 6, &["BACHER", "MACHER"])
+        }
+    }
+
+    #[inline]
+    fn condition_ch0(&self, index: isize) -> bool {
+        index == 0
+            && (self.contains(index + 1, 5, &["HARAC", "HARIS"])
+                || self.contains(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.contains(0, 5, &["CHORE"])
+    }
+
+    #[inline]
+    fn condition_ch1(&self, index: isize) -> bool {
+        self.contains(0, 4, &["VAN ", "VON "])
+            || self.contains(0, 3, &["SCH"])
+            || self.contains(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.contains(index + 2, 1, &["T", "S"])
+            || ((self.contains(index - 1, 1, &["A", "O", "U", "E"])
+                || index == 0)
+                && (self.contains(
+                    index + 2,
+                    1,
+                    &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+                ) || index + 1 == self.len() - 1))
+    }
+
+    #[inline]
+    fn condition_l0(&self, index: isize) -> bool {
+        let len = self.len();
+
+        (index == len - 3
+            && self.contains(index - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+            || ((self.contains(len - 2, 2, &["AS", "OS"])
+                || self.contains(len - 1, 1, &["A", "O"]))
+                && self.contains(index - 1, 4, &["ALLE"]))
+    }
+
+    #[inline]
+    fn condition_m0(&self, index: isize) -> bool {
+        self.at(index + 1) == 'M'
+            || (self.contains(index - 1, 3, &["UMB"])
+                && (index + 1 == self.len() - 1
+                    || self.contains(index + 2, 2, &["ER"])))
+    }
+
+    /// Returns the character at the given index, or `'\0'` if the index is
+    /// out of bounds.
+    #[inline(always)]
+    fn at(&self, index: isize) -> char {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.value.get(index))
+            .copied()
+            .unwrap_or('\0')
+    }
+
+    /// Returns whether the `len` characters starting at `start` are equal to
+    /// any of the given strings.
+    #[inline(always)]
+    fn contains(&self, start: isize, len: usize, criteria: &[&str]) -> bool {
+        let Ok(start) = usize::try_from(start) else { return false };
+
+        let Some(target) = self.value.get(start..start + len) else {
+            return false;
+        };
+
+        criteria
+            .iter()
+            .any(|criterion| target.iter().copied().eq(criterion.chars()))
+    }
+
+    #[inline(always)]
+    fn len(&self) -> isize {
+        self.value.len() as isize
+    }
+
+    /// Returns the index after the given one, skipping the next character if
+    /// it's equal to `ch`.
+    #[inline(always)]
+    fn skip_double(&self, index: isize, ch: char) -> isize {
+        if self.at(index + 1) == ch {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    #[inline(always)]
+    fn is_complete(&self) -> bool {
+        self.primary_len >= self.max_len && self.alternate_len >= self.max_len
+    }
+
+    /// Appends the same character to both codes.
+    #[inline(always)]
+    fn add(&mut self, ch: char) {
+        self.add_both(ch, ch);
+    }
+
+    /// Appends a character to each code.
+    #[inline(always)]
+    fn add_both(&mut self, primary: char, alternate: char) {
+        self.add_str(
+            primary.encode_utf8(&mut [0; 4]),
+            alternate.encode_utf8(&mut [0; 4]),
+        );
+    }
+
+    /// Appends a string to each code.
+    #[inline(always)]
+    fn add_str(&mut self, primary: &str, alternate: &str) {
+        self.add_primary(primary);
+        self.add_alternate(alternate);
+    }
+
+    #[inline(always)]
+    fn add_primary(&mut self, s: &str) {
+        let len =
+            s.len().min(self.max_len - self.primary_len.min(self.max_len));
+        self.primary.push_str(&s[..len]);
+        self.primary_len += len;
+    }
+
+    #[inline(always)]
+    fn add_alternate(&mut self, s: &str) {
+        let len =
+            s.len().min(self.max_len - self.alternate_len.min(self.max_len));
+        self.alternate.push_str(&s[..len]);
+        self.alternate_len += len;
+    }
+}
@@ -0,0 +1,238 @@
+//! A port of the [Metaphone][metaphone] implementation of Apache Commons
+//! Codec.
+//!
+//! [metaphone]: https://en.wikipedia.org/wiki/Metaphone
+
+/// Encodes a word with the original Metaphone algorithm, keeping at most
+/// `4` characters, e.g. `"Smith"` and `"Smyth"` are both encoded as
+/// `"SM0"`, where the `0` stands for the "th" sound.
+///
+/// Non-ASCII letters and any other characters are ignored, and words without
+/// any ASCII letter are encoded as an empty string.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::phonetic::metaphone;
+/// assert_eq!(metaphone("Smith"), "SM0");
+/// assert_eq!(metaphone("Smyth"), "SM0");
+/// assert_eq!(metaphone("Knight"), "NT");
+/// assert_eq!(metaphone("Catherine"), "K0RN");
+/// ```
+#[inline]
+pub fn metaphone(word: &str) -> String {
+    let word =
+        word.chars().map(|ch| ch.to_ascii_uppercase()).collect::<Vec<_>>();
+    let mut code = String::new();
+    encode(&word, super::MAX_CODE_LEN, &mut Vec::new(), &mut code);
+    code
+}
+
+/// Appends the Metaphone code of the given uppercase word to `code`, using
+/// `local` as a scratch buffer.
+#[inline]
+pub(super) fn encode(
+    word: &[char],
+    max_len: usize,
+    local: &mut Vec<char>,
+    code: &mut String,
+) {
+    local.clear();
+    local.extend(word.iter().copied().filter(char::is_ascii_uppercase));
+
+    match local.len() {
+        0 => return,
+        1 => return code.push(local[0]),
+        _ => {},
+    }
+
+    match (local[0], local[1]) {
+        ('K' | 'G' | 'P', 'N') | ('A', 'E') | ('W', 'R') => {
+            local.remove(0);
+        },
+        ('W', 'H') => {
+            local.remove(1);
+        },
+        ('X', _) => local[0] = 'S',
+        _ => {},
+    }
+
+    let local = &**local;
+
+    let len = local.len();
+
+    let start = code.len();
+
+    let mut n = 0;
+
+    while code.len() - start < max_len && n < len {
+        let symb = local[n];
+
+        if symb != 'C' && is_previous(local, n, symb) {
+            n += 1;
+            continue;
+        }
+
+        match symb {
+            'A' | 'E' | 'I' | 'O' | 'U' if n == 0 => code.push(symb),
+
+            // Silent in a final "MB".
+            'B' if !(is_previous(local, n, 'M') && is_last(len, n)) => {
+                code.push(symb)
+            },
+
+            'C' => {
+                if is_previous(local, n, 'S')
+                    && !is_last(len, n)
+                    && is_front_vowel(local[n + 1])
+                {
+                    // Silent in "SCI", "SCE" and "SCY".
+                } else if region_matches(local, n, "CIA") {
+                    code.push('X');
+                } else if !is_last(len, n) && is_front_vowel(local[n + 1]) {
+                    code.push('S');
+                } else if is_previous(local, n, 'S') && is_next(local, n, 'H')
+                {
+                    code.push('K');
+                } else if is_next(local, n, 'H') {
+                    if n == 0 && len >= 3 && is_vowel(local, 2) {
+                        code.push('K');
+                    } else {
+                        code.push('X');
+                    }
+                } else {
+                    code.push('K');
+                }
+            },
+
+            'D' => {
+                if !is_last(len, n + 1)
+                    && is_next(local, n, 'G')
+                    && is_front_vowel(local[n + 2])
+                {
+                    code.push('J');
+                    n += 2;
+                } else {
+                    code.push('T');
+                }
+            },
+
+            'G' => {
+                let is_silent = (is_last(len, n + 1)
+                    && is_next(local, n, 'H'))
+                    || (!is_last(len, n + 1)
+                        && is_next(local, n, 'H')
+                        && !is_vowel(local, n + 2))
+                    || (n > 0
+                        && (region_matches(local, n, "GN")
+                            || region_matches(local, n, "GNED")));
+
+                if is_silent {
+                    // Silent in "GH" and "GN".
+                } else if !is_last(len, n)
+                    && is_front_vowel(local[n + 1])
+                    && !is_previous(local, n, 'G')
+                {
+                    code.push('J');
+                } else {
+                    code.push('K');
+                }
+            },
+
+            'H' => {
+                let is_silent = is_last(len, n)
+                    || (n > 0
+                        && matches!(
+                            local[n - 1],
+                            'C' | 'S' | 'P' | 'T' | 'G'
+                        ));
+
+                if !is_silent && is_vowel(local, n + 1) {
+                    code.push('H');
+                }
+            },
+
+            'F' | 'J' | 'L' | 'M' | 'N' | 'R' => code.push(symb),
+
+            'K' if !is_previous(local, n, 'C') => code.push(symb),
+
+            'P' => {
+                code.push(if is_next(local, n, 'H') { 'F' } else { 'P' });
+            },
+
+            'Q' => code.push('K'),
+
+            'S' => {
+                if region_matches(local, n, "SH")
+                    || region_matches(local, n, "SIO")
+                    || region_matches(local, n, "SIA")
+                {
+                    code.push('X');
+                } else {
+                    code.push('S');
+                }
+            },
+
+            'T' => {
+                if region_matches(local, n, "TIA")
+                    || region_matches(local, n, "TIO")
+                {
+                    code.push('X');
+                } else if region_matches(local, n, "TCH") {
+                    // Silent in "TCH".
+                } else if region_matches(local, n, "TH") {
+                    code.push('0');
+                } else {
+                    code.push('T');
+                }
+            },
+
+            'V' => code.push('F'),
+
+            'W' | 'Y' if !is_last(len, n) && is_vowel(local, n + 1) => {
+                code.push(symb)
+            },
+
+            'X' => code.push_str("KS"),
+
+            'Z' => code.push('S'),
+
+            _ => {},
+        }
+
+        n += 1;
+    }
+
+    code.truncate(start + max_len);
+}
+
+#[inline(always)]
+fn is_front_vowel(ch: char) -> bool {
+    matches!(ch, 'E' | 'I' | 'Y')
+}
+
+#[inline(always)]
+fn is_last(len: usize, idx: usize) -> bool {
+    idx + 1 == len
+}
+
+#[inline(always)]
+fn is_next(local: &[char], idx: usize, ch: char) -> bool {
+    local.get(idx + 1) == Some(&ch)
+}
+
+#[inline(always)]
+fn is_previous(local: &[char], idx: usize, ch: char) -> bool {
+    idx > 0 && local[idx - 1] == ch
+}
+
+#[inline(always)]
+fn is_vowel(local: &[char], idx: usize) -> bool {
+    matches!(local.get(idx), Some('A' | 'E' | 'I' | 'O' | 'U'))
+}
+
+#[inline(always)]
+fn region_matches(local: &[char], idx: usize, region: &str) -> bool {
+    local.len() >= idx + region.len()
+        && local[idx..idx + region.len()].iter().copied().eq(region.chars())
+}
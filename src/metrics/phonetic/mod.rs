@@ -0,0 +1,19 @@
+//! Phonetic encoders and the metric built on top of them.
+//!
+//! See the documentation of [`Phonetic`] for more information.
+
+mod distance;
+mod double_metaphone;
+mod metaphone;
+mod phonetic;
+mod soundex;
+
+pub use distance::PhoneticDistance;
+pub use double_metaphone::double_metaphone;
+pub use metaphone::metaphone;
+pub use phonetic::{Phonetic, PhoneticAlgorithm};
+pub use soundex::soundex;
+
+/// The maximum length of the codes produced by the Metaphone and Double
+/// Metaphone encoders.
+const MAX_CODE_LEN: usize = 4;
@@ -0,0 +1,457 @@
+use core::ops::Range;
+
+use super::*;
+use crate::normalize::normalize;
+use crate::{MatchedRanges, Metric};
+
+/// The phonetic algorithms a [`Phonetic`] metric can use to encode words.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneticAlgorithm {
+    /// The American Soundex algorithm, see [`soundex`].
+    ///
+    /// Soundex always keeps the first letter of a word, so words that sound
+    /// the same but start with different letters, like `"Katherine"` and
+    /// `"Catherine"`, don't match.
+    Soundex,
+
+    /// The original Metaphone algorithm, see [`metaphone`].
+    Metaphone,
+
+    /// The Double Metaphone algorithm, see [`double_metaphone`].
+    ///
+    /// Two words match if either the primary or the alternate code of one of
+    /// them is equal to either code of the other.
+    #[default]
+    DoubleMetaphone,
+}
+
+/// A metric that matches the words of the query and of the candidate based on
+/// how they sound.
+///
+/// Both strings are split into words on every non-alphabetic character, and
+/// every word is encoded with the [`PhoneticAlgorithm`] set with
+/// [`set_algorithm`](Phonetic::set_algorithm). A candidate matches the query
+/// if the code of every word of the query is equal to the code of at least one
+/// word of the candidate, regardless of their order.
+///
+/// Phonetic codes ignore the case of the words, and the ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// words of the candidate whose code matched the code of a word of the query.
+/// See [`PhoneticDistance`] for how candidates are ranked.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::phonetic::Phonetic;
+/// # use norm::Metric;
+/// let mut phonetic = Phonetic::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance = phonetic
+///     .distance_and_ranges("smyth", "John Smith", &mut ranges)
+///     .unwrap();
+///
+/// assert_eq!(distance.unmatched_words(), 1);
+/// assert_eq!(distance.inexact_words(), 1);
+///
+/// assert_eq!(ranges, [5..10]);
+///
+/// assert!(phonetic.distance("Katherine", "Catherine").is_some());
+///
+/// assert!(phonetic.distance("Smyth", "Jones").is_none());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "phonetic")))]
+#[derive(Clone, Default)]
+pub struct Phonetic {
+    /// The encoder used to turn every word into its phonetic code.
+    algorithm: PhoneticAlgorithm,
+
+    /// Whether multi-byte latin characters are normalized to ASCII before the
+    /// words of both strings are encoded.
+    candidate_normalization: bool,
+
+    /// The words of the last query.
+    query_words: Words,
+
+    /// The words of the last candidate.
+    candidate_words: Words,
+
+    /// A scratch buffer used by the encoders.
+    scratch: Vec<char>,
+
+    /// Whether each word of the last candidate matched a word of the query.
+    matched: Vec<bool>,
+}
+
+impl core::fmt::Debug for Phonetic {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Phonetic")
+            .field("algorithm", &self.algorithm)
+            .field("candidate_normalization", &self.candidate_normalization)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Phonetic {
+    /// Creates a new `Phonetic`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the algorithm used to encode the words of the query and the
+    /// candidate. The default is [`PhoneticAlgorithm::DoubleMetaphone`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::phonetic::{Phonetic, PhoneticAlgorithm};
+    /// # use norm::Metric;
+    /// let mut phonetic = Phonetic::new();
+    ///
+    /// assert!(phonetic.distance("Katherine", "Catherine").is_some());
+    ///
+    /// phonetic.set_algorithm(PhoneticAlgorithm::Soundex);
+    ///
+    /// // K365 and C365.
+    /// assert!(phonetic.distance("Katherine", "Catherine").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_algorithm(
+        &mut self,
+        algorithm: PhoneticAlgorithm,
+    ) -> &mut Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets whether multi-byte latin characters should be normalized to ASCII
+    /// before encoding the words. The default is `false`.
+    ///
+    /// The encoders skip the letters they don't know about, which are all the
+    /// non-ASCII ones except for Double Metaphone's `'Ç'` and `'Ñ'`. Without
+    /// normalization a word like `"Dvořák"` therefore loses its `'ř'` and
+    /// `'á'` before it's encoded. The words of the query are normalized too,
+    /// so that a query written with diacritics still gets the same code as
+    /// the candidate it's meant to find.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::phonetic::Phonetic;
+    /// # use norm::Metric;
+    /// let mut phonetic = Phonetic::new();
+    ///
+    /// // The "ř" is ignored by the encoder.
+    /// assert!(phonetic.distance("Dvorak", "Dvořák").is_none());
+    ///
+    /// phonetic.set_candidate_normalization(true);
+    ///
+    /// assert!(phonetic.distance("Dvorak", "Dvořák").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Computes the distance between the query and the candidate, leaving the
+    /// words of the candidate that matched in `self.matched`.
+    #[inline]
+    fn match_words(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<PhoneticDistance> {
+        let algorithm = self.algorithm;
+
+        let normalization = self.candidate_normalization;
+
+        self.query_words.fill(
+            query,
+            algorithm,
+            normalization,
+            &mut self.scratch,
+        );
+
+        self.candidate_words.fill(
+            candidate,
+            algorithm,
+            normalization,
+            &mut self.scratch,
+        );
+
+        let query_words = &self.query_words;
+
+        let candidate_words = &self.candidate_words;
+
+        if query_words.is_empty() || candidate_words.is_empty() {
+            self.matched.clear();
+
+            return (query_words.is_empty() && candidate_words.is_empty())
+                .then(PhoneticDistance::default);
+        }
+
+        self.matched.clear();
+        self.matched.resize(candidate_words.len(), false);
+
+        let mut inexact_words = 0;
+
+        for query_word in &query_words.words {
+            let mut is_matched = false;
+
+            let mut is_exact = false;
+
+            for (candidate_word, matched) in
+                candidate_words.words.iter().zip(&mut self.matched)
+            {
+                if !query_words.sounds_like(
+                    query_word,
+                    candidate_words,
+                    candidate_word,
+                ) {
+                    continue;
+                }
+
+                is_matched = true;
+
+                is_exact |= query_words.letters(query_word)
+                    == candidate_words.letters(candidate_word);
+
+                *matched = true;
+            }
+
+            if !is_matched {
+                return None;
+            }
+
+            inexact_words += !is_exact as usize;
+        }
+
+        let unmatched_words =
+            self.matched.iter().filter(|&&matched| !matched).count();
+
+        Some(PhoneticDistance::new(unmatched_words, inexact_words))
+    }
+}
+
+impl Metric for Phonetic {
+    type Query<'a> = &'a str;
+
+    type Distance = PhoneticDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.match_words(query, candidate)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let distance = self.match_words(query, candidate)?;
+
+        let mut ranges = MatchedRanges::from(ranges);
+
+        for (word, &is_matched) in
+            self.candidate_words.words.iter().zip(&self.matched)
+        {
+            if is_matched {
+                ranges.insert(word.original.clone());
+            }
+        }
+
+        Some(distance)
+    }
+}
+
+/// The encoded words of a string.
+#[derive(Clone, Default)]
+struct Words {
+    /// The uppercase letters of the words, one word after the other.
+    letters: Vec<char>,
+
+    /// The codes of the words, one after the other.
+    codes: String,
+
+    /// The words whose code is not empty, in the order they appear in the
+    /// original string.
+    words: Vec<Word>,
+
+    /// A scratch buffer for the alternate Double Metaphone codes.
+    alternate: String,
+}
+
+#[derive(Clone)]
+struct Word {
+    /// The range of the word in `Words::letters`.
+    letters: Range<usize>,
+
+    /// The range of the primary code of the word in `Words::codes`.
+    primary: Range<usize>,
+
+    /// The range of the alternate code of the word in `Words::codes`. This
+    /// is the same as `primary` for algorithms with a single code.
+    alternate: Range<usize>,
+
+    /// The byte range of the word in the original string.
+    original: Range<usize>,
+}
+
+impl Words {
+    /// Splits the string into words and encodes them, replacing the previous
+    /// ones.
+    #[inline]
+    fn fill(
+        &mut self,
+        s: &str,
+        algorithm: PhoneticAlgorithm,
+        normalization: bool,
+        scratch: &mut Vec<char>,
+    ) {
+        self.letters.clear();
+        self.codes.clear();
+        self.words.clear();
+
+        let mut word_start = None;
+
+        for (offset, ch) in s.char_indices() {
+            if !ch.is_alphabetic() {
+                if let Some(start) = word_start.take() {
+                    self.push(
+                        s,
+                        start..offset,
+                        algorithm,
+                        normalization,
+                        scratch,
+                    );
+                }
+            } else if word_start.is_none() {
+                word_start = Some(offset);
+            }
+        }
+
+        if let Some(start) = word_start {
+            self.push(s, start..s.len(), algorithm, normalization, scratch);
+        }
+    }
+
+    /// Encodes and pushes the word at `s[original]`, unless its code is empty.
+    #[inline]
+    fn push(
+        &mut self,
+        s: &str,
+        original: Range<usize>,
+        algorithm: PhoneticAlgorithm,
+        normalization: bool,
+        scratch: &mut Vec<char>,
+    ) {
+        let letters_start = self.letters.len();
+
+        self.letters.extend(s[original.clone()].chars().map(|ch| {
+            let ch = if normalization { normalize(ch) } else { ch };
+            double_metaphone::uppercase(ch)
+        }));
+
+        let letters = letters_start..self.letters.len();
+
+        let word = &self.letters[letters.clone()];
+
+        let primary_start = self.codes.len();
+
+        let (primary, alternate) = match algorithm {
+            PhoneticAlgorithm::Soundex => {
+                soundex::encode(word, &mut self.codes);
+                let primary = primary_start..self.codes.len();
+                (primary.clone(), primary)
+            },
+
+            PhoneticAlgorithm::Metaphone => {
+                metaphone::encode(
+                    word,
+                    MAX_CODE_LEN,
+                    scratch,
+                    &mut self.codes,
+                );
+                let primary = primary_start..self.codes.len();
+                (primary.clone(), primary)
+            },
+
+            PhoneticAlgorithm::DoubleMetaphone => {
+                self.alternate.clear();
+
+                double_metaphone::encode(
+                    word,
+                    MAX_CODE_LEN,
+                    scratch,
+                    &mut self.codes,
+                    &mut self.alternate,
+                );
+
+                let primary = primary_start..self.codes.len();
+
+                self.codes.push_str(&self.alternate);
+
+                let alternate = primary.end..self.codes.len();
+
+                (primary, alternate)
+            },
+        };
+
+        if primary.is_empty() {
+            self.letters.truncate(letters_start);
+            self.codes.truncate(primary_start);
+            return;
+        }
+
+        self.words.push(Word { letters, primary, alternate, original });
+    }
+
+    /// Returns whether the `word` of these words sounds like the `other_word`
+    /// of the `other` words.
+    #[inline(always)]
+    fn sounds_like(
+        &self,
+        word: &Word,
+        other: &Self,
+        other_word: &Word,
+    ) -> bool {
+        let codes = [
+            &self.codes[word.primary.clone()],
+            &self.codes[word.alternate.clone()],
+        ];
+
+        let other_codes = [
+            &other.codes[other_word.primary.clone()],
+            &other.codes[other_word.alternate.clone()],
+        ];
+
+        codes.iter().any(|code| other_codes.contains(code))
+    }
+
+    #[inline(always)]
+    fn letters(&self, word: &Word) -> &[char] {
+        &self.letters[word.letters.clone()]
+    }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.words.len()
+    }
+}
@@ -0,0 +1,84 @@
+//! An implementation of the American [Soundex][soundex] algorithm.
+//!
+//! [soundex]: https://en.wikipedia.org/wiki/Soundex
+
+/// The length of every Soundex code.
+const CODE_LEN: usize = 4;
+
+/// Encodes a word with the American Soundex algorithm, e.g. `"Robert"` and
+/// `"Rupert"` are both encoded as `"R163"`.
+///
+/// The code is made of the first letter of the word followed by three digits,
+/// and non-ASCII letters and any other characters are ignored. Words without
+/// any ASCII letter are encoded as an empty string.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::phonetic::soundex;
+/// assert_eq!(soundex("Robert"), "R163");
+/// assert_eq!(soundex("Rupert"), "R163");
+/// assert_eq!(soundex("Ashcraft"), "A261");
+/// assert_eq!(soundex("Lee"), "L000");
+/// ```
+#[inline]
+pub fn soundex(word: &str) -> String {
+    let word =
+        word.chars().map(|ch| ch.to_ascii_uppercase()).collect::<Vec<_>>();
+    let mut code = String::new();
+    encode(&word, &mut code);
+    code
+}
+
+/// Appends the Soundex code of the given uppercase word to `code`.
+#[inline]
+pub(super) fn encode(word: &[char], code: &mut String) {
+    let mut letters = word.iter().copied().filter(char::is_ascii_uppercase);
+
+    let Some(first) = letters.next() else { return };
+
+    code.push(first);
+
+    let mut len = 1;
+
+    let mut last_digit = digit(first);
+
+    for letter in letters {
+        if len == CODE_LEN {
+            break;
+        }
+
+        // H and W don't separate two letters with the same digit.
+        if matches!(letter, 'H' | 'W') {
+            continue;
+        }
+
+        let digit = digit(letter);
+
+        if digit != '0' && digit != last_digit {
+            code.push(digit);
+            len += 1;
+        }
+
+        last_digit = digit;
+    }
+
+    for _ in len..CODE_LEN {
+        code.push('0');
+    }
+}
+
+/// Returns the digit of the given uppercase ASCII letter, or `'0'` for the
+/// vowels, `H`, `W` and `Y`.
+#[inline(always)]
+fn digit(letter: char) -> char {
+    match letter {
+        'B' | 'F' | 'P' | 'V' => '1',
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => '2',
+        'D' | 'T' => '3',
+        'L' => '4',
+        'M' | 'N' => '5',
+        'R' => '6',
+        _ => '0',
+    }
+}
@@ -0,0 +1,61 @@
+use core::cmp::Ordering;
+
+/// The Ratcliff/Obershelp distance type.
+///
+/// This struct is returned by
+/// [`RatcliffObershelp`](super::RatcliffObershelp)'s [`Metric`](crate::Metric)
+/// implementation. It wraps a ratio between `0.0` and `1.0`, and it's ordered
+/// by descending ratio so that the most similar candidates come first when
+/// sorting.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RatcliffObershelpDistance(f64);
+
+impl PartialEq for RatcliffObershelpDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RatcliffObershelpDistance {}
+
+impl PartialOrd for RatcliffObershelpDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RatcliffObershelpDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // This makes the type act like a distance and not like a ratio.
+        other.0.total_cmp(&self.0)
+    }
+}
+
+impl RatcliffObershelpDistance {
+    /// Creates a new [`RatcliffObershelpDistance`] from a ratio.
+    #[inline(always)]
+    pub(super) fn from_ratio(ratio: f64) -> Self {
+        debug_assert!((0.0..=1.0).contains(&ratio));
+        Self(ratio)
+    }
+
+    /// Returns the similarity ratio between the query and the candidate, from
+    /// `0.0` (nothing in common) to `1.0` (identical).
+    ///
+    /// This is the same value returned by Python's
+    /// `difflib.SequenceMatcher.ratio()`.
+    #[inline(always)]
+    pub fn ratio(self) -> f64 {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for RatcliffObershelpDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        self.0
+    }
+}
@@ -0,0 +1,9 @@
+//! The Ratcliff/Obershelp similarity metric.
+//!
+//! See the documentation of [`RatcliffObershelp`] for more information.
+
+mod distance;
+mod ratcliff_obershelp;
+
+pub use distance::RatcliffObershelpDistance;
+pub use ratcliff_obershelp::RatcliffObershelp;
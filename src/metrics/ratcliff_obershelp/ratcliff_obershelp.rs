@@ -0,0 +1,631 @@
+use core::ops::Range;
+
+use super::*;
+use crate::normalize::normalize;
+use crate::utils::{self, CharEq};
+use crate::{CaseSensitivity, MatchedRanges, Metric};
+
+/// The minimum number of characters the query must have for the "popular"
+/// characters to be ignored when the junk heuristic is enabled.
+const AUTOJUNK_MIN_LEN: usize = 200;
+
+/// A metric that implements the [Ratcliff/Obershelp][ro] similarity, as
+/// computed by Python's [`difflib.SequenceMatcher`][sm].
+///
+/// The algorithm finds the longest contiguous block of characters shared by
+/// the query and the candidate, and then recursively does the same on the
+/// characters to the left and to the right of that block. The similarity
+/// ratio is then `2 * M / T`, where `M` is the total number of characters in
+/// the matching blocks and `T` is the total number of characters in the query
+/// and in the candidate.
+///
+/// The [`Metric`] implementation of this struct reproduces the results of
+/// `SequenceMatcher(None, candidate, query).ratio()` exactly, which is how
+/// Python's `difflib.get_close_matches` scores its possibilities, including
+/// the way ties between blocks of the same length are broken. The ranges
+/// returned by [`distance_and_ranges`](Metric::distance_and_ranges) are the
+/// ones of the matching blocks in the candidate.
+///
+/// A candidate doesn't match the query if they have no characters in common,
+/// or if their ratio is below the [cutoff](RatcliffObershelp::set_cutoff).
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::ratcliff_obershelp::RatcliffObershelp;
+/// # use norm::Metric;
+/// let mut ro = RatcliffObershelp::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance =
+///     ro.distance_and_ranges("appel", "apple", &mut ranges).unwrap();
+///
+/// // Same as `SequenceMatcher(None, "apple", "appel").ratio()`.
+/// assert_eq!(distance.ratio(), 0.8);
+///
+/// assert_eq!(ranges, [0..4]);
+/// ```
+///
+/// [ro]: https://en.wikipedia.org/wiki/Gestalt_pattern_matching
+/// [sm]: https://docs.python.org/3/library/difflib.html#difflib.SequenceMatcher
+#[cfg_attr(docsrs, doc(cfg(feature = "ratcliff-obershelp")))]
+#[derive(Clone)]
+pub struct RatcliffObershelp {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the characters of the query and
+    /// the candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// Whether characters that are too common in the candidate are ignored
+    /// when looking for matching blocks, like difflib's `autojunk`.
+    autojunk: bool,
+
+    /// The minimum ratio a candidate needs to match the query.
+    cutoff: f64,
+
+    /// The port of difflib's `SequenceMatcher`, whose buffers are reused
+    /// between calls.
+    matcher: SequenceMatcher,
+}
+
+impl core::fmt::Debug for RatcliffObershelp {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RatcliffObershelp")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .field("autojunk", &self.autojunk)
+            .field("cutoff", &self.cutoff)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for RatcliffObershelp {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            candidate_normalization: false,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            autojunk: true,
+            cutoff: 0.0,
+            matcher: SequenceMatcher::default(),
+        }
+    }
+}
+
+impl RatcliffObershelp {
+    /// Creates a new `RatcliffObershelp`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ratcliff_obershelp::RatcliffObershelp;
+    /// # use norm::Metric;
+    /// let mut ro = RatcliffObershelp::new();
+    ///
+    /// assert!(ro.distance("foo", "ƒöö").is_none());
+    ///
+    /// ro.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(ro.distance("foo", "ƒöö").unwrap().ratio(), 1.0);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the characters of the
+    /// query and the candidate. The default is
+    /// [`CaseSensitivity::Sensitive`], which is what Python uses.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ratcliff_obershelp::RatcliffObershelp;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut ro = RatcliffObershelp::new();
+    ///
+    /// assert!(ro.distance("abc", "ABC").is_none());
+    ///
+    /// ro.set_case_sensitivity(CaseSensitivity::Insensitive);
+    ///
+    /// assert_eq!(ro.distance("abc", "ABC").unwrap().ratio(), 1.0);
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets whether to use `SequenceMatcher`'s junk heuristic, which is the
+    /// `autojunk` argument of its constructor. The default is `true`, just
+    /// like in Python.
+    ///
+    /// When enabled and the query has at least 200 characters, the characters
+    /// that make up more than 1% of the query are considered "popular", and
+    /// blocks can't start with a popular character. Note that blocks can
+    /// still be extended to include popular characters on both ends.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ratcliff_obershelp::RatcliffObershelp;
+    /// # use norm::Metric;
+    /// let mut ro = RatcliffObershelp::new();
+    ///
+    /// let query = "a".repeat(200);
+    ///
+    /// // Every character of the query is popular.
+    /// assert!(ro.distance(&query, "bab").is_none());
+    ///
+    /// ro.set_autojunk(false);
+    ///
+    /// assert!(ro.distance(&query, "bab").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_autojunk(&mut self, autojunk: bool) -> &mut Self {
+        self.autojunk = autojunk;
+        self
+    }
+
+    /// Sets the minimum ratio a candidate needs to have to match the query,
+    /// just like the `cutoff` argument of `difflib.get_close_matches`. The
+    /// default is `0.0`, while `get_close_matches` uses `0.6`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cutoff is not between `0.0` and `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::ratcliff_obershelp::RatcliffObershelp;
+    /// # use norm::Metric;
+    /// let mut ro = RatcliffObershelp::new();
+    ///
+    /// let candidates = ["ape", "apple", "peach", "puppy"];
+    ///
+    /// ro.set_cutoff(0.6);
+    ///
+    /// // Same as `get_close_matches("appel", candidates, n = 3)`.
+    /// let mut close_matches = candidates
+    ///     .iter()
+    ///     .filter_map(|&c| ro.distance("appel", c).map(|dist| (dist, c)))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// // Ties are broken by putting the greatest candidates first.
+    /// close_matches
+    ///     .sort_by(|(d1, c1), (d2, c2)| d1.cmp(d2).then_with(|| c2.cmp(c1)));
+    ///
+    /// close_matches.truncate(3);
+    ///
+    /// let close_matches =
+    ///     close_matches.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(close_matches, ["apple", "ape"]);
+    /// ```
+    #[inline(always)]
+    pub fn set_cutoff(&mut self, cutoff: f64) -> &mut Self {
+        assert!(
+            (0.0..=1.0).contains(&cutoff),
+            "the cutoff must be between 0.0 and 1.0, got {cutoff}"
+        );
+        self.cutoff = cutoff;
+        self
+    }
+
+    /// Computes the ratio between the query and the candidate, leaving the
+    /// matching blocks in `self.matcher`.
+    #[inline]
+    fn ratio(&mut self, query: &str, candidate: &str) -> Option<f64> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let char_eq =
+            utils::char_eq(is_case_sensitive, self.candidate_normalization);
+
+        let matcher = &mut self.matcher;
+
+        matcher.set_seqs(candidate, query);
+
+        let total_len = matcher.a.len() + matcher.b.len();
+
+        if total_len == 0 {
+            matcher.matching_blocks.clear();
+            return Some(1.0);
+        }
+
+        matcher.chain_b(is_case_sensitive, self.autojunk);
+
+        let matches = matcher.matching_blocks(
+            is_case_sensitive,
+            self.candidate_normalization,
+            char_eq,
+        );
+
+        if matches == 0 {
+            return None;
+        }
+
+        let ratio = 2.0 * matches as f64 / total_len as f64;
+
+        (ratio >= self.cutoff).then_some(ratio)
+    }
+}
+
+impl Metric for RatcliffObershelp {
+    type Query<'a> = &'a str;
+
+    type Distance = RatcliffObershelpDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.ratio(query, candidate).map(RatcliffObershelpDistance::from_ratio)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let ratio = self.ratio(query, candidate)?;
+
+        self.block_ranges(candidate, |block| block.a, ranges);
+
+        Some(RatcliffObershelpDistance::from_ratio(ratio))
+    }
+}
+
+impl RatcliffObershelp {
+    /// Pushes the byte ranges of the given query covered by the matching
+    /// blocks of the last call to [`distance`](Metric::distance) to `ranges`.
+    ///
+    /// This is the counterpart of the ranges returned by
+    /// [`distance_and_ranges`](Metric::distance_and_ranges), for callers that
+    /// need to swap the query and the candidate.
+    #[cfg(feature = "token-ratio")]
+    #[inline]
+    pub(crate) fn query_ranges(
+        &self,
+        query: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) {
+        self.block_ranges(query, |block| block.b, ranges);
+    }
+
+    /// Pushes the byte ranges of `text` covered by the last matching blocks
+    /// to `ranges`, where `block_start` returns the start of a block in the
+    /// sequence `text` was passed as.
+    #[inline]
+    fn block_ranges(
+        &self,
+        text: &str,
+        block_start: fn(&Block) -> usize,
+        ranges: &mut Vec<Range<usize>>,
+    ) {
+        let mut ranges = MatchedRanges::from(ranges);
+
+        let mut blocks = self.matcher.matching_blocks.iter().peekable();
+
+        for (char_idx, (offset, ch)) in text.char_indices().enumerate() {
+            while blocks
+                .next_if(|block| block_start(block) + block.len <= char_idx)
+                .is_some()
+            {}
+
+            let Some(block) = blocks.peek() else { break };
+
+            if block_start(block) <= char_idx {
+                ranges.insert(offset..offset + ch.len_utf8());
+            }
+        }
+    }
+}
+
+/// A block of characters shared by the two sequences, i.e. `a[a..a + len]`
+/// equals `b[b..b + len]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Block {
+    a: usize,
+    b: usize,
+    len: usize,
+}
+
+impl Block {
+    #[inline(always)]
+    fn a_end(&self) -> usize {
+        self.a + self.len
+    }
+}
+
+/// A port of the parts of Python's `difflib.SequenceMatcher` needed to
+/// compute its matching blocks, using the same names for its fields and
+/// methods.
+#[derive(Clone, Default)]
+struct SequenceMatcher {
+    /// The characters of the first sequence, i.e. the candidate.
+    a: Vec<char>,
+
+    /// The characters of the second sequence, i.e. the query.
+    b: Vec<char>,
+
+    /// The (key, index) pairs of the non-popular characters of `b`, sorted
+    /// by key and then by index. This replaces Python's `b2j` dictionary.
+    b2j: Vec<(char, usize)>,
+
+    /// The indices of `b` that are equal to a given character of `a`.
+    indices: Vec<usize>,
+
+    /// Maps `j + 1` to the length of the match ending at `b[j]` for the
+    /// previous character of `a`, replacing Python's `j2len` dictionary.
+    j2len: Vec<usize>,
+
+    /// Same as `j2len`, but for the current character of `a`.
+    new_j2len: Vec<usize>,
+
+    /// The non-zero entries of `j2len`.
+    j2len_set: Vec<usize>,
+
+    /// The non-zero entries of `new_j2len`.
+    new_j2len_set: Vec<usize>,
+
+    /// The ranges of `a` and `b` that are yet to be searched for blocks.
+    queue: Vec<(usize, usize, usize, usize)>,
+
+    /// The matching blocks, sorted by their position in `a`.
+    matching_blocks: Vec<Block>,
+}
+
+impl SequenceMatcher {
+    /// Sets the two sequences to compare.
+    #[inline]
+    fn set_seqs(&mut self, a: &str, b: &str) {
+        self.a.clear();
+        self.a.extend(a.chars());
+        self.b.clear();
+        self.b.extend(b.chars());
+    }
+
+    /// Fills `self.b2j`, removing the popular characters if `autojunk` is
+    /// set.
+    #[inline]
+    fn chain_b(&mut self, is_case_sensitive: bool, autojunk: bool) {
+        self.b2j.clear();
+
+        self.b2j.extend(
+            self.b
+                .iter()
+                .enumerate()
+                .map(|(j, &ch)| (key(ch, is_case_sensitive), j)),
+        );
+
+        self.b2j.sort_unstable();
+
+        if !autojunk || self.b.len() < AUTOJUNK_MIN_LEN {
+            return;
+        }
+
+        let ntest = self.b.len() / 100 + 1;
+
+        let mut kept = 0;
+
+        let mut start = 0;
+
+        while start < self.b2j.len() {
+            let key = self.b2j[start].0;
+
+            let len = self.b2j[start..].partition_point(|&(k, _)| k == key);
+
+            if len <= ntest {
+                self.b2j.copy_within(start..start + len, kept);
+                kept += len;
+            }
+
+            start += len;
+        }
+
+        self.b2j.truncate(kept);
+    }
+
+    /// Fills `self.indices` with the indices of the characters of `b` that
+    /// are equal to `ch`, in ascending order.
+    #[inline]
+    fn fill_indices(
+        &mut self,
+        ch: char,
+        is_case_sensitive: bool,
+        normalize_candidate: bool,
+    ) {
+        self.indices.clear();
+
+        let key = key(ch, is_case_sensitive);
+
+        self.indices.extend(lookup(&self.b2j, key));
+
+        if normalize_candidate {
+            let normalized_key = self::key(normalize(ch), is_case_sensitive);
+
+            if normalized_key != key {
+                self.indices.extend(lookup(&self.b2j, normalized_key));
+                self.indices.sort_unstable();
+            }
+        }
+    }
+
+    /// Finds the longest block in `a[alo..ahi]` and `b[blo..bhi]`.
+    ///
+    /// If there are multiple blocks with the same maximum length, this
+    /// returns the one that starts earliest in `a`, and of all of those the
+    /// one that starts earliest in `b`.
+    #[inline]
+    fn find_longest_match(
+        &mut self,
+        alo: usize,
+        ahi: usize,
+        blo: usize,
+        bhi: usize,
+        is_case_sensitive: bool,
+        normalize_candidate: bool,
+        char_eq: CharEq,
+    ) -> Block {
+        let (mut besti, mut bestj, mut bestsize) = (alo, blo, 0);
+
+        self.j2len.resize(self.b.len() + 1, 0);
+        self.new_j2len.resize(self.b.len() + 1, 0);
+
+        for i in alo..ahi {
+            self.fill_indices(
+                self.a[i],
+                is_case_sensitive,
+                normalize_candidate,
+            );
+
+            for &j in &self.indices {
+                if j < blo {
+                    continue;
+                }
+
+                if j >= bhi {
+                    break;
+                }
+
+                let k = self.j2len[j] + 1;
+
+                self.new_j2len[j + 1] = k;
+                self.new_j2len_set.push(j + 1);
+
+                if k > bestsize {
+                    (besti, bestj, bestsize) = (i + 1 - k, j + 1 - k, k);
+                }
+            }
+
+            for j in self.j2len_set.drain(..) {
+                self.j2len[j] = 0;
+            }
+
+            core::mem::swap(&mut self.j2len, &mut self.new_j2len);
+            core::mem::swap(&mut self.j2len_set, &mut self.new_j2len_set);
+        }
+
+        for j in self.j2len_set.drain(..) {
+            self.j2len[j] = 0;
+        }
+
+        // Extend the best block with the popular characters on both ends.
+        while besti > alo
+            && bestj > blo
+            && char_eq(self.b[bestj - 1], self.a[besti - 1])
+        {
+            besti -= 1;
+            bestj -= 1;
+            bestsize += 1;
+        }
+
+        while besti + bestsize < ahi
+            && bestj + bestsize < bhi
+            && char_eq(self.b[bestj + bestsize], self.a[besti + bestsize])
+        {
+            bestsize += 1;
+        }
+
+        Block { a: besti, b: bestj, len: bestsize }
+    }
+
+    /// Fills `self.matching_blocks`, returning the total number of matched
+    /// characters.
+    #[inline]
+    fn matching_blocks(
+        &mut self,
+        is_case_sensitive: bool,
+        normalize_candidate: bool,
+        char_eq: CharEq,
+    ) -> usize {
+        self.matching_blocks.clear();
+
+        self.queue.clear();
+        self.queue.push((0, self.a.len(), 0, self.b.len()));
+
+        while let Some((alo, ahi, blo, bhi)) = self.queue.pop() {
+            let block = self.find_longest_match(
+                alo,
+                ahi,
+                blo,
+                bhi,
+                is_case_sensitive,
+                normalize_candidate,
+                char_eq,
+            );
+
+            if block.len == 0 {
+                continue;
+            }
+
+            self.matching_blocks.push(block);
+
+            if alo < block.a && blo < block.b {
+                self.queue.push((alo, block.a, blo, block.b));
+            }
+
+            if block.a_end() < ahi && block.b + block.len < bhi {
+                self.queue.push((
+                    block.a_end(),
+                    ahi,
+                    block.b + block.len,
+                    bhi,
+                ));
+            }
+        }
+
+        self.matching_blocks.sort_unstable();
+
+        self.matching_blocks.iter().map(|block| block.len).sum()
+    }
+}
+
+/// Returns the key used to group equal characters in `b2j`.
+#[inline(always)]
+fn key(ch: char, is_case_sensitive: bool) -> char {
+    if is_case_sensitive {
+        ch
+    } else {
+        ch.to_ascii_lowercase()
+    }
+}
+
+/// Returns the indices of `b` whose key is `key`, in ascending order.
+#[inline(always)]
+fn lookup(
+    b2j: &[(char, usize)],
+    key: char,
+) -> impl Iterator<Item = usize> + '_ {
+    let start = b2j.partition_point(|&(k, _)| k < key);
+
+    b2j[start..].iter().take_while(move |&&(k, _)| k == key).map(|&(_, j)| j)
+}
@@ -0,0 +1,60 @@
+use core::cmp::Ordering;
+
+/// The token ratio distance type.
+///
+/// This struct is returned by [`TokenRatio`](super::TokenRatio)'s
+/// [`Metric`](crate::Metric) implementation. It wraps a ratio between `0.0`
+/// and `1.0`, and it's ordered by descending ratio so that the most similar
+/// candidates come first when sorting.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokenRatioDistance(f64);
+
+impl PartialEq for TokenRatioDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TokenRatioDistance {}
+
+impl PartialOrd for TokenRatioDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TokenRatioDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // This makes the type act like a distance and not like a ratio.
+        other.0.total_cmp(&self.0)
+    }
+}
+
+impl TokenRatioDistance {
+    /// Creates a new [`TokenRatioDistance`] from a ratio.
+    #[inline(always)]
+    pub(super) fn from_ratio(ratio: f64) -> Self {
+        debug_assert!((0.0..=1.0).contains(&ratio));
+        Self(ratio)
+    }
+
+    /// Returns the similarity ratio between the query and the candidate, from
+    /// `0.0` (nothing in common) to `1.0` (same tokens).
+    ///
+    /// Unlike fuzzywuzzy's `token_sort_ratio` and `token_set_ratio`, the ratio
+    /// is neither scaled to `0..=100` nor rounded to an integer.
+    #[inline(always)]
+    pub fn ratio(self) -> f64 {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for TokenRatioDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        self.0
+    }
+}
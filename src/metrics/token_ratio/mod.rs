@@ -0,0 +1,9 @@
+//! The token sort and token set ratio metric.
+//!
+//! See the documentation of [`TokenRatio`] for more information.
+
+mod distance;
+mod token_ratio;
+
+pub use distance::TokenRatioDistance;
+pub use token_ratio::{TokenRatio, TokenRatioMode};
@@ -0,0 +1,629 @@
+use core::cmp::Ordering;
+use core::ops::Range;
+
+use super::*;
+use crate::normalize::normalize;
+use crate::ratcliff_obershelp::RatcliffObershelp;
+use crate::{CaseSensitivity, MatchedRanges, Metric};
+
+/// The ways a [`TokenRatio`] can compare the tokens of the query and the
+/// ones of the candidate.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TokenRatioMode {
+    /// Like fuzzywuzzy's `token_sort_ratio`, the tokens of both strings are
+    /// sorted alphabetically and joined by a single space, and the ratio is
+    /// the one between the two resulting strings.
+    ///
+    /// This makes the metric insensitive to the order of the tokens, but
+    /// every token still counts.
+    Sort,
+
+    /// Like fuzzywuzzy's `token_set_ratio`, both strings are turned into
+    /// sets of tokens, and the ratio is the best one between the sorted
+    /// intersection of the two sets and each set, and between the two sets.
+    ///
+    /// This makes a candidate whose tokens are a superset of the query's a
+    /// perfect match.
+    #[default]
+    Set,
+}
+
+/// A metric that compares the query and the candidate token by token, in the
+/// style of fuzzywuzzy's [`token_sort_ratio` and `token_set_ratio`][fw].
+///
+/// Both strings are split into tokens on every character that's not
+/// alphanumeric, e.g. whitespace and punctuation, and the tokens are then
+/// rearranged as described in [`TokenRatioMode`]. The rearranged strings are
+/// compared with the [`RatcliffObershelp`] metric, which is what fuzzywuzzy
+/// uses when `python-Levenshtein` is not installed.
+///
+/// This makes the metric well suited for queries whose words are not in the
+/// same order as the ones of the candidates, e.g. a query like `"york new"`
+/// for a candidate like `"New York City"`.
+///
+/// A candidate doesn't match the query if they have no characters in common,
+/// or if one of them doesn't have any token. The ranges returned by
+/// [`distance_and_ranges`](Metric::distance_and_ranges) are the ones of the
+/// whole tokens of the candidate that share at least one character with the
+/// query.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::token_ratio::{TokenRatio, TokenRatioMode};
+/// # use norm::Metric;
+/// let mut token_ratio = TokenRatio::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance = token_ratio
+///     .distance_and_ranges("york new", "New York City", &mut ranges)
+///     .unwrap();
+///
+/// assert_eq!(distance.ratio(), 1.0);
+///
+/// assert_eq!(ranges, [0..3, 4..8]);
+///
+/// token_ratio.set_mode(TokenRatioMode::Sort);
+///
+/// // Same as comparing "new york" with "city new york".
+/// let distance = token_ratio.distance("york new", "New York City").unwrap();
+///
+/// assert_eq!(distance.ratio(), 16.0 / 21.0);
+/// ```
+///
+/// [fw]: https://github.com/seatgeek/fuzzywuzzy
+#[cfg_attr(docsrs, doc(cfg(feature = "token-ratio")))]
+#[derive(Clone)]
+pub struct TokenRatio {
+    /// Whether multi-byte latin characters are normalized to ASCII before the
+    /// tokens of both strings are compared.
+    candidate_normalization: bool,
+
+    /// The case sensitivity used to compare the tokens of the query and the
+    /// candidate.
+    case_sensitivity: CaseSensitivity,
+
+    /// How the tokens are rearranged before the strings are compared.
+    mode: TokenRatioMode,
+
+    /// The metric used to compare the rearranged strings.
+    inner: RatcliffObershelp,
+
+    /// The tokens of the last query.
+    query_tokens: Tokens,
+
+    /// The tokens of the last candidate.
+    candidate_tokens: Tokens,
+
+    /// The rearranged tokens of the last query.
+    query_joined: String,
+
+    /// The rearranged tokens of the last candidate.
+    candidate_joined: String,
+
+    /// The byte range in `candidate_joined` of each token of the candidate
+    /// that was pushed to it, together with its index in `candidate_tokens`.
+    segments: Vec<(Range<usize>, usize)>,
+
+    /// The ranges matched by the inner metric in `candidate_joined`.
+    inner_ranges: Vec<Range<usize>>,
+
+    /// Whether each token in `candidate_tokens` was matched.
+    matched: Vec<bool>,
+}
+
+impl core::fmt::Debug for TokenRatio {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TokenRatio")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("case_sensitivity", &self.case_sensitivity)
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TokenRatio {
+    #[inline]
+    fn default() -> Self {
+        let mut inner = RatcliffObershelp::new();
+
+        // The tokens are already case-folded and normalized.
+        inner
+            .set_case_sensitivity(CaseSensitivity::Sensitive)
+            .set_candidate_normalization(false);
+
+        Self {
+            candidate_normalization: false,
+            case_sensitivity: CaseSensitivity::default(),
+            mode: TokenRatioMode::default(),
+            inner,
+            query_tokens: Tokens::default(),
+            candidate_tokens: Tokens::default(),
+            query_joined: String::new(),
+            candidate_joined: String::new(),
+            segments: Vec::new(),
+            inner_ranges: Vec::new(),
+            matched: Vec::new(),
+        }
+    }
+}
+
+impl TokenRatio {
+    /// Creates a new `TokenRatio`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters should be normalized to ASCII
+    /// before comparing the tokens. The default is `false`.
+    ///
+    /// The tokens of the query are normalized too, and in both strings this
+    /// happens before the tokens are sorted and deduplicated. Otherwise a
+    /// `"café"` in the query and a normalized `"cafe"` in the candidate could
+    /// sort differently and end up in different positions of the joined
+    /// strings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::token_ratio::TokenRatio;
+    /// # use norm::Metric;
+    /// let mut token_ratio = TokenRatio::new();
+    ///
+    /// assert!(token_ratio.distance("foo", "ƒöö").is_none());
+    ///
+    /// token_ratio.set_candidate_normalization(true);
+    ///
+    /// assert_eq!(token_ratio.distance("foo", "ƒöö").unwrap().ratio(), 1.0);
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets the case sensitivity to use when comparing the tokens of the
+    /// query and the candidate. The default is [`CaseSensitivity::Smart`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::token_ratio::TokenRatio;
+    /// # use norm::{CaseSensitivity, Metric};
+    /// let mut token_ratio = TokenRatio::new();
+    ///
+    /// assert_eq!(token_ratio.distance("abc", "ABC").unwrap().ratio(), 1.0);
+    ///
+    /// token_ratio.set_case_sensitivity(CaseSensitivity::Sensitive);
+    ///
+    /// assert!(token_ratio.distance("abc", "ABC").is_none());
+    /// ```
+    #[inline(always)]
+    pub fn set_case_sensitivity(
+        &mut self,
+        case_sensitivity: CaseSensitivity,
+    ) -> &mut Self {
+        self.case_sensitivity = case_sensitivity;
+        self
+    }
+
+    /// Sets how the tokens of the query and the candidate are compared. The
+    /// default is [`TokenRatioMode::Set`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::token_ratio::{TokenRatio, TokenRatioMode};
+    /// # use norm::Metric;
+    /// let mut token_ratio = TokenRatio::new();
+    ///
+    /// // Repeated tokens are ignored in set mode..
+    /// assert_eq!(token_ratio.distance("a b", "b a a").unwrap().ratio(), 1.0);
+    ///
+    /// token_ratio.set_mode(TokenRatioMode::Sort);
+    ///
+    /// // ..but not in sort mode.
+    /// assert_eq!(token_ratio.distance("a b", "b a a").unwrap().ratio(), 0.75);
+    /// ```
+    #[inline(always)]
+    pub fn set_mode(&mut self, mode: TokenRatioMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Computes the ratio between the query and the candidate, also filling
+    /// `self.matched` if `RANGES` is true.
+    #[inline]
+    fn ratio<const RANGES: bool>(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<f64> {
+        let is_case_sensitive = match self.case_sensitivity {
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+        };
+
+        let normalization = self.candidate_normalization;
+
+        self.query_tokens.fill(query, is_case_sensitive, normalization);
+
+        self.candidate_tokens.fill(
+            candidate,
+            is_case_sensitive,
+            normalization,
+        );
+
+        if RANGES {
+            self.matched.clear();
+            self.matched.resize(self.candidate_tokens.len(), false);
+        }
+
+        if self.query_tokens.is_empty() || self.candidate_tokens.is_empty() {
+            return (self.query_tokens.is_empty()
+                && self.candidate_tokens.is_empty())
+            .then_some(1.0);
+        }
+
+        let query_tokens = &self.query_tokens;
+        let candidate_tokens = &self.candidate_tokens;
+        let query_joined = &mut self.query_joined;
+        let candidate_joined = &mut self.candidate_joined;
+        let segments = &mut self.segments;
+
+        query_joined.clear();
+        candidate_joined.clear();
+        segments.clear();
+
+        // The (query, candidate) pairs to compare, expressed as the lengths
+        // of the prefixes of the joined strings.
+        let pairs = match self.mode {
+            TokenRatioMode::Sort => {
+                for idx in 0..query_tokens.len() {
+                    query_tokens.push_to(idx, query_joined, None);
+                }
+
+                for idx in 0..candidate_tokens.len() {
+                    candidate_tokens.push_to(
+                        idx,
+                        candidate_joined,
+                        Some(&mut *segments),
+                    );
+                }
+
+                [
+                    Some((query_joined.len(), candidate_joined.len())),
+                    None,
+                    None,
+                ]
+            },
+
+            TokenRatioMode::Set => {
+                // First the intersection of the two sets of tokens..
+                merge(
+                    query_tokens,
+                    candidate_tokens,
+                    |query_idx, candidate_idx| {
+                        if let (Some(query_idx), Some(candidate_idx)) =
+                            (query_idx, candidate_idx)
+                        {
+                            query_tokens.push_to(
+                                query_idx,
+                                query_joined,
+                                None,
+                            );
+                            candidate_tokens.push_to(
+                                candidate_idx,
+                                candidate_joined,
+                                Some(&mut *segments),
+                            );
+                        }
+                    },
+                );
+
+                let query_intersection_len = query_joined.len();
+
+                let candidate_intersection_len = candidate_joined.len();
+
+                // ..and then the tokens that are only in one of them.
+                merge(
+                    query_tokens,
+                    candidate_tokens,
+                    |query_idx, candidate_idx| match (query_idx, candidate_idx)
+                    {
+                        (Some(idx), None) => {
+                            query_tokens.push_to(idx, query_joined, None)
+                        },
+                        (None, Some(idx)) => candidate_tokens.push_to(
+                            idx,
+                            candidate_joined,
+                            Some(&mut *segments),
+                        ),
+                        _ => {},
+                    },
+                );
+
+                [
+                    Some((query_joined.len(), candidate_intersection_len)),
+                    Some((query_intersection_len, candidate_joined.len())),
+                    Some((query_joined.len(), candidate_joined.len())),
+                ]
+            },
+        };
+
+        let mut best: Option<(f64, (usize, usize))> = None;
+
+        // `RatcliffObershelp` computes `SequenceMatcher(None, candidate,
+        // query)`, but fuzzywuzzy passes the query first, and the ratio isn't
+        // symmetric, so we swap the two strings.
+        for (query_len, candidate_len) in pairs.into_iter().flatten() {
+            let Some(distance) = self.inner.distance(
+                &candidate_joined[..candidate_len],
+                &query_joined[..query_len],
+            ) else {
+                continue;
+            };
+
+            let ratio = distance.ratio();
+
+            if best.is_none_or(|(best_ratio, _)| ratio > best_ratio) {
+                best = Some((ratio, (query_len, candidate_len)));
+            }
+        }
+
+        let (ratio, (query_len, candidate_len)) = best?;
+
+        if RANGES {
+            self.inner_ranges.clear();
+
+            let candidate_joined = &candidate_joined[..candidate_len];
+
+            self.inner.distance(candidate_joined, &query_joined[..query_len]);
+
+            self.inner.query_ranges(candidate_joined, &mut self.inner_ranges);
+
+            for (segment, idx) in segments.iter() {
+                let is_matched = segment.end <= candidate_len
+                    && self.inner_ranges.iter().any(|range| {
+                        range.start < segment.end && segment.start < range.end
+                    });
+
+                if is_matched {
+                    self.matched[*idx] = true;
+                }
+            }
+
+            // In set mode only the first of several equal tokens is pushed to
+            // the joined string, so we propagate the match to the others.
+            let mut start = 0;
+
+            while start < candidate_tokens.len() {
+                let mut end = start + 1;
+
+                while end < candidate_tokens.len()
+                    && candidate_tokens.text(end)
+                        == candidate_tokens.text(start)
+                {
+                    end += 1;
+                }
+
+                if self.matched[start..end].contains(&true) {
+                    self.matched[start..end].fill(true);
+                }
+
+                start = end;
+            }
+        }
+
+        Some(ratio)
+    }
+}
+
+impl Metric for TokenRatio {
+    type Query<'a> = &'a str;
+
+    type Distance = TokenRatioDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        self.ratio::<false>(query, candidate)
+            .map(TokenRatioDistance::from_ratio)
+    }
+
+    #[inline]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        let ratio = self.ratio::<true>(query, candidate)?;
+
+        let mut ranges = MatchedRanges::from(ranges);
+
+        for (token, &is_matched) in
+            self.candidate_tokens.tokens.iter().zip(&self.matched)
+        {
+            if is_matched {
+                ranges.insert(token.original.clone());
+            }
+        }
+
+        Some(TokenRatioDistance::from_ratio(ratio))
+    }
+}
+
+/// Calls `fun` once for every distinct token of the query and the candidate,
+/// in alphabetical order, with the index of the first token with that text
+/// in the query and in the candidate, if any.
+#[inline]
+fn merge(
+    query_tokens: &Tokens,
+    candidate_tokens: &Tokens,
+    mut fun: impl FnMut(Option<usize>, Option<usize>),
+) {
+    let mut query_iter = query_tokens.unique().peekable();
+
+    let mut candidate_iter = candidate_tokens.unique().peekable();
+
+    loop {
+        let (query_idx, candidate_idx) =
+            match (query_iter.peek(), candidate_iter.peek()) {
+                (Some(&query_idx), Some(&candidate_idx)) => match query_tokens
+                    .text(query_idx)
+                    .cmp(candidate_tokens.text(candidate_idx))
+                {
+                    Ordering::Less => (query_iter.next(), None),
+                    Ordering::Greater => (None, candidate_iter.next()),
+                    Ordering::Equal => {
+                        (query_iter.next(), candidate_iter.next())
+                    },
+                },
+                (Some(_), None) => (query_iter.next(), None),
+                (None, Some(_)) => (None, candidate_iter.next()),
+                (None, None) => break,
+            };
+
+        fun(query_idx, candidate_idx);
+    }
+}
+
+/// The tokens of a string, sorted alphabetically.
+#[derive(Clone, Default)]
+struct Tokens {
+    /// The characters of the tokens after they've been normalized and
+    /// case-folded, one token after the other.
+    folded: String,
+
+    /// The tokens, in the order they appear in the original string.
+    tokens: Vec<Token>,
+}
+
+#[derive(Clone)]
+struct Token {
+    /// The byte range of the token in `Tokens::folded`.
+    folded: Range<usize>,
+
+    /// The byte range of the token in the original string.
+    original: Range<usize>,
+}
+
+impl Tokens {
+    /// Splits the string into tokens, replacing the previous ones.
+    #[inline]
+    fn fill(&mut self, s: &str, is_case_sensitive: bool, normalization: bool) {
+        self.folded.clear();
+        self.tokens.clear();
+
+        let mut token_start = None;
+
+        for (offset, ch) in s.char_indices() {
+            if !ch.is_alphanumeric() {
+                if let Some(start) = token_start.take() {
+                    self.push(
+                        s,
+                        start..offset,
+                        is_case_sensitive,
+                        normalization,
+                    );
+                }
+            } else if token_start.is_none() {
+                token_start = Some(offset);
+            }
+        }
+
+        if let Some(start) = token_start {
+            self.push(s, start..s.len(), is_case_sensitive, normalization);
+        }
+
+        let folded = &self.folded;
+
+        self.tokens.sort_by(|lhs, rhs| {
+            folded[lhs.folded.clone()].cmp(&folded[rhs.folded.clone()])
+        });
+    }
+
+    /// Pushes the token at `s[original]`.
+    #[inline]
+    fn push(
+        &mut self,
+        s: &str,
+        original: Range<usize>,
+        is_case_sensitive: bool,
+        normalization: bool,
+    ) {
+        let start = self.folded.len();
+
+        for ch in s[original.clone()].chars() {
+            let ch = if normalization { normalize(ch) } else { ch };
+
+            self.folded.push(if is_case_sensitive {
+                ch
+            } else {
+                ch.to_ascii_lowercase()
+            });
+        }
+
+        let folded = start..self.folded.len();
+
+        self.tokens.push(Token { folded, original });
+    }
+
+    /// Pushes the text of the token with the given index to `joined`,
+    /// separating it from the previous token with a space, and records its
+    /// range in `segments` if given.
+    #[inline]
+    fn push_to(
+        &self,
+        idx: usize,
+        joined: &mut String,
+        segments: Option<&mut Vec<(Range<usize>, usize)>>,
+    ) {
+        if !joined.is_empty() {
+            joined.push(' ');
+        }
+
+        let start = joined.len();
+
+        joined.push_str(self.text(idx));
+
+        if let Some(segments) = segments {
+            segments.push((start..joined.len(), idx));
+        }
+    }
+
+    /// Returns the folded text of the token with the given index.
+    #[inline(always)]
+    fn text(&self, idx: usize) -> &str {
+        &self.folded[self.tokens[idx].folded.clone()]
+    }
+
+    /// Returns an iterator over the indices of the first token of every group
+    /// of equal tokens.
+    #[inline]
+    fn unique(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(move |&idx| {
+            idx == 0 || self.text(idx - 1) != self.text(idx)
+        })
+    }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.tokens.len()
+    }
+}
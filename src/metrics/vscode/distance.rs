@@ -0,0 +1,67 @@
+use core::cmp::Ordering;
+
+pub(super) type Score = i64;
+
+/// The VS Code distance type.
+///
+/// This struct is returned by [`VsCode`](super::VsCode)'s
+/// [`Metric`](crate::Metric) implementation.
+#[derive(Debug, Clone, Copy)]
+pub struct VsCodeDistance(Score);
+
+impl PartialEq for VsCodeDistance {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for VsCodeDistance {}
+
+impl PartialOrd for VsCodeDistance {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VsCodeDistance {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // This makes the type act like a distance and not like a score.
+        other.0.cmp(&self.0)
+    }
+}
+
+impl Default for VsCodeDistance {
+    #[inline]
+    fn default() -> Self {
+        Self::from_score(0)
+    }
+}
+
+impl VsCodeDistance {
+    /// Creates a new [`VsCodeDistance`] from a score.
+    #[inline(always)]
+    pub(super) fn from_score(score: Score) -> Self {
+        Self(score)
+    }
+
+    /// Returns a score representation of the distance.
+    ///
+    /// This is not part of the public API and should not be relied upon.
+    ///
+    /// It's only used internally for testing and debugging purposes.
+    #[cfg(any(feature = "__into-score", feature = "__tests"))]
+    #[inline(always)]
+    pub fn into_score(self) -> Score {
+        self.0
+    }
+}
+
+impl crate::combinators::Relevance for VsCodeDistance {
+    #[inline(always)]
+    fn relevance(&self) -> f64 {
+        self.0 as f64
+    }
+}
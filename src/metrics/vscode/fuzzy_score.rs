@@ -0,0 +1,548 @@
+use super::*;
+use crate::utils::CharEq;
+
+/// The maximum number of characters of the pattern and of the word that are
+/// considered when scoring. Any character past this limit is ignored.
+pub(super) const MAX_LEN: usize = 128;
+
+/// The penalty applied when a gap starts right after a contiguous match.
+const GAP_START_PENALTY: Score = -5;
+
+/// The direction a cell of the scoring matrix was reached from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Arrow {
+    /// The character of the pattern was matched to the character of the word.
+    #[default]
+    Diag,
+
+    /// The character of the word was skipped.
+    Left,
+
+    /// The two previous characters of the word were skipped, jumping over a
+    /// diagonal.
+    LeftLeft,
+}
+
+/// A port of the `fuzzyScore` function found in VS Code's
+/// `src/vs/base/common/filters.ts`.
+///
+/// The scoring matrices are kept around between calls to avoid allocating a
+/// new one for every candidate.
+#[derive(Clone, Default)]
+pub(super) struct FuzzyScore {
+    /// The characters of the last pattern.
+    pattern: Vec<char>,
+
+    /// The lowercase characters of the last pattern.
+    pattern_low: Vec<char>,
+
+    /// The characters of the last word.
+    word: Vec<char>,
+
+    /// The lowercase characters of the last word.
+    word_low: Vec<char>,
+
+    /// The minimum word position each character of the pattern can be
+    /// matched at.
+    min_word_match_pos: Vec<usize>,
+
+    /// The maximum word position each character of the pattern can be
+    /// matched at.
+    max_word_match_pos: Vec<usize>,
+
+    /// The length of the contiguous diagonal match ending at each cell.
+    diag: Vec<usize>,
+
+    /// The best score of each cell.
+    table: Vec<Score>,
+
+    /// The direction each cell was reached from.
+    arrows: Vec<Arrow>,
+
+    /// The character offsets in the word of the characters matched by the
+    /// last successful call to [`score`](Self::score), in ascending order.
+    positions: Vec<usize>,
+}
+
+impl FuzzyScore {
+    /// Returns the lowercase characters of the last word.
+    #[inline(always)]
+    pub(super) fn word_low(&self) -> &[char] {
+        &self.word_low
+    }
+
+    /// Returns the lowercase characters of the last pattern.
+    #[inline(always)]
+    pub(super) fn pattern_low(&self) -> &[char] {
+        &self.pattern_low
+    }
+
+    /// Returns the character offsets of the matched characters.
+    #[inline(always)]
+    pub(super) fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// Loads the pattern and the word, returning the number of characters of
+    /// each that take part in the scoring.
+    #[inline]
+    pub(super) fn load(
+        &mut self,
+        pattern: &str,
+        word: &str,
+    ) -> (usize, usize) {
+        fill(&mut self.pattern, &mut self.pattern_low, pattern);
+        fill(&mut self.word, &mut self.word_low, word);
+        (self.pattern.len().min(MAX_LEN), self.word.len().min(MAX_LEN))
+    }
+
+    /// Scores the pattern against the word, returning `None` if they don't
+    /// match.
+    ///
+    /// The lowercase characters of the pattern and of the word are compared
+    /// with `char_eq`.
+    #[inline]
+    pub(super) fn score(
+        &mut self,
+        pattern: &str,
+        word: &str,
+        char_eq: CharEq,
+        first_match_can_be_weak: bool,
+        boost_full_match: bool,
+    ) -> Option<Score> {
+        let (pattern_len, word_len) = self.load(pattern, word);
+
+        if pattern_len == 0 || word_len == 0 || pattern_len > word_len {
+            return None;
+        }
+
+        // Run a simple check to see if the characters of the pattern occur
+        // in order in the word. If they don't, no match is possible.
+        if !self.is_pattern_in_word(pattern_len, word_len, char_eq) {
+            return None;
+        }
+
+        self.fill_in_max_word_match_pos(pattern_len, word_len, char_eq);
+
+        let width = word_len + 1;
+
+        let cells = (pattern_len + 1) * width;
+
+        self.diag.clear();
+        self.diag.resize(cells, 0);
+
+        self.table.clear();
+        self.table.resize(cells, 0);
+
+        self.arrows.clear();
+        self.arrows.resize(cells, Arrow::Diag);
+
+        let mut has_strong_first_match = false;
+
+        for pattern_pos in 0..pattern_len {
+            let row = pattern_pos + 1;
+
+            // Reduce the search space to the possible matching word positions
+            // and to the ones accessible from the next row.
+            let min_word_match_pos = self.min_word_match_pos[pattern_pos];
+
+            let max_word_match_pos = self.max_word_match_pos[pattern_pos];
+
+            let next_max_word_match_pos = if pattern_pos + 1 < pattern_len {
+                self.max_word_match_pos[pattern_pos + 1]
+            } else {
+                word_len
+            };
+
+            for word_pos in min_word_match_pos..next_max_word_match_pos {
+                let column = word_pos + 1;
+
+                let cell = row * width + column;
+
+                let up_left = cell - width - 1;
+
+                let diag_score = if word_pos <= max_word_match_pos {
+                    self.do_score(
+                        pattern_pos,
+                        word_pos,
+                        word_len,
+                        self.diag[up_left] == 0,
+                        char_eq,
+                        &mut has_strong_first_match,
+                    )
+                    .map(|score| score + self.table[up_left])
+                } else {
+                    None
+                };
+
+                let can_come_left = word_pos > min_word_match_pos;
+
+                let left_score = if can_come_left {
+                    self.table[cell - 1]
+                        + gap_start_penalty(self.diag[cell - 1])
+                } else {
+                    0
+                };
+
+                let can_come_left_left = word_pos > min_word_match_pos + 1
+                    && self.diag[cell - 1] > 0;
+
+                let left_left_score = if can_come_left_left {
+                    self.table[cell - 2]
+                        + gap_start_penalty(self.diag[cell - 2])
+                } else {
+                    0
+                };
+
+                if can_come_left_left
+                    && (!can_come_left || left_left_score >= left_score)
+                    && diag_score.is_none_or(|diag| left_left_score >= diag)
+                {
+                    // Always prefer jumping over a diagonal, since that means
+                    // the match is earlier in the word.
+                    self.table[cell] = left_left_score;
+                    self.arrows[cell] = Arrow::LeftLeft;
+                    self.diag[cell] = 0;
+                } else if can_come_left
+                    && diag_score.is_none_or(|diag| left_score >= diag)
+                {
+                    // Always prefer going left, since that means the match is
+                    // earlier in the word.
+                    self.table[cell] = left_score;
+                    self.arrows[cell] = Arrow::Left;
+                    self.diag[cell] = 0;
+                } else if let Some(diag_score) = diag_score {
+                    self.table[cell] = diag_score;
+                    self.arrows[cell] = Arrow::Diag;
+                    self.diag[cell] = self.diag[up_left] + 1;
+                } else {
+                    unreachable!("the first reachable cell is always a match");
+                }
+            }
+        }
+
+        if !has_strong_first_match && !first_match_can_be_weak {
+            return None;
+        }
+
+        let mut score = self.table[cells - 1];
+
+        let max_match_column = self.backtrack(pattern_len, word_len, char_eq);
+
+        if word_len == pattern_len && boost_full_match {
+            // The word matches the pattern with all its characters, so we
+            // boost it to come ahead of the other words.
+            score += 2;
+        }
+
+        // Add a penalty of 1 for each skipped character of the word.
+        score -= (max_match_column - pattern_len) as Score;
+
+        Some(score)
+    }
+
+    /// Walks the matrices back from the bottom-right cell, filling
+    /// `self.positions` and returning the column of the last match.
+    #[inline]
+    fn backtrack(
+        &mut self,
+        pattern_len: usize,
+        word_len: usize,
+        char_eq: CharEq,
+    ) -> usize {
+        let width = word_len + 1;
+
+        let mut row = pattern_len;
+
+        let mut column = word_len;
+
+        let mut backwards_diag_len = 0;
+
+        let mut max_match_column = 0;
+
+        self.positions.clear();
+
+        while row >= 1 {
+            // Find the column where we go diagonally up.
+            let mut diag_column = column;
+
+            while diag_column >= 1 {
+                match self.arrows[row * width + diag_column] {
+                    Arrow::LeftLeft => diag_column -= 2,
+                    Arrow::Left => diag_column -= 1,
+                    Arrow::Diag => break,
+                }
+            }
+
+            // Overturn the "forwards" decision if keeping the "backwards"
+            // diagonal would give a longer contiguous match.
+            if backwards_diag_len > 1
+                && char_eq(
+                    self.pattern_low[row - 1],
+                    self.word_low[column - 1],
+                )
+                && !self.is_upper_case_at(diag_column - 1)
+                && backwards_diag_len + 1
+                    > self.diag[row * width + diag_column]
+            {
+                diag_column = column;
+            }
+
+            if diag_column == column {
+                backwards_diag_len += 1;
+            } else {
+                backwards_diag_len = 1;
+            }
+
+            if max_match_column == 0 {
+                max_match_column = diag_column;
+            }
+
+            row -= 1;
+            column = diag_column - 1;
+            self.positions.push(column);
+        }
+
+        self.positions.reverse();
+
+        max_match_column
+    }
+
+    /// Scores matching the character of the pattern at `pattern_pos` to the
+    /// character of the word at `word_pos`, returning `None` if they're not
+    /// equal.
+    #[inline]
+    fn do_score(
+        &self,
+        pattern_pos: usize,
+        word_pos: usize,
+        word_len: usize,
+        new_match_start: bool,
+        char_eq: CharEq,
+        has_strong_first_match: &mut bool,
+    ) -> Option<Score> {
+        if !char_eq(self.pattern_low[pattern_pos], self.word_low[word_pos]) {
+            return None;
+        }
+
+        let same_case = self.pattern[pattern_pos] == self.word[word_pos];
+
+        let prev_pos = word_pos.wrapping_sub(1);
+
+        let mut score = 1;
+
+        let mut is_gap_location = false;
+
+        if word_pos == pattern_pos {
+            // Common prefix: `foobar <-> foobaz`.
+            score = if same_case { 7 } else { 5 };
+        } else if self.is_upper_case_at(word_pos)
+            && (word_pos == 0 || !self.is_upper_case_at(prev_pos))
+        {
+            // Hitting an uppercase character: `foo <-> forOthers`.
+            score = if same_case { 7 } else { 5 };
+            is_gap_location = true;
+        } else if is_separator_at(&self.word_low, word_pos)
+            && (word_pos == 0 || !is_separator_at(&self.word_low, prev_pos))
+        {
+            // Hitting a separator: `. <-> foo.bar`.
+            score = 5;
+        } else if is_separator_at(&self.word_low, prev_pos)
+            || is_whitespace_at(&self.word_low, prev_pos)
+        {
+            // Right after a separator: `foo <-> bar_foo`.
+            score = 5;
+            is_gap_location = true;
+        }
+
+        if score > 1 && pattern_pos == 0 {
+            *has_strong_first_match = true;
+        }
+
+        if !is_gap_location {
+            is_gap_location = self.is_upper_case_at(word_pos)
+                || is_separator_at(&self.word_low, prev_pos)
+                || is_whitespace_at(&self.word_low, prev_pos);
+        }
+
+        if pattern_pos == 0 {
+            // The first character of the pattern would match a character
+            // that's not at the start of the word, so we penalize the gap
+            // preceding it.
+            if word_pos > 0 {
+                score -= if is_gap_location { 3 } else { 5 };
+            }
+        } else if new_match_start {
+            // This would be the start of a new match after a gap.
+            score += if is_gap_location { 2 } else { 0 };
+        } else {
+            // This is part of a contiguous match, so we give it a slight
+            // bonus, but only if it's not a preferred gap location.
+            score += if is_gap_location { 0 } else { 1 };
+        }
+
+        if word_pos + 1 == word_len {
+            // We always penalize gaps, which gives an unfair advantage to
+            // matches ending at the last character of the word, so we pretend
+            // there's a gap after it.
+            score -= if is_gap_location { 3 } else { 5 };
+        }
+
+        Some(score)
+    }
+
+    /// Returns whether the characters of the pattern appear in order in the
+    /// word, filling `self.min_word_match_pos` along the way.
+    #[inline]
+    fn is_pattern_in_word(
+        &mut self,
+        pattern_len: usize,
+        word_len: usize,
+        char_eq: CharEq,
+    ) -> bool {
+        self.min_word_match_pos.clear();
+        self.min_word_match_pos.resize(pattern_len, 0);
+
+        let mut pattern_pos = 0;
+
+        let mut word_pos = 0;
+
+        while pattern_pos < pattern_len && word_pos < word_len {
+            if char_eq(self.pattern_low[pattern_pos], self.word_low[word_pos])
+            {
+                self.min_word_match_pos[pattern_pos] = word_pos;
+                pattern_pos += 1;
+            }
+            word_pos += 1;
+        }
+
+        pattern_pos == pattern_len
+    }
+
+    /// Fills `self.max_word_match_pos` by matching the pattern to the word
+    /// backwards.
+    #[inline]
+    fn fill_in_max_word_match_pos(
+        &mut self,
+        pattern_len: usize,
+        word_len: usize,
+        char_eq: CharEq,
+    ) {
+        self.max_word_match_pos.clear();
+        self.max_word_match_pos.resize(pattern_len, 0);
+
+        let mut pattern_pos = pattern_len;
+
+        let mut word_pos = word_len;
+
+        while pattern_pos > 0 && word_pos > 0 {
+            if char_eq(
+                self.pattern_low[pattern_pos - 1],
+                self.word_low[word_pos - 1],
+            ) {
+                self.max_word_match_pos[pattern_pos - 1] = word_pos - 1;
+                pattern_pos -= 1;
+            }
+            word_pos -= 1;
+        }
+    }
+
+    /// Returns whether the character of the word at `pos` is uppercase, i.e.
+    /// whether it differs from its lowercase version.
+    #[inline(always)]
+    fn is_upper_case_at(&self, pos: usize) -> bool {
+        self.word.get(pos) != self.word_low.get(pos)
+    }
+}
+
+/// Returns the penalty for starting a gap after a cell whose contiguous
+/// diagonal match has the given length.
+#[inline(always)]
+fn gap_start_penalty(diag: usize) -> Score {
+    if diag > 0 {
+        GAP_START_PENALTY
+    } else {
+        0
+    }
+}
+
+/// Clears the buffers and fills them with the characters of `s` and their
+/// lowercase versions.
+#[inline]
+fn fill(chars: &mut Vec<char>, lower: &mut Vec<char>, s: &str) {
+    chars.clear();
+    chars.extend(s.chars());
+    lower.clear();
+    lower.extend(chars.iter().copied().map(to_lowercase));
+}
+
+/// Lowercases the character, keeping it as is if its lowercase version is
+/// not a single character.
+#[inline(always)]
+fn to_lowercase(ch: char) -> char {
+    if ch.is_ascii() {
+        return ch.to_ascii_lowercase();
+    }
+
+    let mut lower = ch.to_lowercase();
+
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => ch,
+    }
+}
+
+/// Returns whether the character at `pos` is a word separator.
+#[inline(always)]
+fn is_separator_at(chars: &[char], pos: usize) -> bool {
+    chars.get(pos).is_some_and(|&ch| {
+        matches!(
+            ch,
+            '_' | '-'
+                | '.'
+                | ' '
+                | '/'
+                | '\\'
+                | '\''
+                | '"'
+                | ':'
+                | '$'
+                | '<'
+                | '>'
+                | '('
+                | ')'
+                | '['
+                | ']'
+                | '{'
+                | '}'
+        ) || is_emoji_imprecise(ch)
+    })
+}
+
+/// Returns whether the character at `pos` is a space or a tab.
+#[inline(always)]
+fn is_whitespace_at(chars: &[char], pos: usize) -> bool {
+    matches!(chars.get(pos), Some(' ' | '\t'))
+}
+
+/// Returns whether the character is (very likely) an emoji, using the same
+/// ranges as VS Code.
+#[inline(always)]
+fn is_emoji_imprecise(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x1F1E6..=0x1F1FF
+            | 8986
+            | 8987
+            | 9200
+            | 9203
+            | 9728..=10175
+            | 11088
+            | 11093
+            | 127744..=128591
+            | 128640..=128764
+            | 128992..=129008
+            | 129280..=129535
+            | 129648..=129782
+    )
+}
@@ -0,0 +1,12 @@
+//! A metric implementing the fuzzy scorer of Visual Studio Code.
+//!
+//! See the documentation of [`VsCode`] for more information.
+
+mod distance;
+mod fuzzy_score;
+mod vscode;
+
+pub use distance::VsCodeDistance;
+use distance::*;
+use fuzzy_score::FuzzyScore;
+pub use vscode::VsCode;
@@ -0,0 +1,394 @@
+use core::ops::Range;
+
+use super::fuzzy_score::MAX_LEN;
+use super::*;
+use crate::utils::{self, CharEq};
+use crate::{MatchedRanges, Metric};
+
+/// The base score of a path whose basename starts with the query.
+const LABEL_PREFIX_SCORE_THRESHOLD: Score = 1 << 17;
+
+/// The base score of a path whose basename matches the query.
+const LABEL_SCORE_THRESHOLD: Score = 1 << 16;
+
+/// A metric that implements the fuzzy scorer of [Visual Studio Code][vscode].
+///
+/// The [`Metric`] implementation of this struct is a port of the
+/// `fuzzyScore` function found in VS Code's `src/vs/base/common/filters.ts`,
+/// and it produces the same scores and the same matched positions.
+///
+/// A candidate matches the query if it contains all the characters of the
+/// query in the same order, and if the first character of the query is
+/// matched at the start of the candidate, at the start of a word, or right
+/// after a separator. Matches are then scored by rewarding characters
+/// matched at the start of a word, at a camelCase boundary or after a
+/// separator, characters with the same case as in the query, and
+/// consecutive matches, while penalizing the gaps between them.
+///
+/// Characters are always compared case-insensitively, and just like in VS
+/// Code only the first 128 characters of the query and of the candidate are
+/// considered. An empty query matches every candidate with a score of zero.
+///
+/// # Path mode
+///
+/// When the path mode is enabled with
+/// [`set_path_mode`](VsCode::set_path_mode), candidates are treated as file
+/// paths and scored the same way Quick Open scores files: the query is first
+/// matched against the basename of the path, and only if that fails against
+/// the whole path, i.e. the directory followed by the basename. Basename
+/// matches always rank above path matches, and basenames starting with the
+/// query rank above all other basename matches, with shorter basenames
+/// ranking higher.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::vscode::VsCode;
+/// # use norm::Metric;
+/// let mut vscode = VsCode::new();
+/// let mut ranges = Vec::new();
+///
+/// let distance = vscode
+///     .distance_and_ranges("TEdit", "TextEditor", &mut ranges)
+///     .unwrap();
+///
+/// assert_eq!(ranges, [0..1, 4..8]);
+///
+/// // The first character of the query has to match at a word start.
+/// assert!(vscode.distance("ext", "TextEditor").is_none());
+///
+/// // "TextEditor" is a better match than "TypeOfTextEditor" since fewer
+/// // characters are skipped before the last match.
+/// assert!(distance < vscode.distance("TEdit", "TypeOfTextEditor").unwrap());
+/// ```
+///
+/// [vscode]: https://github.com/microsoft/vscode
+#[cfg_attr(docsrs, doc(cfg(feature = "vscode")))]
+#[derive(Clone)]
+pub struct VsCode {
+    /// Whether multi-byte latin characters in the candidate are normalized to
+    /// ASCII before being compared to the query.
+    candidate_normalization: bool,
+
+    /// Whether candidates are scored like file paths, matching the basename
+    /// before the whole path.
+    path_mode: bool,
+
+    /// Whether the first character of the query can match in the middle of a
+    /// word.
+    first_match_can_be_weak: bool,
+
+    /// Whether candidates fully matched by the query get a score boost.
+    boost_full_match: bool,
+
+    /// The scorer doing the actual work.
+    scorer: FuzzyScore,
+}
+
+impl core::fmt::Debug for VsCode {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VsCode")
+            .field("candidate_normalization", &self.candidate_normalization)
+            .field("path_mode", &self.path_mode)
+            .field("first_match_can_be_weak", &self.first_match_can_be_weak)
+            .field("boost_full_match", &self.boost_full_match)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for VsCode {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            candidate_normalization: false,
+            path_mode: false,
+            first_match_can_be_weak: false,
+            boost_full_match: true,
+            scorer: FuzzyScore::default(),
+        }
+    }
+}
+
+impl VsCode {
+    /// Creates a new `VsCode`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether multi-byte latin characters in the candidate should be
+    /// normalized to ASCII before comparing them to the query. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::vscode::VsCode;
+    /// # use norm::Metric;
+    /// let mut vscode = VsCode::new();
+    ///
+    /// assert!(vscode.distance("foo", "ƒöö").is_none());
+    ///
+    /// vscode.set_candidate_normalization(true);
+    ///
+    /// assert!(vscode.distance("foo", "ƒöö").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_candidate_normalization(
+        &mut self,
+        normalization: bool,
+    ) -> &mut Self {
+        self.candidate_normalization = normalization;
+        self
+    }
+
+    /// Sets whether candidates should be treated as file paths, scoring
+    /// their basename and their directory separately. The default is
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::vscode::VsCode;
+    /// # use norm::Metric;
+    /// let mut vscode = VsCode::new();
+    ///
+    /// let in_dir = vscode.distance("main", "src/main/lib.rs").unwrap();
+    /// let in_basename = vscode.distance("main", "src/lib/main.rs").unwrap();
+    ///
+    /// // Both candidates match at the start of a word.
+    /// assert!(in_dir < in_basename);
+    ///
+    /// vscode.set_path_mode(true);
+    ///
+    /// let in_dir = vscode.distance("main", "src/main/lib.rs").unwrap();
+    /// let in_basename = vscode.distance("main", "src/lib/main.rs").unwrap();
+    ///
+    /// // Matches in the basename are now preferred.
+    /// assert!(in_basename < in_dir);
+    /// ```
+    #[inline(always)]
+    pub fn set_path_mode(&mut self, path_mode: bool) -> &mut Self {
+        self.path_mode = path_mode;
+        self
+    }
+
+    /// Sets whether the first character of the query can be matched in the
+    /// middle of a word. The default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::vscode::VsCode;
+    /// # use norm::Metric;
+    /// let mut vscode = VsCode::new();
+    ///
+    /// assert!(vscode.distance("ext", "TextEditor").is_none());
+    ///
+    /// vscode.set_first_match_can_be_weak(true);
+    ///
+    /// assert!(vscode.distance("ext", "TextEditor").is_some());
+    /// ```
+    #[inline(always)]
+    pub fn set_first_match_can_be_weak(
+        &mut self,
+        first_match_can_be_weak: bool,
+    ) -> &mut Self {
+        self.first_match_can_be_weak = first_match_can_be_weak;
+        self
+    }
+
+    /// Sets whether candidates whose characters are all matched by the query
+    /// should get a score boost. The default is `true`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use norm::vscode::VsCode;
+    /// # use norm::Metric;
+    /// let mut vscode = VsCode::new();
+    ///
+    /// let boosted = vscode.distance("foo", "foo").unwrap();
+    ///
+    /// vscode.set_boost_full_match(false);
+    ///
+    /// assert!(boosted < vscode.distance("foo", "foo").unwrap());
+    /// ```
+    #[inline(always)]
+    pub fn set_boost_full_match(
+        &mut self,
+        boost_full_match: bool,
+    ) -> &mut Self {
+        self.boost_full_match = boost_full_match;
+        self
+    }
+
+    /// Scores the candidate, inserting the byte ranges of the matched
+    /// characters into `ranges` if `RANGES` is true.
+    ///
+    /// In path mode a match in the basename is preferred, and it's only if the
+    /// query can't be found there that the whole path is scored.
+    #[inline]
+    fn distance<const RANGES: bool>(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<VsCodeDistance> {
+        if query.is_empty() {
+            return Some(VsCodeDistance::from_score(0));
+        }
+
+        if !self.path_mode {
+            let score = self.fuzzy_score(query, candidate)?;
+
+            if RANGES {
+                self.insert_ranges(candidate, 0, ranges);
+            }
+
+            return Some(VsCodeDistance::from_score(score));
+        }
+
+        let (dir_len, label) = match candidate.rfind(['/', '\\']) {
+            Some(sep_offset) => (sep_offset, &candidate[sep_offset + 1..]),
+            None => (0, candidate),
+        };
+
+        let label_offset = candidate.len() - label.len();
+
+        if let Some(label_score) = self.fuzzy_score(query, label) {
+            // If the basename starts with the query we give it a much higher
+            // base score, so that typing a file name wins over results that
+            // are found somewhere else in the basename.
+            let query_len = self.scorer.pattern_low().len();
+
+            let label_len = self.scorer.word_low().len();
+
+            let char_eq = self.char_eq();
+
+            let is_prefix_match = query_len <= label_len
+                && self
+                    .scorer
+                    .pattern_low()
+                    .iter()
+                    .zip(self.scorer.word_low())
+                    .all(|(&query_ch, &label_ch)| char_eq(query_ch, label_ch));
+
+            let base_score = if is_prefix_match {
+                // Give another boost to shorter basenames, so that e.g.
+                // "window.ts" ranks above "windowActions.ts" for "window".
+                let prefix_len_boost =
+                    (query_len as f64 / label_len as f64 * 100.0).round();
+
+                LABEL_PREFIX_SCORE_THRESHOLD + prefix_len_boost as Score
+            } else {
+                LABEL_SCORE_THRESHOLD
+            };
+
+            if RANGES {
+                if is_prefix_match {
+                    let prefix_byte_len = label
+                        .char_indices()
+                        .nth(query_len)
+                        .map_or(label.len(), |(offset, _)| offset);
+
+                    let mut ranges = MatchedRanges::from(ranges);
+
+                    ranges
+                        .insert(label_offset..label_offset + prefix_byte_len);
+                } else {
+                    self.insert_ranges(label, label_offset, ranges);
+                }
+            }
+
+            return Some(VsCodeDistance::from_score(base_score + label_score));
+        }
+
+        if dir_len == 0 {
+            return None;
+        }
+
+        let score = self.fuzzy_score(query, candidate)?;
+
+        if RANGES {
+            self.insert_ranges(candidate, 0, ranges);
+        }
+
+        Some(VsCodeDistance::from_score(score))
+    }
+
+    /// Returns the function used to compare the lowercase characters of the
+    /// query and the candidate.
+    #[inline(always)]
+    fn char_eq(&self) -> CharEq {
+        utils::char_eq(false, self.candidate_normalization)
+    }
+
+    /// Runs VS Code's `fuzzyScore` on the query and the word.
+    #[inline(always)]
+    fn fuzzy_score(&mut self, query: &str, word: &str) -> Option<Score> {
+        let char_eq = self.char_eq();
+
+        self.scorer.score(
+            query,
+            word,
+            char_eq,
+            self.first_match_can_be_weak,
+            self.boost_full_match,
+        )
+    }
+
+    /// Inserts the byte ranges of the characters of the word matched by the
+    /// last successful call to [`fuzzy_score`](Self::fuzzy_score), shifted by
+    /// `offset`.
+    #[inline]
+    fn insert_ranges(
+        &self,
+        word: &str,
+        offset: usize,
+        ranges: &mut Vec<Range<usize>>,
+    ) {
+        let mut ranges = MatchedRanges::from(ranges);
+
+        let mut positions = self.scorer.positions().iter().copied().peekable();
+
+        for (char_idx, (byte_offset, ch)) in
+            word.char_indices().take(MAX_LEN).enumerate()
+        {
+            let Some(&position) = positions.peek() else { break };
+
+            if char_idx == position {
+                let start = offset + byte_offset;
+                ranges.insert(start..start + ch.len_utf8());
+                positions.next();
+            }
+        }
+    }
+}
+
+impl Metric for VsCode {
+    type Query<'a> = &'a str;
+
+    type Distance = VsCodeDistance;
+
+    #[inline(always)]
+    fn distance(
+        &mut self,
+        query: &str,
+        candidate: &str,
+    ) -> Option<Self::Distance> {
+        let ranges = &mut Vec::new();
+        self.distance::<false>(query, candidate, ranges)
+    }
+
+    #[inline(always)]
+    fn distance_and_ranges(
+        &mut self,
+        query: &str,
+        candidate: &str,
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Option<Self::Distance> {
+        self.distance::<true>(query, candidate, ranges)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::Metric;
+
+/// A trait representing a parser of the queries of a [`Metric`].
+///
+/// Every metric has its own [`Query`](Metric::Query) type, and this trait
+/// provides a standard way to build it from a raw string, so that generic
+/// code like a picker can be written once over any metric and its parser.
+///
+/// Metrics whose queries are plain strings can use the [`StrParser`], while
+/// the fzf metrics can use the [`FzfParser`](crate::fzf::FzfParser).
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::fzf::{FzfParser, FzfV2};
+/// # use norm::levenshtein::Levenshtein;
+/// # use norm::{Metric, Parser, StrParser};
+/// fn best_match<'c, M, P>(
+///     metric: &mut M,
+///     parser: &mut P,
+///     query: &str,
+///     candidates: &[&'c str],
+/// ) -> Option<&'c str>
+/// where
+///     M: for<'a> Metric<Query<'a>: Copy>,
+///     P: Parser<M>,
+/// {
+///     let query = parser.parse(query);
+///
+///     candidates
+///         .iter()
+///         .filter_map(|&candidate| {
+///             let distance = metric.distance(query, candidate)?;
+///             Some((distance, candidate))
+///         })
+///         .min_by(|(a, _), (b, _)| a.cmp(b))
+///         .map(|(_, candidate)| candidate)
+/// }
+///
+/// let candidates = ["fob", "foo_bar", "baz"];
+///
+/// let mut fzf = FzfV2::new();
+///
+/// let mut lev = Levenshtein::new();
+///
+/// assert_eq!(
+///     best_match(&mut fzf, &mut FzfParser::new(), "fb", &candidates),
+///     Some("foo_bar")
+/// );
+///
+/// assert_eq!(
+///     best_match(&mut lev, &mut StrParser, "fb", &candidates),
+///     Some("fob")
+/// );
+/// ```
+pub trait Parser<M: Metric + ?Sized> {
+    /// Parses the given string into a query of the metric.
+    ///
+    /// The returned query can borrow both from the parser and from the
+    /// string.
+    fn parse<'a>(&'a mut self, query: &'a str) -> M::Query<'a>;
+}
+
+/// The [`Parser`] of the metrics whose queries are plain strings, which
+/// returns its input as is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrParser;
+
+impl<M> Parser<M> for StrParser
+where
+    M: for<'a> Metric<Query<'a> = &'a str> + ?Sized,
+{
+    #[inline(always)]
+    fn parse<'a>(&'a mut self, query: &'a str) -> &'a str {
+        query
+    }
+}
@@ -0,0 +1,39 @@
+use crate::Metric;
+
+/// A trait for metrics whose distances can be mapped to a normalized
+/// similarity.
+///
+/// The distances of most metrics are only meaningful when compared to other
+/// distances computed for the same query. This trait maps them to a
+/// similarity between `0.0` and `1.0`, where `1.0` is the best possible match
+/// for the query, which makes it possible to apply thresholds like "only keep
+/// the matches with a similarity above `0.6`", or to blend the results of
+/// different metrics.
+///
+/// # Example
+///
+/// ```rust
+/// # use norm::fzf::{FzfParser, FzfV2};
+/// # use norm::{Metric, Similarity};
+/// let mut fzf = FzfV2::new();
+/// let mut parser = FzfParser::new();
+///
+/// let query = parser.parse("foo");
+///
+/// let exact = fzf.distance(query, "foo").unwrap();
+///
+/// assert_eq!(fzf.similarity(query, &exact), 1.0);
+///
+/// let scattered = fzf.distance(query, "xfxxxoxxxo").unwrap();
+///
+/// assert!(fzf.similarity(query, &scattered) < 0.5);
+/// ```
+pub trait Similarity: Metric {
+    /// Returns the similarity between `0.0` and `1.0` corresponding to the
+    /// given distance, which must have been computed for the same query.
+    fn similarity(
+        &self,
+        query: Self::Query<'_>,
+        distance: &Self::Distance,
+    ) -> f64;
+}
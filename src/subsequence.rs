@@ -0,0 +1,616 @@
+//! Subsequence search on ASCII haystacks.
+//!
+//! Looking for the characters of a pattern one at a time with `memchr` means
+//! restarting a new search (and paying its setup cost) for every character.
+//! The functions in this module instead look for the whole pattern in a
+//! single pass over the haystack: every block of bytes is loaded once, and
+//! all the pattern characters that fall inside it are found before moving on
+//! to the next block.
+//!
+//! The vectorised implementation is selected at runtime if the CPU supports
+//! it, otherwise we fall back to a scalar implementation built on `memchr`.
+
+#[cfg(feature = "__benches")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::utils::ascii_letter_flip_case;
+
+/// Whether the vectorised implementations should be used when the CPU
+/// supports them. Only the benchmarks turn this off, to measure the scalar
+/// implementations on the same machine.
+#[cfg(feature = "__benches")]
+static SIMD_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables the vectorised subsequence search.
+#[cfg(feature = "__benches")]
+#[doc(hidden)]
+pub fn set_simd_enabled(enabled: bool) {
+    SIMD_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn use_avx2() -> bool {
+    #[cfg(feature = "__benches")]
+    if !SIMD_ENABLED.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    std::is_x86_feature_detected!("avx2")
+}
+
+/// Looks for the first occurrence of `needle` as a subsequence of
+/// `haystack`, calling `on_match` with the offset of every matched byte.
+///
+/// Returns the offset of the byte matching the last character of the needle,
+/// or `None` if the needle is empty or not a subsequence of the haystack.
+#[inline(always)]
+pub(crate) fn find_first<I, F>(
+    haystack: &[u8],
+    needle: I,
+    is_case_sensitive: bool,
+    on_match: F,
+) -> Option<usize>
+where
+    I: Iterator<Item = char>,
+    F: FnMut(usize),
+{
+    #[cfg(target_arch = "x86_64")]
+    if use_avx2() {
+        // SAFETY: we just checked that the CPU supports AVX2.
+        return unsafe {
+            avx2::find_first(haystack, needle, is_case_sensitive, on_match)
+        };
+    }
+
+    scalar::find_first(haystack, needle, is_case_sensitive, on_match)
+}
+
+/// Like [`find_first`], but scans the haystack backwards looking for the
+/// characters yielded by `needle`, which should therefore be given in reverse
+/// order.
+///
+/// Returns the offset of the byte matching the last character yielded by the
+/// needle, i.e. the start of the match.
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+#[inline(always)]
+pub(crate) fn find_last<I, F>(
+    haystack: &[u8],
+    needle: I,
+    is_case_sensitive: bool,
+    on_match: F,
+) -> Option<usize>
+where
+    I: Iterator<Item = char>,
+    F: FnMut(usize),
+{
+    #[cfg(target_arch = "x86_64")]
+    if use_avx2() {
+        // SAFETY: we just checked that the CPU supports AVX2.
+        return unsafe {
+            avx2::find_last(haystack, needle, is_case_sensitive, on_match)
+        };
+    }
+
+    scalar::find_last(haystack, needle, is_case_sensitive, on_match)
+}
+
+/// Returns the two bytes matching the given needle character, which are
+/// the same byte unless the character is a letter and the search is
+/// case-insensitive.
+///
+/// Returns `None` if the character is not ASCII, in which case it can't be
+/// found in an ASCII haystack.
+#[inline(always)]
+fn needle_bytes(ch: char, is_case_sensitive: bool) -> Option<(u8, u8)> {
+    if !ch.is_ascii() {
+        return None;
+    }
+
+    let byte = ch as u8;
+
+    if is_case_sensitive || !byte.is_ascii_alphabetic() {
+        Some((byte, byte))
+    } else {
+        Some((byte, ascii_letter_flip_case(byte)))
+    }
+}
+
+mod scalar {
+    use super::needle_bytes;
+
+    #[inline(always)]
+    pub(super) fn find_first<I, F>(
+        haystack: &[u8],
+        needle: I,
+        is_case_sensitive: bool,
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
+        let mut last_match = None;
+
+        let mut offset = 0;
+
+        for ch in needle {
+            let (a, b) = needle_bytes(ch, is_case_sensitive)?;
+
+            let idx = offset + memchr::memchr2(a, b, &haystack[offset..])?;
+
+            on_match(idx);
+
+            last_match = Some(idx);
+
+            offset = idx + 1;
+        }
+
+        last_match
+    }
+
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[inline(always)]
+    pub(super) fn find_last<I, F>(
+        haystack: &[u8],
+        needle: I,
+        is_case_sensitive: bool,
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
+        let mut last_match = None;
+
+        let mut end = haystack.len();
+
+        for ch in needle {
+            let (a, b) = needle_bytes(ch, is_case_sensitive)?;
+
+            let idx = memchr::memrchr2(a, b, &haystack[..end])?;
+
+            on_match(idx);
+
+            last_match = Some(idx);
+
+            end = idx;
+        }
+
+        last_match
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use core::arch::x86_64::*;
+
+    use super::needle_bytes;
+
+    /// The number of bytes in a 256-bit register.
+    const LANES: usize = 32;
+
+    /// The number of registers we look at in a single iteration while
+    /// skipping over blocks that don't contain the current needle character.
+    const UNROLL: usize = 4;
+
+    /// The two bytes matching the current needle character, each one
+    /// broadcast to all the lanes of a register.
+    #[derive(Clone, Copy)]
+    struct Needle {
+        a: __m256i,
+        b: __m256i,
+    }
+
+    impl Needle {
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn new(ch: char, is_case_sensitive: bool) -> Option<Self> {
+            let (a, b) = needle_bytes(ch, is_case_sensitive)?;
+            let a = _mm256_set1_epi8(a as i8);
+            let b = _mm256_set1_epi8(b as i8);
+            Some(Self { a, b })
+        }
+
+        /// Returns a register whose lanes are all ones where the byte in
+        /// `chunk` matches the needle, and all zeros otherwise.
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn eq(self, chunk: __m256i) -> __m256i {
+            let eq_a = _mm256_cmpeq_epi8(chunk, self.a);
+            let eq_b = _mm256_cmpeq_epi8(chunk, self.b);
+            _mm256_or_si256(eq_a, eq_b)
+        }
+
+        /// Returns a bitmask with the `i`-th bit set if the `i`-th byte of
+        /// `chunk` matches the needle.
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn eq_mask(self, chunk: __m256i) -> u32 {
+            _mm256_movemask_epi8(self.eq(chunk)) as u32
+        }
+
+        /// Returns `true` if any of the `UNROLL * LANES` bytes starting at
+        /// `ptr` match the needle.
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn is_in_block(self, ptr: *const u8) -> bool {
+            let mut any = _mm256_setzero_si256();
+            for idx in 0..UNROLL {
+                let chunk = load(ptr.add(idx * LANES));
+                any = _mm256_or_si256(any, self.eq(chunk));
+            }
+            _mm256_movemask_epi8(any) != 0
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn matches(self, byte: u8) -> bool {
+            let a = _mm256_extract_epi8::<0>(self.a) as u8;
+            let b = _mm256_extract_epi8::<0>(self.b) as u8;
+            byte == a || byte == b
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(ptr: *const u8) -> __m256i {
+        _mm256_loadu_si256(ptr as *const _)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn find_first<I, F>(
+        haystack: &[u8],
+        mut needle: I,
+        is_case_sensitive: bool,
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
+        let mut current = Needle::new(needle.next()?, is_case_sensitive)?;
+
+        let ptr = haystack.as_ptr();
+
+        let len = haystack.len();
+
+        if len < LANES {
+            return scalar_first(
+                haystack,
+                current,
+                needle,
+                is_case_sensitive,
+                on_match,
+            );
+        }
+
+        let mut offset = 0;
+
+        while offset < len {
+            // Quickly skip over the blocks that don't contain the current
+            // character.
+            while offset + UNROLL * LANES <= len
+                && !current.is_in_block(ptr.add(offset))
+            {
+                offset += UNROLL * LANES;
+            }
+
+            // If there are less than `LANES` bytes left we load the last
+            // `LANES` bytes of the haystack, skipping the lanes we've already
+            // looked at.
+            let start = offset.min(len - LANES);
+
+            let chunk = load(ptr.add(start));
+
+            // The index of the first lane we haven't looked at yet.
+            let mut from = offset - start;
+
+            loop {
+                let mask = current.eq_mask(chunk) & (u32::MAX << from);
+
+                if mask == 0 {
+                    break;
+                }
+
+                let lane = mask.trailing_zeros() as usize;
+
+                on_match(start + lane);
+
+                let Some(next) = needle.next() else {
+                    return Some(start + lane);
+                };
+
+                current = Needle::new(next, is_case_sensitive)?;
+
+                from = lane + 1;
+
+                if from == LANES {
+                    break;
+                }
+            }
+
+            offset = start + LANES;
+        }
+
+        None
+    }
+
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn find_last<I, F>(
+        haystack: &[u8],
+        mut needle: I,
+        is_case_sensitive: bool,
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
+        let mut current = Needle::new(needle.next()?, is_case_sensitive)?;
+
+        let ptr = haystack.as_ptr();
+
+        if haystack.len() < LANES {
+            return scalar_last(
+                haystack,
+                current,
+                needle,
+                is_case_sensitive,
+                on_match,
+            );
+        }
+
+        let mut end = haystack.len();
+
+        while end > 0 {
+            // Quickly skip over the blocks that don't contain the current
+            // character.
+            while end >= UNROLL * LANES
+                && !current.is_in_block(ptr.add(end - UNROLL * LANES))
+            {
+                end -= UNROLL * LANES;
+            }
+
+            // If there are less than `LANES` bytes left we load the first
+            // `LANES` bytes of the haystack, skipping the lanes we've already
+            // looked at.
+            let start = end.saturating_sub(LANES);
+
+            let chunk = load(ptr.add(start));
+
+            // The number of lanes, starting from the first one, that we
+            // haven't looked at yet.
+            let mut until = end - start;
+
+            while until > 0 {
+                let mask =
+                    current.eq_mask(chunk) & (u32::MAX >> (LANES - until));
+
+                if mask == 0 {
+                    break;
+                }
+
+                let lane = (u32::BITS - 1 - mask.leading_zeros()) as usize;
+
+                on_match(start + lane);
+
+                let Some(next) = needle.next() else {
+                    return Some(start + lane);
+                };
+
+                current = Needle::new(next, is_case_sensitive)?;
+
+                until = lane;
+            }
+
+            end = start;
+        }
+
+        None
+    }
+
+    /// Byte-by-byte version of [`find_first`] used for haystacks shorter
+    /// than a register.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn scalar_first<I, F>(
+        haystack: &[u8],
+        mut current: Needle,
+        mut needle: I,
+        is_case_sensitive: bool,
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
+        for (idx, &byte) in haystack.iter().enumerate() {
+            if current.matches(byte) {
+                on_match(idx);
+
+                let Some(next) = needle.next() else {
+                    return Some(idx);
+                };
+
+                current = Needle::new(next, is_case_sensitive)?;
+            }
+        }
+
+        None
+    }
+
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    /// Byte-by-byte version of [`find_last`] used for haystacks shorter
+    /// than a register.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn scalar_last<I, F>(
+        haystack: &[u8],
+        mut current: Needle,
+        mut needle: I,
+        is_case_sensitive: bool,
+        mut on_match: F,
+    ) -> Option<usize>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(usize),
+    {
+        for (idx, &byte) in haystack.iter().enumerate().rev() {
+            if current.matches(byte) {
+                on_match(idx);
+
+                let Some(next) = needle.next() else {
+                    return Some(idx);
+                };
+
+                current = Needle::new(next, is_case_sensitive)?;
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first(
+        haystack: &str,
+        needle: &str,
+        is_case_sensitive: bool,
+    ) -> Option<(usize, Vec<usize>)> {
+        let mut matches = Vec::new();
+
+        let last = find_first(
+            haystack.as_bytes(),
+            needle.chars(),
+            is_case_sensitive,
+            |idx| matches.push(idx),
+        )?;
+
+        let mut scalar_matches = Vec::new();
+
+        let scalar_last = scalar::find_first(
+            haystack.as_bytes(),
+            needle.chars(),
+            is_case_sensitive,
+            |idx| scalar_matches.push(idx),
+        );
+
+        assert_eq!(scalar_last, Some(last));
+
+        assert_eq!(scalar_matches, matches);
+
+        Some((last, matches))
+    }
+
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    fn last(
+        haystack: &str,
+        needle: &str,
+        is_case_sensitive: bool,
+    ) -> Option<(usize, Vec<usize>)> {
+        let mut matches = Vec::new();
+
+        let start = find_last(
+            haystack.as_bytes(),
+            needle.chars().rev(),
+            is_case_sensitive,
+            |idx| matches.push(idx),
+        )?;
+
+        let mut scalar_matches = Vec::new();
+
+        let scalar_start = scalar::find_last(
+            haystack.as_bytes(),
+            needle.chars().rev(),
+            is_case_sensitive,
+            |idx| scalar_matches.push(idx),
+        );
+
+        assert_eq!(scalar_start, Some(start));
+
+        assert_eq!(scalar_matches, matches);
+
+        Some((start, matches))
+    }
+
+    #[test]
+    fn subsequence_find_first_short() {
+        assert_eq!(first("a_b_abcd", "abc", true), Some((6, vec![0, 2, 6])));
+        assert_eq!(first("a_b_abcd", "ABC", false), Some((6, vec![0, 2, 6])));
+        assert_eq!(first("a_b_abcd", "ABC", true), None);
+        assert_eq!(first("a_b_abcd", "abcde", true), None);
+        assert_eq!(first("a_b_abcd", "", true), None);
+        assert_eq!(first("a_b_abcd", "ä", false), None);
+    }
+
+    #[test]
+    fn subsequence_find_first_long() {
+        let haystack =
+            format!("{}f{}o{}O", "_".repeat(40), "x".repeat(70), "y");
+
+        assert_eq!(
+            first(&haystack, "foo", false),
+            Some((113, vec![40, 111, 113]))
+        );
+        assert_eq!(first(&haystack, "foo", true), None);
+
+        // Multiple matches within the same block.
+        let haystack = "abcdefghijklmnopqrstuvwxyz0123456789".repeat(3);
+
+        assert_eq!(
+            first(&haystack, "a0za9", true),
+            Some((107, vec![0, 26, 61, 72, 107]))
+        );
+    }
+
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[test]
+    fn subsequence_find_last_short() {
+        assert_eq!(last("a_b_abcd", "abc", true), Some((4, vec![6, 5, 4])));
+        assert_eq!(last("a_b_abcd", "A_B", false), Some((0, vec![5, 3, 0])));
+        assert_eq!(last("a_b_abcd", "A_B", true), None);
+        assert_eq!(last("a_b_abcd", "", true), None);
+    }
+
+    #[cfg(any(feature = "fzf-v1", feature = "fzf-v2"))]
+    #[test]
+    fn subsequence_find_last_long() {
+        let haystack =
+            format!("O{}o{}f{}", "y", "x".repeat(70), "_".repeat(40));
+
+        assert_eq!(last(&haystack, "oof", false), Some((0, vec![73, 2, 0])));
+        assert_eq!(last(&haystack, "oof", true), None);
+    }
+
+    #[test]
+    fn subsequence_block_boundaries() {
+        for len in [31, 32, 33, 63, 64, 65, 127, 128, 129, 200] {
+            for (a, b) in [(0, len - 1), (len / 2, len / 2 + 1), (30, 33)] {
+                if b >= len {
+                    continue;
+                }
+
+                let mut haystack = vec![b'_'; len];
+                haystack[a] = b'a';
+                haystack[b] = b'b';
+                let haystack = String::from_utf8(haystack).unwrap();
+
+                assert_eq!(
+                    first(&haystack, "ab", false),
+                    Some((b, vec![a, b]))
+                );
+                assert_eq!(
+                    last(&haystack, "ab", false),
+                    Some((a, vec![b, a]))
+                );
+                assert_eq!(first(&haystack, "ba", false), None);
+                assert_eq!(last(&haystack, "ba", false), None);
+            }
+        }
+    }
+}
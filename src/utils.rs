@@ -4,9 +4,25 @@ use crate::normalize::{is_normalized, normalize};
 pub(crate) type CharEq = fn(char, char) -> bool;
 
 /// TODO: docs
+#[cfg(any(
+    feature = "damerau-levenshtein",
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "fzy",
+    feature = "levenshtein",
+    feature = "skim"
+))]
 const ASCII_CASE_MASK: u8 = 0b0010_0000;
 
 /// TODO: docs
+#[cfg(any(
+    feature = "damerau-levenshtein",
+    feature = "fzf-v1",
+    feature = "fzf-v2",
+    feature = "fzy",
+    feature = "levenshtein",
+    feature = "skim"
+))]
 #[inline(always)]
 pub fn ascii_letter_flip_case(ascii_letter: u8) -> u8 {
     debug_assert!(ascii_letter.is_ascii_alphabetic());
@@ -44,6 +60,7 @@ pub fn char_eq(is_case_sensitive: bool, normalize_candidate: bool) -> CharEq {
 }
 
 /// TODO: docs
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 #[inline(always)]
 fn leading_spaces(s: &str) -> usize {
     s.bytes().take_while(|&b| b == b' ').count()
@@ -60,7 +77,50 @@ fn normalize_candidate_char(query_char: char, candidate_char: char) -> char {
 }
 
 /// TODO: docs
+#[cfg(any(feature = "fzf-v1", feature = "fzf-v2", feature = "skim"))]
 #[inline(always)]
 pub fn strip_leading_spaces(s: &str) -> &str {
     &s[leading_spaces(s)..]
 }
+
+/// A tiny xorshift PRNG used by the randomized tests, so that we don't need
+/// to depend on `rand`.
+#[cfg(all(
+    test,
+    any(
+        feature = "damerau-levenshtein",
+        feature = "fzf-v1",
+        feature = "fzf-v2",
+        feature = "fzy",
+        feature = "levenshtein",
+        feature = "skim"
+    )
+))]
+pub(crate) struct Xorshift(u64);
+
+#[cfg(all(
+    test,
+    any(
+        feature = "damerau-levenshtein",
+        feature = "fzf-v1",
+        feature = "fzf-v2",
+        feature = "fzy",
+        feature = "levenshtein",
+        feature = "skim"
+    )
+))]
+impl Xorshift {
+    /// Creates a new generator from a non-zero seed.
+    pub(crate) fn new(seed: u64) -> Self {
+        debug_assert_ne!(seed, 0);
+        Self(seed)
+    }
+
+    /// Returns a pseudo-random number in `0..max`.
+    pub(crate) fn next(&mut self, max: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % max as u64) as usize
+    }
+}
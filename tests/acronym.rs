@@ -0,0 +1,148 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+mod common;
+
+use common::{assert_no_match, ranges};
+use norm::fzf::Acronym;
+use norm::Metric;
+
+#[test]
+fn acronym_camel_case() {
+    let mut acronym = Acronym::new();
+
+    let distance = acronym.distance("gcu", "getCurrentUser").unwrap();
+
+    assert!(distance.is_acronym());
+
+    assert_eq!(distance.unmatched_words(), 0);
+
+    assert_eq!(
+        ranges(&mut acronym, "gcu", "getCurrentUser"),
+        [0..1, 3..4, 10..11]
+    );
+}
+
+#[test]
+fn acronym_snake_case() {
+    let mut acronym = Acronym::new();
+
+    assert_eq!(
+        ranges(&mut acronym, "hrp", "http_request_parser"),
+        [0..1, 5..6, 13..14]
+    );
+}
+
+#[test]
+fn acronym_word_boundaries() {
+    let mut acronym = Acronym::new();
+
+    // "HTTPServer" is split into "HTTP" and "Server".
+    assert_eq!(ranges(&mut acronym, "hs", "HTTPServer"), [0..1, 4..5]);
+
+    // Numbers start a new word.
+    assert_eq!(ranges(&mut acronym, "v2", "v2beta"), [0..2]);
+
+    assert_eq!(ranges(&mut acronym, "uv", "utf8 value"), [0..1, 5..6]);
+
+    // Delimiters and whitespace separate words.
+    assert_eq!(
+        ranges(&mut acronym, "sml", "src/main lib"),
+        [0..1, 4..5, 9..10]
+    );
+}
+
+#[test]
+fn acronym_numbers() {
+    let mut acronym = Acronym::new();
+
+    // A number starts a new word, but the letters following it don't, so
+    // "base64Encode" is split into "base" and "64Encode".
+    assert_eq!(ranges(&mut acronym, "b6", "base64Encode"), [0..1, 4..5]);
+
+    assert_eq!(ranges(&mut acronym, "b64e", "base64Encode"), [0..1, 4..7]);
+
+    assert_no_match(&mut acronym, "be", "base64Encode");
+}
+
+#[test]
+fn acronym_delimiter_runs() {
+    let mut acronym = Acronym::new();
+
+    // Leading, trailing and repeated delimiters don't create empty words.
+    let distance = acronym.distance("i", "__init__").unwrap();
+
+    assert_eq!(distance.unmatched_words(), 0);
+
+    assert_eq!(ranges(&mut acronym, "i", "__init__"), [2..3]);
+
+    assert_eq!(ranges(&mut acronym, "fb", "foo::bar"), [0..1, 5..6]);
+
+    assert_no_match(&mut acronym, "f", "__");
+}
+
+#[test]
+fn acronym_unicode_word_boundaries() {
+    let mut acronym = Acronym::new();
+
+    // "é" is 2 bytes long, and a lowercase non-ASCII letter followed by an
+    // uppercase one is a camelCase boundary.
+    assert_eq!(ranges(&mut acronym, "éc", "étéChaud"), [0..2, 5..6]);
+
+    // Emojis are not word characters, so they separate words.
+    assert_eq!(ranges(&mut acronym, "ab", "a😀b"), [0..1, 5..6]);
+}
+
+#[test]
+fn acronym_word_prefixes() {
+    let mut acronym = Acronym::new();
+
+    let distance = acronym.distance("getcu", "getCurrentUser").unwrap();
+
+    assert!(!distance.is_acronym());
+
+    assert_eq!(distance.extra_chars(), 2);
+
+    assert_eq!(
+        ranges(&mut acronym, "getcu", "getCurrentUser"),
+        [0..4, 10..11]
+    );
+
+    // Characters in the middle of a word can't be matched.
+    assert_no_match(&mut acronym, "gu", "getcurrent");
+}
+
+#[test]
+fn acronym_order() {
+    let mut acronym = Acronym::new();
+
+    assert_no_match(&mut acronym, "ucg", "getCurrentUser");
+}
+
+#[test]
+fn acronym_ranking() {
+    let mut acronym = Acronym::new();
+
+    let exact = acronym.distance("gcu", "getCurrentUser").unwrap();
+
+    let longer = acronym.distance("gcu", "getCurrentUserName").unwrap();
+
+    let skipped = acronym.distance("gcu", "getCachedCurrentUser").unwrap();
+
+    let prefix = acronym.distance("gcu", "getCursor").unwrap();
+
+    assert_eq!(skipped.skipped_words(), 1);
+
+    assert!(exact < longer);
+
+    assert!(longer < skipped);
+
+    // Matching only initials always wins.
+    assert!(skipped < prefix);
+}
+
+#[test]
+fn acronym_prefers_earlier_words() {
+    let mut acronym = Acronym::new();
+
+    assert_eq!(ranges(&mut acronym, "ab", "a_a_b"), [0..1, 4..5]);
+}
@@ -0,0 +1,426 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use core::cmp::Reverse;
+
+use norm::combinators::Combination;
+use norm::fzf::{Acronym, FzfParser, FzfV2};
+use norm::fzy::Fzy;
+use norm::hamming::Hamming;
+use norm::levenshtein::Levenshtein;
+use norm::phonetic::Phonetic;
+use norm::Metric;
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[test]
+fn combinators_then_breaks_ties() {
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("foo");
+
+    let mut metric = FzfV2::new().then(Levenshtein::new());
+
+    let short = metric.distance((query, "foo"), "foo_bar").unwrap();
+
+    let long = metric.distance((query, "foo"), "foo_bar_baz").unwrap();
+
+    assert_eq!(short.0, long.0);
+
+    assert!(short < long);
+}
+
+#[test]
+fn combinators_then_first_metric_wins() {
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("abc");
+
+    let mut metric = FzfV2::new().then(Levenshtein::new());
+
+    let exact = metric.distance((query, "abc"), "xx_abc").unwrap();
+
+    let scattered = metric.distance((query, "abc"), "axbxc").unwrap();
+
+    // The Levenshtein distance to "abc" is smaller for "axbxc", but the
+    // second metric is only used to break ties.
+    assert!(exact.1 > scattered.1);
+
+    assert!(exact < scattered);
+}
+
+#[test]
+fn combinators_then_ranges_of_first_metric() {
+    let mut parser = FzfParser::new();
+
+    let mut metric = FzfV2::new().then(Hamming::new());
+
+    let mut ranges = Vec::new();
+
+    let _ = metric
+        .distance_and_ranges(
+            (parser.parse("fb"), "foo"),
+            "foo_bar",
+            &mut ranges,
+        )
+        .unwrap();
+
+    assert_eq!(ranges, [0..1, 4..5]);
+}
+
+#[test]
+fn combinators_then_both_must_match() {
+    let mut parser = FzfParser::new();
+
+    let mut metric = FzfV2::new().then(Hamming::new());
+
+    let mut ranges = vec![0..1];
+
+    // Fzf matches the candidate but Hamming doesn't since the candidate is
+    // shorter than its query, so the ranges must be left untouched.
+    assert!(metric
+        .distance_and_ranges(
+            (parser.parse("ab"), "abcdef"),
+            "xab",
+            &mut ranges,
+        )
+        .is_none());
+
+    assert_eq!(ranges, [0..1]);
+
+    assert!(metric.distance((parser.parse("z"), "ab"), "xab").is_none());
+}
+
+#[test]
+fn combinators_then_second_mut() {
+    let mut metric = Hamming::new().then(Hamming::new());
+
+    metric.second_mut().set_max_mismatches(Some(0));
+
+    assert!(metric.distance(("abc", "abc"), "abd").is_none());
+
+    assert!(metric.distance(("abc", "abd"), "abd").is_some());
+}
+
+#[test]
+fn combinators_then_distance_within() {
+    let mut parser = FzfParser::new();
+
+    let query = (parser.parse("foo"), "foo");
+
+    let mut metric = FzfV2::new().then(Levenshtein::new());
+
+    let cutoff = metric.distance(query, "foo_bar").unwrap();
+
+    let candidates = ["foo", "foo_bar", "foo_bar_baz", "f_o_o", "bar"];
+
+    for candidate in candidates {
+        assert_eq!(
+            metric.distance_within(query, candidate, &cutoff),
+            metric
+                .distance(query, candidate)
+                .filter(|distance| *distance <= cutoff),
+            "{candidate:?}"
+        );
+    }
+
+    // Same fzf distance, but a higher Levenshtein distance.
+    assert!(metric.distance_within(query, "foo_bar_baz", &cutoff).is_none());
+
+    // A better fzf distance, so the Levenshtein distance doesn't matter.
+    let cutoff = metric.distance(query, "f_o_o").unwrap();
+
+    assert!(metric.distance_within(query, "foo_bar_baz", &cutoff).is_some());
+}
+
+#[test]
+fn combinators_filter() {
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("git");
+
+    let mut fzf = FzfV2::new().filter(|path| !path.starts_with('.'));
+
+    assert!(fzf.distance(query, "src/git.rs").is_some());
+
+    assert!(fzf.distance(query, ".gitignore").is_none());
+
+    let mut ranges = Vec::new();
+
+    assert!(fzf
+        .distance_and_ranges(query, ".gitignore", &mut ranges)
+        .is_none());
+
+    assert!(ranges.is_empty());
+
+    let _ = fzf.distance_and_ranges(query, "src/git.rs", &mut ranges);
+
+    assert_eq!(ranges, [4..7]);
+}
+
+#[test]
+fn combinators_map_candidate_shifts_ranges() {
+    let mut parser = FzfParser::new();
+
+    let mut fzf = FzfV2::new().map_candidate(basename);
+
+    let mut ranges = Vec::new();
+
+    let _ = fzf
+        .distance_and_ranges(parser.parse("mn"), "src/main.rs", &mut ranges)
+        .unwrap();
+
+    assert_eq!(ranges, [4..5, 7..8]);
+
+    ranges.clear();
+
+    let _ = fzf
+        .distance_and_ranges(parser.parse("mars"), "src/main.rs", &mut ranges)
+        .unwrap();
+
+    assert_eq!(ranges, [4..6, 9..11]);
+
+    assert!(fzf.distance(parser.parse("src"), "src/main.rs").is_none());
+}
+
+#[test]
+fn combinators_map_candidate_unicode() {
+    let mut lev = Levenshtein::new().map_candidate(basename);
+
+    let mut ranges = Vec::new();
+
+    let _ =
+        lev.distance_and_ranges("café", "ñandú/café", &mut ranges).unwrap();
+
+    assert_eq!(ranges, [8..13]);
+}
+
+#[test]
+fn combinators_map_candidate_not_substring() {
+    let mut lev = Levenshtein::new().map_candidate(|_| "foo");
+
+    let mut ranges = vec![0..1];
+
+    let distance = lev.distance_and_ranges("foo", "bar", &mut ranges);
+
+    // The distance is the same, but the ranges can't be mapped back to the
+    // candidate.
+    assert_eq!(distance, lev.distance("foo", "bar"));
+
+    assert_eq!(ranges, [0..1]);
+}
+
+#[test]
+fn combinators_map_candidate_literal_fallback() {
+    let mut lev = Levenshtein::new()
+        .map_candidate(|c| c.rsplit_once('/').map_or("", |(_, base)| base));
+
+    let mut ranges = Vec::new();
+
+    let distance = lev.distance_and_ranges("", "nodir", &mut ranges);
+
+    assert_eq!(distance, lev.distance("", "nodir"));
+
+    assert!(distance.is_some());
+
+    assert!(ranges.is_empty());
+}
+
+#[test]
+fn combinators_reversed() {
+    let mut lev = Levenshtein::new().reversed();
+
+    let far = lev.distance("abc", "xyz").unwrap();
+
+    let close = lev.distance("abc", "abd").unwrap();
+
+    assert!(far < close);
+
+    assert_eq!(close.0.edits(), 1);
+
+    let mut ranges = Vec::new();
+
+    let Reverse(_) =
+        lev.distance_and_ranges("abc", "abd", &mut ranges).unwrap();
+
+    assert_eq!(ranges, [0..2]);
+}
+
+#[test]
+fn combinators_weighted_tie_break() {
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("lib");
+
+    let weight = |path: &str| if path.starts_with("src") { 1.0 } else { 0.0 };
+
+    let mut fzf = FzfV2::new().weighted(weight);
+
+    let src = fzf.distance(query, "src/lib.rs").unwrap();
+
+    let doc = fzf.distance(query, "doc/lib.rs").unwrap();
+
+    assert_eq!(src.inner(), doc.inner());
+
+    assert!(src < doc);
+
+    assert_eq!(src.weight(), 1.0);
+
+    assert_eq!(src.combined(), None);
+
+    // The weight can't make up for a worse match.
+    let scattered = fzf.distance(query, "src/l_i_b.rs").unwrap();
+
+    assert!(doc < scattered);
+}
+
+#[test]
+fn combinators_weighted_add() {
+    let weight = |candidate: &str| if candidate == "abd" { 0.75 } else { 0.0 };
+
+    let mut lev = Levenshtein::new().weighted(weight);
+
+    lev.set_combination(Combination::Add);
+
+    let exact = lev.distance("abc", "abc").unwrap();
+
+    let boosted = lev.distance("abc", "abd").unwrap();
+
+    assert_eq!(exact.combined(), Some(1.0));
+
+    assert_eq!(boosted.combined(), Some(1.25));
+
+    assert!(boosted < exact);
+}
+
+#[test]
+fn combinators_weighted_multiply() {
+    let weight = |candidate: &str| if candidate == "abd" { 1.5 } else { 1.0 };
+
+    let mut lev = Levenshtein::new().weighted(weight);
+
+    lev.set_combination(Combination::Multiply);
+
+    let exact = lev.distance("abc", "abc").unwrap();
+
+    let boosted = lev.distance("abc", "abd").unwrap();
+
+    // 1.0 * 1.0 vs 0.5 * 1.5.
+    assert!(exact < boosted);
+
+    let far = lev.distance("abc", "xyz").unwrap();
+
+    assert!(boosted < far);
+}
+
+#[test]
+fn combinators_weighted_acronym() {
+    let weight = |candidate: &str| {
+        if candidate == "getCurrentUserName" {
+            0.5
+        } else {
+            0.0
+        }
+    };
+
+    let mut acronym = Acronym::new().weighted(weight);
+
+    let exact = acronym.distance("gcu", "getCurrentUser").unwrap();
+
+    let longer = acronym.distance("gcu", "getCurrentUserName").unwrap();
+
+    let prefix = acronym.distance("gcu", "getCursor").unwrap();
+
+    assert!(exact < longer);
+
+    assert!(longer < prefix);
+
+    acronym.set_combination(Combination::Add);
+
+    let exact = acronym.distance("gcu", "getCurrentUser").unwrap();
+
+    let longer = acronym.distance("gcu", "getCurrentUserName").unwrap();
+
+    let prefix = acronym.distance("gcu", "getCursor").unwrap();
+
+    assert_eq!(exact.combined(), Some(1.0));
+
+    assert!(longer < exact);
+
+    assert!(exact < prefix);
+}
+
+#[test]
+fn combinators_weighted_phonetic() {
+    let weight =
+        |candidate: &str| if candidate == "Smyth" { 0.5 } else { 0.0 };
+
+    let mut phonetic = Phonetic::new().weighted(weight);
+
+    let exact = phonetic.distance("Smith", "Smith").unwrap();
+
+    let inexact = phonetic.distance("Smith", "Smyth").unwrap();
+
+    let extra_word = phonetic.distance("Smith", "John Smith").unwrap();
+
+    assert!(exact < inexact);
+
+    assert!(inexact < extra_word);
+
+    phonetic.set_combination(Combination::Add);
+
+    let exact = phonetic.distance("Smith", "Smith").unwrap();
+
+    let inexact = phonetic.distance("Smith", "Smyth").unwrap();
+
+    let extra_word = phonetic.distance("Smith", "John Smith").unwrap();
+
+    assert_eq!(exact.combined(), Some(1.0));
+
+    assert!(inexact < exact);
+
+    assert!(exact < extra_word);
+}
+
+#[test]
+fn combinators_weighted_multiply_infinite_relevance() {
+    let weight = |candidate: &str| if candidate == "abc" { 0.0 } else { 1.0 };
+
+    let mut fzy = Fzy::new().weighted(weight);
+
+    fzy.set_combination(Combination::Multiply);
+
+    // Exact matches have an infinite relevance, which would give NaN when
+    // multiplied by a zero weight.
+    let exact = fzy.distance("abc", "abc").unwrap();
+
+    let fuzzy = fzy.distance("abc", "a_b_c").unwrap();
+
+    assert_eq!(exact.combined(), Some(0.0));
+
+    assert!(fuzzy < exact);
+}
+
+#[test]
+fn combinators_weighted_ranges() {
+    let mut parser = FzfParser::new();
+
+    let mut fzf = FzfV2::new().weighted(|_| 1.0);
+
+    let mut ranges = Vec::new();
+
+    let _ = fzf
+        .distance_and_ranges(parser.parse("lib"), "src/lib.rs", &mut ranges)
+        .unwrap();
+
+    assert_eq!(ranges, [4..7]);
+
+    ranges.clear();
+
+    assert!(fzf
+        .distance_and_ranges(parser.parse("xyz"), "src/lib.rs", &mut ranges)
+        .is_none());
+
+    assert!(ranges.is_empty());
+}
@@ -0,0 +1,37 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use core::ops::Range;
+
+use norm::Metric;
+
+/// Returns the byte ranges of the candidate that matched the query, panicking
+/// if the candidate is not a match.
+pub fn ranges<M>(
+    metric: &mut M,
+    query: &str,
+    candidate: &str,
+) -> Vec<Range<usize>>
+where
+    M: for<'a> Metric<Query<'a> = &'a str>,
+{
+    let mut ranges = Vec::new();
+    metric.distance_and_ranges(query, candidate, &mut ranges).unwrap();
+    ranges
+}
+
+/// Asserts that the candidate doesn't match the query, and that looking for
+/// its ranges leaves the buffer untouched.
+pub fn assert_no_match<M>(metric: &mut M, query: &str, candidate: &str)
+where
+    M: for<'a> Metric<Query<'a> = &'a str>,
+{
+    assert!(metric.distance(query, candidate).is_none());
+
+    let mut ranges = vec![usize::MAX..usize::MAX];
+
+    assert!(metric
+        .distance_and_ranges(query, candidate, &mut ranges)
+        .is_none());
+
+    assert_eq!(ranges, [usize::MAX..usize::MAX]);
+}
@@ -0,0 +1,215 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use norm::levenshtein::DamerauLevenshtein;
+use norm::{CaseSensitivity, Metric};
+
+fn edits_and_ranges(
+    osa: &mut DamerauLevenshtein,
+    query: &str,
+    candidate: &str,
+) -> Option<(usize, Vec<core::ops::Range<usize>>)> {
+    let mut ranges = Vec::new();
+    let distance = osa.distance_and_ranges(query, candidate, &mut ranges)?;
+    Some((distance.edits(), ranges))
+}
+
+#[test]
+fn damerau_levenshtein_empty_query() {
+    let mut osa = DamerauLevenshtein::new();
+
+    assert_eq!(edits_and_ranges(&mut osa, "", ""), Some((0, vec![])));
+
+    assert_eq!(edits_and_ranges(&mut osa, "", "foo"), Some((3, vec![])));
+}
+
+#[test]
+fn damerau_levenshtein_empty_candidate() {
+    let mut osa = DamerauLevenshtein::new();
+
+    assert_eq!(edits_and_ranges(&mut osa, "foo", ""), Some((3, vec![])));
+}
+
+#[test]
+fn damerau_levenshtein_equal() {
+    let mut osa = DamerauLevenshtein::new();
+
+    assert_eq!(
+        edits_and_ranges(&mut osa, "foo bar", "foo bar"),
+        Some((0, vec![0..7]))
+    );
+}
+
+#[test]
+fn damerau_levenshtein_transposition() {
+    let mut osa = DamerauLevenshtein::new();
+
+    assert_eq!(
+        edits_and_ranges(&mut osa, "teh", "the"),
+        Some((1, vec![0..1]))
+    );
+
+    assert_eq!(
+        edits_and_ranges(&mut osa, "recieve", "receive"),
+        Some((1, vec![0..3, 5..7]))
+    );
+
+    // Transposition followed by an insertion.
+    assert_eq!(osa.distance("form", "froms").unwrap().edits(), 2);
+}
+
+#[test]
+fn damerau_levenshtein_restricted() {
+    let mut osa = DamerauLevenshtein::new();
+
+    // The unrestricted Damerau-Levenshtein distance would be 2.
+    assert_eq!(osa.distance("ca", "abc").unwrap().edits(), 3);
+}
+
+#[test]
+fn damerau_levenshtein_kitten_sitting() {
+    let mut osa = DamerauLevenshtein::new();
+
+    assert_eq!(
+        edits_and_ranges(&mut osa, "kitten", "sitting"),
+        Some((3, vec![1..4, 5..6]))
+    );
+}
+
+#[test]
+fn damerau_levenshtein_unicode_ranges() {
+    let mut osa = DamerauLevenshtein::new();
+
+    // "ö" is 2 bytes long.
+    assert_eq!(
+        edits_and_ranges(&mut osa, "föoa", "fööa"),
+        Some((1, vec![0..3, 5..6]))
+    );
+
+    assert_eq!(
+        edits_and_ranges(&mut osa, "aöb", "öab"),
+        Some((1, vec![3..4]))
+    );
+}
+
+#[test]
+fn damerau_levenshtein_case_sensitivity() {
+    let mut osa = DamerauLevenshtein::new();
+
+    assert_eq!(osa.distance("teh", "THE").unwrap().edits(), 1);
+
+    // Smart case: the query has an uppercase letter.
+    assert_eq!(osa.distance("Teh", "the").unwrap().edits(), 2);
+
+    osa.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    assert_eq!(osa.distance("Teh", "tHE").unwrap().edits(), 1);
+
+    osa.set_case_sensitivity(CaseSensitivity::Sensitive);
+
+    assert_eq!(osa.distance("teh", "THE").unwrap().edits(), 3);
+}
+
+#[test]
+fn damerau_levenshtein_candidate_normalization() {
+    let mut osa = DamerauLevenshtein::new();
+
+    assert_eq!(osa.distance("caef", "café").unwrap().edits(), 2);
+
+    osa.set_candidate_normalization(true);
+
+    assert_eq!(
+        edits_and_ranges(&mut osa, "caef", "café"),
+        Some((1, vec![0..2]))
+    );
+
+    // The query is never normalized.
+    assert_eq!(osa.distance("caéf", "cafe").unwrap().edits(), 2);
+}
+
+#[test]
+fn damerau_levenshtein_max_distance() {
+    let mut osa = DamerauLevenshtein::new();
+
+    osa.set_max_distance(Some(1));
+
+    assert!(osa.distance("abcd", "badc").is_none());
+
+    assert!(osa.distance("abcd", "bacd").is_some());
+
+    let long = "ab".repeat(50);
+
+    let swapped = format!("ba{}", &long[2..]);
+
+    assert_eq!(osa.distance(&long, &swapped).unwrap().edits(), 1);
+
+    assert!(osa.distance(&long, &"ba".repeat(50)).is_none());
+}
+
+#[test]
+fn damerau_levenshtein_long_query() {
+    let mut osa = DamerauLevenshtein::new();
+
+    let query =
+        "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&\
+         ()*+";
+
+    assert_eq!(query.len(), 71);
+
+    let candidate = format!("ba{}", &query[2..]);
+
+    assert_eq!(
+        edits_and_ranges(&mut osa, query, &candidate),
+        Some((1, vec![2..71]))
+    );
+}
+
+#[test]
+fn damerau_levenshtein_distance_within() {
+    let mut osa = DamerauLevenshtein::new();
+
+    let long = "abcdefghij".repeat(10);
+
+    let mut edited = long.clone();
+
+    edited.replace_range(40..43, "xyz");
+
+    let swapped = format!("ba{}", &long[2..]);
+
+    let candidates =
+        ["kitten", "sitting", "mitten", "", &long, &edited, &swapped];
+
+    for query in candidates {
+        let cutoffs = candidates
+            .iter()
+            .map(|candidate| osa.distance(query, candidate).unwrap())
+            .collect::<Vec<_>>();
+
+        for cutoff in &cutoffs {
+            for candidate in candidates {
+                assert_eq!(
+                    osa.distance_within(query, candidate, cutoff),
+                    osa.distance(query, candidate)
+                        .filter(|distance| distance <= cutoff),
+                    "{query:?} vs {candidate:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn damerau_levenshtein_distance_within_keeps_max_distance() {
+    let mut osa = DamerauLevenshtein::new();
+
+    let cutoff = osa.distance("kitten", "").unwrap();
+
+    osa.set_max_distance(Some(2));
+
+    // The lower of the two limits wins.
+    assert!(osa.distance_within("kitten", "sitting", &cutoff).is_none());
+
+    assert!(osa.distance_within("kitten", "mitten", &cutoff).is_some());
+
+    // The maximum distance is restored after the call.
+    assert!(osa.distance("kitten", "sitting").is_none());
+}
@@ -0,0 +1,149 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use norm::fzf::{FzfParser, FzfV1, FzfV2, Skim};
+use norm::hamming::{Hamming, HammingDistance};
+use norm::levenshtein::{Levenshtein, LevenshteinDistance};
+use norm::{CaseSensitivity, DynMetric, Dynamic};
+
+fn fzf(name: &str) -> Box<dyn DynMetric> {
+    match name {
+        "v1" => Box::new(Dynamic::with_parser(FzfV1::new(), FzfParser::new())),
+        "v2" => Box::new(Dynamic::with_parser(FzfV2::new(), FzfParser::new())),
+        "skim" => {
+            Box::new(Dynamic::with_parser(Skim::new(), FzfParser::new()))
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn dyn_metric_switch_at_runtime() {
+    for name in ["v1", "v2", "skim"] {
+        let mut metric = fzf(name);
+
+        let mut ranges = Vec::new();
+
+        let distance =
+            metric.distance_and_ranges("foo", "a_foo_b", &mut ranges);
+
+        assert!(distance.is_some(), "{name}");
+
+        assert_eq!(ranges, [2..5], "{name}");
+
+        assert!(metric.distance("xyz", "a_foo_b").is_none(), "{name}");
+    }
+}
+
+#[test]
+fn dyn_metric_parses_extended_query() {
+    let mut metric = fzf("v2");
+
+    assert!(metric.distance("^foo bar$", "foo_baz_bar").is_some());
+
+    assert!(metric.distance("^foo bar$", "a_foo_bar").is_none());
+
+    assert!(metric.distance("!baz", "foo_bar").is_some());
+
+    assert!(metric.distance("!baz", "foo_baz").is_none());
+}
+
+#[test]
+fn dyn_metric_str_query() {
+    let mut metrics: Vec<Box<dyn DynMetric>> = vec![
+        Box::new(Dynamic::new(Levenshtein::new())),
+        Box::new(Dynamic::new(Hamming::new())),
+    ];
+
+    for metric in &mut metrics {
+        let close = metric.distance("abc", "abd").unwrap();
+
+        let far = metric.distance("abc", "xyz").unwrap();
+
+        assert!(close < far);
+    }
+}
+
+#[test]
+fn dyn_metric_downcast() {
+    let mut metric = Dynamic::new(Hamming::new());
+
+    let distance = metric.distance("abc", "xbd").unwrap();
+
+    let hamming = distance.downcast_ref::<HammingDistance>().unwrap();
+
+    assert_eq!(hamming.mismatches(), 2);
+
+    assert!(distance.downcast_ref::<u32>().is_none());
+}
+
+#[test]
+fn dyn_metric_metric_mut() {
+    let mut metric = Dynamic::new(Levenshtein::new());
+
+    assert!(metric.distance("abc", "ABC").is_some());
+
+    metric.metric_mut().set_case_sensitivity(CaseSensitivity::Sensitive);
+
+    let insensitive = Dynamic::new(Levenshtein::new()).distance("abc", "ABC");
+
+    assert!(metric.distance("abc", "ABC") > insensitive);
+}
+
+#[test]
+fn dyn_metric_distance_within() {
+    let candidates = ["foo", "foo_bar", "f_o_o", "xfxoxo", "bar"];
+
+    for name in ["v1", "v2", "skim"] {
+        let mut metric = fzf(name);
+
+        let cutoff = metric.distance("foo", "foo_bar").unwrap();
+
+        for candidate in candidates {
+            assert_eq!(
+                metric.distance_within("foo", candidate, &cutoff),
+                metric
+                    .distance("foo", candidate)
+                    .filter(|distance| *distance <= cutoff),
+                "{name}: {candidate:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn dyn_metric_compare_different_metrics() {
+    let lev = Dynamic::new(Levenshtein::new()).distance("abc", "abc").unwrap();
+
+    let ham = Dynamic::new(Hamming::new()).distance("abc", "abc").unwrap();
+
+    assert_ne!(lev, ham);
+
+    assert_eq!(lev.cmp(&ham), ham.cmp(&lev).reverse());
+
+    let mut distances = vec![ham.clone(), lev.clone(), ham.clone()];
+
+    distances.sort();
+
+    // Distances of the same type end up next to each other.
+    assert_eq!(distances[1], ham);
+
+    distances.dedup();
+
+    assert_eq!(distances.len(), 2);
+}
+
+#[test]
+fn dyn_metric_clone_distance() {
+    let distance = Dynamic::new(Levenshtein::new())
+        .distance("kitten", "sitting")
+        .unwrap();
+
+    let cloned = distance.clone();
+
+    assert_eq!(cloned, distance);
+
+    assert_eq!(
+        cloned.downcast_ref::<LevenshteinDistance>().map(|d| d.edits()),
+        Some(3)
+    );
+}
@@ -0,0 +1,72 @@
+use norm::Frecency;
+
+const HOUR: u64 = 60 * 60;
+
+const DAY: u64 = 24 * HOUR;
+
+const WEEK: u64 = 7 * DAY;
+
+#[test]
+fn frecency_new() {
+    let frecency = Frecency::new(100);
+
+    assert_eq!(frecency.rank(), 1.0);
+
+    assert_eq!(frecency.last_access(), 100);
+}
+
+#[test]
+fn frecency_decay() {
+    let frecency = Frecency::from_parts(2.0, 0);
+
+    assert_eq!(frecency.score(0), 8.0);
+
+    assert_eq!(frecency.score(HOUR - 1), 8.0);
+
+    assert_eq!(frecency.score(HOUR), 4.0);
+
+    assert_eq!(frecency.score(DAY), 1.0);
+
+    assert_eq!(frecency.score(WEEK), 0.5);
+
+    assert_eq!(frecency.score(100 * WEEK), 0.5);
+}
+
+#[test]
+fn frecency_access() {
+    let mut frecency = Frecency::new(0);
+
+    frecency.access(2 * DAY);
+
+    assert_eq!(frecency.rank(), 2.0);
+
+    assert_eq!(frecency.last_access(), 2 * DAY);
+
+    assert_eq!(frecency.score(2 * DAY), 8.0);
+
+    // Accesses recorded out of order don't move the last access back.
+    frecency.access(DAY);
+
+    assert_eq!(frecency.last_access(), 2 * DAY);
+
+    assert_eq!(frecency.rank(), 3.0);
+}
+
+#[test]
+fn frecency_clock_skew() {
+    let frecency = Frecency::new(DAY);
+
+    // A timestamp before the last access counts as a recent access.
+    assert_eq!(frecency.score(0), 4.0);
+}
+
+#[test]
+fn frecency_age() {
+    let mut frecency = Frecency::from_parts(10.0, 0);
+
+    frecency.age(0.5);
+
+    assert_eq!(frecency.rank(), 5.0);
+
+    assert_eq!(frecency.score(0), 20.0);
+}
@@ -1,6 +1,6 @@
 #![allow(clippy::single_range_in_vec_init)]
 
-use norm::fzf::{bonus, penalty};
+use norm::fzf::{bonus, penalty, FzfParser};
 use norm::CaseSensitivity;
 use CaseSensitivity::*;
 
@@ -377,6 +377,29 @@ pub fn upstream_exact_7<F: Fzf>() {
     assert_eq!(m.matched_ranges, [1..3]);
 }
 
+pub fn exact_restart_bonus_1<F: Fzf>() {
+    let (_, m) = fzf::<F>(Insensitive, "'A", "b_b-1AaaAA/");
+
+    let m = m.unwrap();
+
+    // After a match, the search restarts from the next character but keeps
+    // the class of the character before the previous match, so all the `a`s
+    // in the run get the same bonus and the leftmost one wins.
+    assert_eq!(m.distance.into_score(), 16);
+
+    assert_eq!(m.matched_ranges, [5..6]);
+}
+
+pub fn exact_restart_bonus_2<F: Fzf>() {
+    let (_, m) = fzf::<F>(Insensitive, "'A xa", "1B1aaAxa/");
+
+    let m = m.unwrap();
+
+    assert_eq!(m.distance.into_score(), 52);
+
+    assert_eq!(m.matched_ranges, [3..4, 6..8]);
+}
+
 pub fn upstream_prefix_1<F: Fzf>() {
     let (_, m) = fzf::<F>(Sensitive, "^Foo", "fooBarbaz");
     assert!(m.is_none());
@@ -532,6 +555,178 @@ pub fn upstream_suffix_6<F: Fzf>() {
     assert_eq!(m.matched_ranges, [6..10]);
 }
 
+pub fn similarity_best_match<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("foo");
+
+    for candidate in ["foo", "foo_bar", "bar foo"] {
+        let distance = fzf.distance(query, candidate).unwrap();
+        assert_eq!(fzf.similarity(query, &distance), 1.0, "{candidate}");
+    }
+}
+
+pub fn similarity_follows_distance<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("fbr");
+
+    let mut similarities = ["f_b_r", "fooBar", "foo_bar", "fbr"]
+        .into_iter()
+        .map(|candidate| {
+            let distance = fzf.distance(query, candidate).unwrap();
+            (distance, fzf.similarity(query, &distance))
+        })
+        .collect::<Vec<_>>();
+
+    similarities.sort_by_key(|&(distance, _)| distance);
+
+    for window in similarities.windows(2) {
+        assert!(window[0].1 >= window[1].1);
+    }
+
+    for (_, similarity) in similarities {
+        assert!((0.0..=1.0).contains(&similarity));
+    }
+}
+
+pub fn similarity_extended<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("^foo bar | xyz !baz");
+
+    let exact = fzf.distance(query, "foo bar").unwrap();
+
+    assert_eq!(fzf.similarity(query, &exact), 1.0);
+
+    let partial = fzf.distance(query, "foo_qux_b_a_r").unwrap();
+
+    let similarity = fzf.similarity(query, &partial);
+
+    assert!(0.0 < similarity && similarity < 1.0);
+
+    // Inverse patterns don't contribute to the score.
+    let query = parser.parse("!baz");
+
+    let distance = fzf.distance(query, "foo").unwrap();
+
+    assert_eq!(fzf.similarity(query, &distance), 1.0);
+}
+
+pub fn similarity_single_inverse_fuzzy<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("!'B");
+
+    // The query can't score any candidate above zero, so a similarity of
+    // 1.0 must mean a score of zero.
+    for candidate in ["abAbB/a/", "abA/a/", "B", "b", ""] {
+        let Some(distance) = fzf.distance(query, candidate) else {
+            assert!(candidate.contains('B'), "{candidate:?}");
+            continue;
+        };
+
+        assert_eq!(distance.into_score(), 0, "{candidate:?}");
+
+        assert_eq!(fzf.similarity(query, &distance), 1.0, "{candidate:?}");
+    }
+}
+
+pub fn similarity_empty_query<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("");
+
+    let distance = fzf.distance(query, "foo").unwrap();
+
+    assert_eq!(fzf.similarity(query, &distance), 1.0);
+}
+
+pub fn distance_within_same_as_filtered_distance<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let candidates = [
+        "foo",
+        "foo_bar",
+        "fooBar",
+        "f_o_o",
+        "f__o__o__b__a__r",
+        "xfxxxoxxxoxxxbxxxaxxxr",
+        "src/foo/bar.rs",
+        "bar foo",
+        "qux",
+    ];
+
+    for query in ["f", "foo", "fbr", "fooBar", "^foo bar | qux !baz"] {
+        let query = parser.parse(query);
+
+        let cutoffs = candidates
+            .iter()
+            .filter_map(|candidate| fzf.distance(query, candidate))
+            .collect::<Vec<_>>();
+
+        for cutoff in &cutoffs {
+            for candidate in candidates {
+                assert_eq!(
+                    fzf.distance_within(query, candidate, cutoff),
+                    fzf.distance(query, candidate)
+                        .filter(|distance| distance <= cutoff),
+                    "{query:?} vs {candidate:?}"
+                );
+            }
+        }
+    }
+}
+
+pub fn distance_within_unreachable_cutoff<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let cutoff = fzf.distance(parser.parse("foobar"), "foobar").unwrap();
+
+    // Even a perfect match for a shorter query can't score as high.
+    let query = parser.parse("foo");
+
+    assert!(fzf.distance_within(query, "foo", &cutoff).is_none());
+
+    // The cutoff doesn't stick around after the call.
+    assert!(fzf.distance(query, "f_o_o").is_some());
+}
+
+pub fn distance_within_single_inverse_fuzzy<F: Fzf>() {
+    let mut fzf = F::default();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("!'B");
+
+    // A single inverse pattern excludes the candidates it matches..
+    assert!(fzf.distance(query, "abAbB/a/").is_none());
+
+    // ..and gives a score of zero to all the others.
+    let distance = fzf.distance(query, "abA/a/").unwrap();
+
+    assert_eq!(distance.into_score(), 0);
+
+    assert_eq!(
+        fzf.distance_within(query, "abA/a/", &distance),
+        Some(distance)
+    );
+}
+
 pub use utils::*;
 
 mod utils {
@@ -543,11 +738,12 @@ mod utils {
     }
 
     use norm::fzf::{FzfDistance, FzfParser, FzfQuery, FzfV1, FzfV2};
-    use norm::{CaseSensitivity, Metric};
+    use norm::{CaseSensitivity, Metric, Similarity};
 
     pub trait Fzf:
         Default
         + for<'a> Metric<Query<'a> = FzfQuery<'a>, Distance = FzfDistance>
+        + Similarity
     {
         fn set_case_sensitivity(
             &mut self,
@@ -46,6 +46,16 @@ fn fzf_v1_upstream_exact_7() {
     common::upstream_exact_7::<FzfV1>()
 }
 
+#[test]
+fn fzf_v1_exact_restart_bonus_1() {
+    common::exact_restart_bonus_1::<FzfV1>()
+}
+
+#[test]
+fn fzf_v1_exact_restart_bonus_2() {
+    common::exact_restart_bonus_2::<FzfV1>()
+}
+
 #[test]
 fn fzf_v1_upstream_fuzzy_1() {
     common::upstream_fuzzy_1::<FzfV1>();
@@ -216,6 +226,46 @@ fn fzf_v1_upstream_suffix_6() {
     common::upstream_suffix_6::<FzfV1>()
 }
 
+#[test]
+fn fzf_v1_similarity_best_match() {
+    common::similarity_best_match::<FzfV1>();
+}
+
+#[test]
+fn fzf_v1_similarity_follows_distance() {
+    common::similarity_follows_distance::<FzfV1>();
+}
+
+#[test]
+fn fzf_v1_similarity_extended() {
+    common::similarity_extended::<FzfV1>();
+}
+
+#[test]
+fn fzf_v1_similarity_empty_query() {
+    common::similarity_empty_query::<FzfV1>();
+}
+
+#[test]
+fn fzf_v1_similarity_single_inverse_fuzzy() {
+    common::similarity_single_inverse_fuzzy::<FzfV1>();
+}
+
+#[test]
+fn fzf_v1_distance_within_same_as_filtered_distance() {
+    common::distance_within_same_as_filtered_distance::<FzfV1>();
+}
+
+#[test]
+fn fzf_v1_distance_within_unreachable_cutoff() {
+    common::distance_within_unreachable_cutoff::<FzfV1>();
+}
+
+#[test]
+fn fzf_v1_distance_within_single_inverse_fuzzy() {
+    common::distance_within_single_inverse_fuzzy::<FzfV1>();
+}
+
 #[test]
 fn fzf_v1_score_1() {
     let mut fzf = FzfV1::new();
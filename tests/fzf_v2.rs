@@ -46,6 +46,16 @@ fn fzf_v2_upstream_exact_7() {
     common::upstream_exact_7::<FzfV2>()
 }
 
+#[test]
+fn fzf_v2_exact_restart_bonus_1() {
+    common::exact_restart_bonus_1::<FzfV2>()
+}
+
+#[test]
+fn fzf_v2_exact_restart_bonus_2() {
+    common::exact_restart_bonus_2::<FzfV2>()
+}
+
 #[test]
 fn fzf_v2_upstream_fuzzy_1() {
     common::upstream_fuzzy_1::<FzfV2>();
@@ -216,6 +226,46 @@ fn fzf_v2_upstream_suffix_6() {
     common::upstream_suffix_6::<FzfV2>()
 }
 
+#[test]
+fn fzf_v2_similarity_best_match() {
+    common::similarity_best_match::<FzfV2>();
+}
+
+#[test]
+fn fzf_v2_similarity_follows_distance() {
+    common::similarity_follows_distance::<FzfV2>();
+}
+
+#[test]
+fn fzf_v2_similarity_extended() {
+    common::similarity_extended::<FzfV2>();
+}
+
+#[test]
+fn fzf_v2_similarity_empty_query() {
+    common::similarity_empty_query::<FzfV2>();
+}
+
+#[test]
+fn fzf_v2_similarity_single_inverse_fuzzy() {
+    common::similarity_single_inverse_fuzzy::<FzfV2>();
+}
+
+#[test]
+fn fzf_v2_distance_within_same_as_filtered_distance() {
+    common::distance_within_same_as_filtered_distance::<FzfV2>();
+}
+
+#[test]
+fn fzf_v2_distance_within_unreachable_cutoff() {
+    common::distance_within_unreachable_cutoff::<FzfV2>();
+}
+
+#[test]
+fn fzf_v2_distance_within_single_inverse_fuzzy() {
+    common::distance_within_single_inverse_fuzzy::<FzfV2>();
+}
+
 #[test]
 fn fzf_v2_score_1() {
     let mut fzf = FzfV2::new();
@@ -332,3 +382,154 @@ fn fzf_v2_score_6() {
 
     assert!(distance.is_none());
 }
+
+#[test]
+fn fzf_v2_typos_disabled_by_default() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    assert!(fzf.distance(parser.parse("recieve"), "receive").is_none());
+}
+
+#[test]
+fn fzf_v2_typos_substitution() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    let mut ranges = Vec::new();
+
+    fzf.set_max_typos(1);
+
+    let _ = fzf
+        .distance_and_ranges(parser.parse("fxo"), "fao", &mut ranges)
+        .unwrap();
+
+    // The substituted "a" is not part of the ranges.
+    assert_eq!(ranges, [0..1, 2..3]);
+}
+
+#[test]
+fn fzf_v2_typos_insertion() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    let mut ranges = Vec::new();
+
+    fzf.set_max_typos(1);
+
+    let _ = fzf
+        .distance_and_ranges(parser.parse("fooo"), "xfoo", &mut ranges)
+        .unwrap();
+
+    assert_eq!(ranges, [1..4]);
+}
+
+#[test]
+fn fzf_v2_typos_transposition() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    let mut ranges = Vec::new();
+
+    fzf.set_max_typos(1);
+
+    let _ = fzf
+        .distance_and_ranges(parser.parse("abcd"), "acbd", &mut ranges)
+        .unwrap();
+
+    // Both transposed characters are left out of the ranges.
+    assert_eq!(ranges, [0..1, 3..4]);
+}
+
+#[test]
+fn fzf_v2_typos_budget() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("fxxbar");
+
+    fzf.set_max_typos(1);
+
+    assert!(fzf.distance(query, "foobar").is_none());
+
+    fzf.set_max_typos(2);
+
+    assert!(fzf.distance(query, "foobar").is_some());
+}
+
+#[test]
+fn fzf_v2_typos_penalty() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    fzf.set_max_typos(1);
+
+    let exact = fzf.distance(parser.parse("foo"), "foo").unwrap();
+
+    let typo = fzf.distance(parser.parse("fxo"), "fao").unwrap();
+
+    assert!(exact < typo);
+
+    let with_penalty = typo.into_score();
+
+    fzf.set_typo_penalty(0);
+
+    let without_penalty =
+        fzf.distance(parser.parse("fxo"), "fao").unwrap().into_score();
+
+    assert_eq!(without_penalty - with_penalty, bonus::MATCH);
+}
+
+#[test]
+fn fzf_v2_typos_single_char_pattern() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    fzf.set_max_typos(3);
+
+    // A pattern has to match at least one of its characters.
+    assert!(fzf.distance(parser.parse("x"), "foo").is_none());
+
+    assert!(fzf.distance(parser.parse("xy"), "foo").is_none());
+}
+
+#[test]
+fn fzf_v2_typos_per_pattern() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    fzf.set_max_typos(1);
+
+    // Every pattern gets its own budget.
+    assert!(fzf.distance(parser.parse("fxo bxr"), "foo bar").is_some());
+
+    // Exact and inverse patterns don't tolerate typos.
+    assert!(fzf.distance(parser.parse("'fxo"), "foo").is_none());
+
+    assert!(fzf.distance(parser.parse("!fxo"), "foo").is_some());
+}
+
+#[test]
+fn fzf_v2_typos_unicode() {
+    let mut fzf = FzfV2::new();
+
+    let mut parser = FzfParser::new();
+
+    let mut ranges = Vec::new();
+
+    fzf.set_max_typos(1);
+
+    let _ = fzf
+        .distance_and_ranges(parser.parse("çxé"), "çaé", &mut ranges)
+        .unwrap();
+
+    assert_eq!(ranges, [0..2, 3..5]);
+}
@@ -0,0 +1,222 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use norm::fzy::{score, Fzy};
+use norm::{CaseSensitivity, Metric};
+
+fn score(query: &str, candidate: &str) -> f64 {
+    Fzy::new().distance(query, candidate).unwrap().into_score()
+}
+
+fn ranges(query: &str, candidate: &str) -> Vec<core::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    Fzy::new().distance_and_ranges(query, candidate, &mut ranges).unwrap();
+    ranges
+}
+
+fn assert_score_eq(expected: f64, actual: f64) {
+    assert!(
+        (expected - actual).abs() < 1e-9 || expected == actual,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn fzy_upstream_has_match() {
+    let mut fzy = Fzy::new();
+
+    assert!(fzy.distance("a", "a").is_some());
+    assert!(fzy.distance("a", "ab").is_some());
+    assert!(fzy.distance("a", "ba").is_some());
+    assert!(fzy.distance("abc", "a|b|c").is_some());
+
+    // Non-empty query on an empty candidate.
+    assert!(fzy.distance("a", "").is_none());
+
+    assert!(fzy.distance("a", "b").is_none());
+    assert!(fzy.distance("ass", "tags").is_none());
+
+    // An empty query matches everything.
+    assert!(fzy.distance("", "").is_some());
+    assert!(fzy.distance("", "a").is_some());
+}
+
+#[test]
+fn fzy_upstream_relative_scores() {
+    // Prefer the start of words.
+    assert!(
+        score("amor", "app/models/order") > score("amor", "app/models/zrder")
+    );
+
+    // Prefer consecutive letters.
+    assert!(score("amo", "app/m/foo") < score("amo", "app/models/foo"));
+
+    // Prefer contiguous over a letter following a period.
+    assert!(score("gemfil", "Gemfile.lock") < score("gemfil", "Gemfile"));
+
+    // Prefer shorter matches.
+    assert!(score("abce", "abcdef") > score("abce", "abc de"));
+    assert!(score("abc", "    a b c ") > score("abc", "     a  b  c "));
+    assert!(score("abc", " a b c    ") > score("abc", " a  b  c "));
+
+    // Prefer shorter candidates.
+    assert!(score("test", "tests") > score("test", "testing"));
+
+    // Prefer matches at the start of the candidate.
+    assert!(score("test", "testing") > score("test", "/testing"));
+}
+
+#[test]
+fn fzy_upstream_score_exact_match() {
+    assert_eq!(score("abc", "abc"), score::MAX);
+    assert_eq!(score("aBc", "abC"), score::MAX);
+}
+
+#[test]
+fn fzy_upstream_score_empty_query() {
+    assert_eq!(score("", ""), score::MIN);
+    assert_eq!(score("", "a"), score::MIN);
+    assert_eq!(score("", "bb"), score::MIN);
+}
+
+#[test]
+fn fzy_upstream_score_gaps() {
+    use score::*;
+
+    assert_score_eq(GAP_LEADING, score("a", "*a"));
+    assert_score_eq(GAP_LEADING * 2.0, score("a", "*ba"));
+    assert_score_eq(GAP_LEADING * 2.0 + GAP_TRAILING, score("a", "**a*"));
+    assert_score_eq(
+        GAP_LEADING * 2.0 + GAP_TRAILING * 2.0,
+        score("a", "**a**"),
+    );
+    assert_score_eq(
+        GAP_LEADING * 2.0 + MATCH_CONSECUTIVE + GAP_TRAILING * 2.0,
+        score("aa", "**aa**"),
+    );
+    assert_score_eq(
+        GAP_LEADING + GAP_LEADING + GAP_INNER + GAP_TRAILING + GAP_TRAILING,
+        score("aa", "**a*a**"),
+    );
+}
+
+#[test]
+fn fzy_upstream_score_consecutive() {
+    use score::*;
+
+    assert_score_eq(GAP_LEADING + MATCH_CONSECUTIVE, score("aa", "*aa"));
+    assert_score_eq(
+        GAP_LEADING + MATCH_CONSECUTIVE * 2.0,
+        score("aaa", "*aaa"),
+    );
+    assert_score_eq(
+        GAP_LEADING + GAP_INNER + MATCH_CONSECUTIVE,
+        score("aaa", "*a*aa"),
+    );
+}
+
+#[test]
+fn fzy_upstream_score_slash() {
+    use score::*;
+
+    assert_score_eq(GAP_LEADING + MATCH_SLASH, score("a", "/a"));
+    assert_score_eq(GAP_LEADING * 2.0 + MATCH_SLASH, score("a", "*/a"));
+    assert_score_eq(
+        GAP_LEADING * 2.0 + MATCH_SLASH + MATCH_CONSECUTIVE,
+        score("aa", "a/aa"),
+    );
+}
+
+#[test]
+fn fzy_upstream_score_capital() {
+    use score::*;
+
+    assert_score_eq(GAP_LEADING + MATCH_CAPITAL, score("a", "bA"));
+    assert_score_eq(GAP_LEADING * 2.0 + MATCH_CAPITAL, score("a", "baA"));
+    assert_score_eq(
+        GAP_LEADING * 2.0 + MATCH_CAPITAL + MATCH_CONSECUTIVE,
+        score("aa", "baAa"),
+    );
+}
+
+#[test]
+fn fzy_upstream_score_dot() {
+    use score::*;
+
+    assert_score_eq(GAP_LEADING + MATCH_DOT, score("a", ".a"));
+    assert_score_eq(GAP_LEADING * 3.0 + MATCH_DOT, score("a", "*a.a"));
+    assert_score_eq(GAP_LEADING + GAP_INNER + MATCH_DOT, score("aa", "*a.a"));
+}
+
+#[test]
+fn fzy_upstream_score_long_candidate() {
+    let candidate = "a".repeat(1025);
+
+    let mut fzy = Fzy::new();
+
+    let mut ranges = Vec::new();
+
+    let distance =
+        fzy.distance_and_ranges("aa", &candidate, &mut ranges).unwrap();
+
+    assert_eq!(distance.into_score(), score::MIN);
+
+    assert!(ranges.is_empty());
+}
+
+#[test]
+fn fzy_upstream_positions() {
+    assert_eq!(ranges("amo", "app/models/foo"), [0..1, 4..6]);
+
+    assert_eq!(ranges("amor", "app/models/order"), [0..1, 4..5, 11..13]);
+
+    assert_eq!(ranges("as", "tags"), [1..2, 3..4]);
+
+    assert_eq!(ranges("as", "examples.txt"), [2..3, 7..8]);
+
+    assert_eq!(ranges("abc", "a/a/b/c/c"), [2..3, 4..5, 6..7]);
+
+    assert_eq!(ranges("foo", "foo"), [0..3]);
+}
+
+#[test]
+fn fzy_unicode_ranges() {
+    // "ö" is 2 bytes long.
+    assert_eq!(ranges("öb", "aö/öb"), [4..7]);
+
+    assert_eq!(ranges("a", "öa"), [2..3]);
+}
+
+#[test]
+fn fzy_case_sensitivity() {
+    let mut fzy = Fzy::new();
+
+    assert!(fzy.distance("FOO", "foo").is_some());
+
+    fzy.set_case_sensitivity(CaseSensitivity::Smart);
+
+    assert!(fzy.distance("foo", "FOO").is_some());
+
+    assert!(fzy.distance("Foo", "foo").is_none());
+
+    fzy.set_case_sensitivity(CaseSensitivity::Sensitive);
+
+    assert!(fzy.distance("foo", "FOO").is_none());
+}
+
+#[test]
+fn fzy_candidate_normalization() {
+    let mut fzy = Fzy::new();
+
+    assert!(fzy.distance("cafe", "Café").is_none());
+
+    fzy.set_candidate_normalization(true);
+
+    let mut ranges = Vec::new();
+
+    let distance =
+        fzy.distance_and_ranges("cafe", "Café", &mut ranges).unwrap();
+
+    assert_eq!(distance.into_score(), score::MAX);
+
+    assert_eq!(ranges, [0..5]);
+}
@@ -0,0 +1,106 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+mod common;
+
+use common::{assert_no_match, ranges};
+use norm::hamming::Hamming;
+use norm::Metric;
+
+fn mismatches(hamming: &mut Hamming, query: &str, candidate: &str) -> usize {
+    hamming.distance(query, candidate).unwrap().mismatches()
+}
+
+#[test]
+fn hamming_same_length() {
+    let mut hamming = Hamming::new();
+
+    assert_eq!(mismatches(&mut hamming, "karolin", "kathrin"), 3);
+
+    assert_eq!(mismatches(&mut hamming, "1011101", "1001001"), 2);
+
+    assert_eq!(mismatches(&mut hamming, "2173896", "2233796"), 3);
+
+    assert_eq!(mismatches(&mut hamming, "abc", "abc"), 0);
+
+    assert_eq!(ranges(&mut hamming, "karolin", "kathrin"), [0..7]);
+}
+
+#[test]
+fn hamming_sliding_window() {
+    let mut hamming = Hamming::new();
+
+    assert_eq!(mismatches(&mut hamming, "3f9a", "a1b2 3f8a c4d5"), 1);
+
+    assert_eq!(ranges(&mut hamming, "3f9a", "a1b2 3f8a c4d5"), [5..9]);
+}
+
+#[test]
+fn hamming_leftmost_best_window() {
+    let mut hamming = Hamming::new();
+
+    // Both "abd" and "xbc" are one mismatch away from the query.
+    assert_eq!(ranges(&mut hamming, "abc", "abd xbc"), [0..3]);
+
+    // An exact match wins over an earlier inexact one.
+    assert_eq!(ranges(&mut hamming, "abc", "abd abc"), [4..7]);
+}
+
+#[test]
+fn hamming_shorter_candidate() {
+    let mut hamming = Hamming::new();
+
+    assert_no_match(&mut hamming, "abcd", "abc");
+
+    assert_no_match(&mut hamming, "a", "");
+
+    // "é" is 2 bytes long, so the candidate is as long as the query in bytes
+    // but not in characters.
+    assert_no_match(&mut hamming, "abc", "aé");
+}
+
+#[test]
+fn hamming_longer_candidate() {
+    let mut hamming = Hamming::new();
+
+    // The last window is also considered.
+    assert_eq!(ranges(&mut hamming, "abc", "xxabc"), [2..5]);
+
+    // Windows are made of characters, not bytes.
+    assert_eq!(ranges(&mut hamming, "abc", "ééabx"), [4..7]);
+
+    assert_eq!(mismatches(&mut hamming, "abc", "ééabx"), 1);
+
+    // The characters outside of the best window are not counted.
+    assert_eq!(
+        hamming.distance("abc", "abc"),
+        hamming.distance("abc", "xxxxabc")
+    );
+}
+
+#[test]
+fn hamming_max_mismatches() {
+    let mut hamming = Hamming::new();
+
+    hamming.set_max_mismatches(Some(1));
+
+    assert_eq!(mismatches(&mut hamming, "abc", "xxx abx"), 1);
+
+    assert_no_match(&mut hamming, "abc", "xyc");
+
+    hamming.set_max_mismatches(Some(0));
+
+    assert_no_match(&mut hamming, "abc", "abx");
+
+    assert_eq!(mismatches(&mut hamming, "abc", "xabc"), 0);
+}
+
+#[test]
+fn hamming_candidate_normalization() {
+    let mut hamming = Hamming::new();
+
+    assert_eq!(mismatches(&mut hamming, "cafe", "café"), 1);
+
+    hamming.set_candidate_normalization(true);
+
+    assert_eq!(mismatches(&mut hamming, "cafe", "café"), 0);
+}
@@ -0,0 +1,164 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use norm::jaro_winkler::JaroWinkler;
+use norm::{CaseSensitivity, Metric};
+
+fn similarity(jw: &mut JaroWinkler, query: &str, candidate: &str) -> f64 {
+    jw.distance(query, candidate).unwrap().similarity()
+}
+
+fn assert_close(similarity: f64, expected: f64) {
+    assert!(
+        (similarity - expected).abs() < 0.001,
+        "expected {expected}, got {similarity}"
+    );
+}
+
+#[test]
+fn jaro_winkler_empty() {
+    let mut jw = JaroWinkler::new();
+
+    assert_eq!(similarity(&mut jw, "", ""), 1.0);
+
+    assert!(jw.distance("", "foo").is_none());
+
+    assert!(jw.distance("foo", "").is_none());
+}
+
+#[test]
+fn jaro_winkler_equal() {
+    let mut jw = JaroWinkler::new();
+
+    let mut ranges = Vec::new();
+
+    let distance = jw.distance_and_ranges("foo", "foo", &mut ranges).unwrap();
+
+    assert_eq!(distance.similarity(), 1.0);
+
+    assert_eq!(ranges, [0..3]);
+}
+
+#[test]
+fn jaro_winkler_no_common_chars() {
+    let mut jw = JaroWinkler::new();
+
+    let mut ranges = vec![0..1];
+
+    assert!(jw.distance_and_ranges("abc", "xyz", &mut ranges).is_none());
+
+    assert_eq!(ranges, [0..1]);
+
+    // The characters are equal but too far apart.
+    assert!(jw.distance("ab", "xxxxba").is_none());
+}
+
+#[test]
+fn jaro_winkler_known_values() {
+    let mut jw = JaroWinkler::new();
+
+    assert_close(similarity(&mut jw, "martha", "marhta"), 0.961);
+
+    assert_close(similarity(&mut jw, "dwayne", "duane"), 0.840);
+
+    assert_close(similarity(&mut jw, "dixon", "dicksonx"), 0.813);
+
+    assert_close(similarity(&mut jw, "jellyfish", "smellyfish"), 0.896);
+}
+
+#[test]
+fn jaro_winkler_ordering() {
+    let mut jw = JaroWinkler::new();
+
+    let mut candidates = ["dicksonx", "duane", "dwayne", "dwane"];
+
+    candidates.sort_by_key(|candidate| jw.distance("dwayne", candidate));
+
+    assert_eq!(candidates, ["dwayne", "dwane", "duane", "dicksonx"]);
+}
+
+#[test]
+fn jaro_winkler_ranges() {
+    let mut jw = JaroWinkler::new();
+
+    let mut ranges = Vec::new();
+
+    jw.distance_and_ranges("dwayne", "duane", &mut ranges).unwrap();
+
+    assert_eq!(ranges, [0..1, 2..5]);
+
+    ranges.clear();
+
+    // "ö" is 2 bytes long.
+    jw.distance_and_ranges("föö", "föo", &mut ranges).unwrap();
+
+    assert_eq!(ranges, [0..3]);
+}
+
+#[test]
+fn jaro_winkler_case_sensitivity() {
+    let mut jw = JaroWinkler::new();
+
+    assert_eq!(similarity(&mut jw, "foo", "FOO"), 1.0);
+
+    // Smart case: the query has an uppercase letter.
+    assert!(similarity(&mut jw, "Foo", "foo") < 1.0);
+
+    jw.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    assert_eq!(similarity(&mut jw, "Foo", "fOO"), 1.0);
+
+    jw.set_case_sensitivity(CaseSensitivity::Sensitive);
+
+    assert!(jw.distance("foo", "FOO").is_none());
+}
+
+#[test]
+fn jaro_winkler_candidate_normalization() {
+    let mut jw = JaroWinkler::new();
+
+    jw.set_candidate_normalization(true);
+
+    let mut ranges = Vec::new();
+
+    let distance =
+        jw.distance_and_ranges("cafe", "Café", &mut ranges).unwrap();
+
+    assert_eq!(distance.similarity(), 1.0);
+
+    assert_eq!(ranges, [0..5]);
+
+    // The query is never normalized.
+    assert!(similarity(&mut jw, "café", "cafe") < 1.0);
+}
+
+#[test]
+fn jaro_winkler_prefix_scale() {
+    let mut jw = JaroWinkler::new();
+
+    jw.set_prefix_scale(0.0);
+
+    // The plain Jaro similarity.
+    assert_close(similarity(&mut jw, "martha", "marhta"), 0.944);
+
+    jw.set_prefix_scale(0.25);
+
+    assert_close(similarity(&mut jw, "martha", "marhta"), 0.986);
+}
+
+#[test]
+#[should_panic]
+fn jaro_winkler_prefix_scale_too_big() {
+    JaroWinkler::new().set_prefix_scale(0.3);
+}
+
+#[test]
+fn jaro_winkler_boost_threshold() {
+    let mut jw = JaroWinkler::new();
+
+    // The Jaro similarity is ~0.767, which is above the default threshold.
+    assert_close(similarity(&mut jw, "dixon", "dicksonx"), 0.813);
+
+    jw.set_boost_threshold(0.8);
+
+    assert_close(similarity(&mut jw, "dixon", "dicksonx"), 0.767);
+}
@@ -0,0 +1,150 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use core::ops::Range;
+
+use norm::lcs::{Lcs, LcsMode};
+use norm::{CaseSensitivity, Metric};
+
+fn lcs_len(query: &str, candidate: &str) -> usize {
+    Lcs::new().distance(query, candidate).unwrap().lcs_len()
+}
+
+fn ranges(query: &str, candidate: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    Lcs::new().distance_and_ranges(query, candidate, &mut ranges).unwrap();
+    ranges
+}
+
+#[test]
+fn lcs_lengths() {
+    assert_eq!(lcs_len("abcde", "ace"), 3);
+    assert_eq!(lcs_len("abc", "bca"), 2);
+    assert_eq!(lcs_len("AGGTAB", "GXTXAYB"), 4);
+    assert_eq!(lcs_len("kitten", "sitting"), 4);
+    assert_eq!(lcs_len("foo", "foo"), 3);
+}
+
+#[test]
+fn lcs_distance_and_ranges_agree() {
+    let mut lcs = Lcs::new();
+
+    for (query, candidate) in
+        [("abc", "bca"), ("kitten", "sitting"), ("AGGTAB", "GXTXAYB")]
+    {
+        let mut ranges = Vec::new();
+
+        let with_ranges =
+            lcs.distance_and_ranges(query, candidate, &mut ranges).unwrap();
+
+        assert_eq!(lcs.distance(query, candidate).unwrap(), with_ranges);
+
+        let matched_len: usize = ranges.iter().map(|r| r.len()).sum();
+
+        assert_eq!(matched_len, with_ranges.lcs_len());
+    }
+}
+
+#[test]
+fn lcs_ranges() {
+    assert_eq!(ranges("ace", "abcde"), [0..1, 2..3, 4..5]);
+    assert_eq!(ranges("abc", "abc"), [0..3]);
+    assert_eq!(ranges("kitten", "sitting"), [1..4, 5..6]);
+}
+
+#[test]
+fn lcs_ranges_unicode() {
+    assert_eq!(ranges("öb", "aöxb"), [1..3, 4..5]);
+}
+
+#[test]
+fn lcs_no_common_chars() {
+    let mut lcs = Lcs::new();
+
+    assert!(lcs.distance("abc", "xyz").is_none());
+
+    let mut ranges = vec![0..1];
+
+    assert!(lcs.distance_and_ranges("abc", "xyz", &mut ranges).is_none());
+
+    // The ranges are left untouched on a non-match.
+    assert_eq!(ranges, [0..1]);
+}
+
+#[test]
+fn lcs_empty() {
+    let mut lcs = Lcs::new();
+
+    let distance = lcs.distance("", "foo").unwrap();
+
+    assert_eq!(distance.lcs_len(), 0);
+    assert_eq!(distance.normalized(), 1.0);
+
+    assert_eq!(lcs.distance("", "").unwrap().normalized(), 0.0);
+
+    assert!(lcs.distance("foo", "").is_none());
+}
+
+#[test]
+fn lcs_length_mode_ordering() {
+    let mut lcs = Lcs::new();
+
+    let abc = lcs.distance("abcd", "xaxbxc").unwrap();
+    let ab = lcs.distance("abcd", "ab").unwrap();
+
+    assert!(abc < ab);
+}
+
+#[test]
+fn lcs_normalized_mode() {
+    let mut lcs = Lcs::new();
+
+    lcs.set_mode(LcsMode::Normalized);
+
+    let distance = lcs.distance("abcd", "xaxbxc").unwrap();
+
+    assert_eq!(distance.normalized(), 1.0 - 6.0 / 10.0);
+
+    // "ab" has a shorter subsequence, but it's much closer in length.
+    assert!(lcs.distance("abcd", "ab").unwrap() < distance);
+
+    assert_eq!(lcs.distance("abc", "abc").unwrap().normalized(), 0.0);
+}
+
+#[test]
+fn lcs_mixed_modes_ordering() {
+    let mut lcs = Lcs::new();
+
+    let length = lcs.distance("abcd", "xaxbxc").unwrap();
+
+    lcs.set_mode(LcsMode::Normalized);
+
+    let normalized = lcs.distance("abcd", "ab").unwrap();
+
+    // Meaningless, but it has to be consistent.
+    assert_eq!(length.cmp(&normalized), normalized.cmp(&length).reverse());
+
+    assert_ne!(length, normalized);
+}
+
+#[test]
+fn lcs_case_sensitivity() {
+    let mut lcs = Lcs::new();
+
+    assert_eq!(lcs.distance("abc", "ABC").unwrap().lcs_len(), 3);
+
+    // Smart case with an uppercase letter in the query.
+    assert_eq!(lcs.distance("Abc", "ABC").unwrap().lcs_len(), 1);
+
+    lcs.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    assert_eq!(lcs.distance("Abc", "ABC").unwrap().lcs_len(), 3);
+}
+
+#[test]
+fn lcs_long_candidate() {
+    let candidate = "ab".repeat(500);
+
+    assert_eq!(lcs_len("ba", &candidate), 2);
+
+    assert_eq!(lcs_len(&"a".repeat(300), &candidate), 300);
+}
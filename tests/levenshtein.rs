@@ -0,0 +1,193 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use norm::levenshtein::Levenshtein;
+use norm::{CaseSensitivity, Metric};
+
+fn edits_and_ranges(
+    lev: &mut Levenshtein,
+    query: &str,
+    candidate: &str,
+) -> Option<(usize, Vec<core::ops::Range<usize>>)> {
+    let mut ranges = Vec::new();
+    let distance = lev.distance_and_ranges(query, candidate, &mut ranges)?;
+    Some((distance.edits(), ranges))
+}
+
+#[test]
+fn levenshtein_empty_query() {
+    let mut lev = Levenshtein::new();
+
+    assert_eq!(edits_and_ranges(&mut lev, "", ""), Some((0, vec![])));
+
+    assert_eq!(edits_and_ranges(&mut lev, "", "foo"), Some((3, vec![])));
+}
+
+#[test]
+fn levenshtein_empty_candidate() {
+    let mut lev = Levenshtein::new();
+
+    assert_eq!(edits_and_ranges(&mut lev, "foo", ""), Some((3, vec![])));
+}
+
+#[test]
+fn levenshtein_equal() {
+    let mut lev = Levenshtein::new();
+
+    assert_eq!(
+        edits_and_ranges(&mut lev, "foo bar", "foo bar"),
+        Some((0, vec![0..7]))
+    );
+}
+
+#[test]
+fn levenshtein_kitten_sitting() {
+    let mut lev = Levenshtein::new();
+
+    assert_eq!(
+        edits_and_ranges(&mut lev, "kitten", "sitting"),
+        Some((3, vec![1..4, 5..6]))
+    );
+
+    assert_eq!(
+        edits_and_ranges(&mut lev, "sitting", "kitten"),
+        Some((3, vec![1..4, 5..6]))
+    );
+}
+
+#[test]
+fn levenshtein_unicode_ranges() {
+    let mut lev = Levenshtein::new();
+
+    // "ö" is 2 bytes long.
+    assert_eq!(
+        edits_and_ranges(&mut lev, "foo", "föo"),
+        Some((1, vec![0..1, 3..4]))
+    );
+
+    assert_eq!(
+        edits_and_ranges(&mut lev, "föö", "xföö"),
+        Some((1, vec![1..6]))
+    );
+}
+
+#[test]
+fn levenshtein_case_sensitivity() {
+    let mut lev = Levenshtein::new();
+
+    assert_eq!(lev.distance("foo", "FOO").unwrap().edits(), 0);
+
+    // Smart case: the query has an uppercase letter.
+    assert_eq!(lev.distance("Foo", "foo").unwrap().edits(), 1);
+
+    lev.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    assert_eq!(lev.distance("Foo", "fOO").unwrap().edits(), 0);
+
+    lev.set_case_sensitivity(CaseSensitivity::Sensitive);
+
+    assert_eq!(lev.distance("foo", "FOO").unwrap().edits(), 3);
+}
+
+#[test]
+fn levenshtein_candidate_normalization() {
+    let mut lev = Levenshtein::new();
+
+    lev.set_candidate_normalization(true);
+
+    assert_eq!(
+        edits_and_ranges(&mut lev, "cafe", "Café!"),
+        Some((1, vec![0..5]))
+    );
+
+    // The query is never normalized.
+    assert_eq!(lev.distance("café", "cafe").unwrap().edits(), 1);
+}
+
+#[test]
+fn levenshtein_max_distance() {
+    let mut lev = Levenshtein::new();
+
+    lev.set_max_distance(Some(1));
+
+    assert!(lev.distance("foo", "fo").is_some());
+
+    assert!(lev.distance("foo", "f").is_none());
+
+    assert!(lev.distance("foo", "bar").is_none());
+
+    let long = "a".repeat(100);
+
+    assert!(lev.distance(&long, &long[1..]).is_some());
+
+    assert!(lev.distance(&long, &"b".repeat(100)).is_none());
+}
+
+#[test]
+fn levenshtein_long_query() {
+    let mut lev = Levenshtein::new();
+
+    let query =
+        "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&\
+         ()*+";
+
+    assert_eq!(query.len(), 71);
+
+    let candidate = format!("__{}", &query[..50]);
+
+    // 2 insertions and 21 deletions.
+    assert_eq!(
+        edits_and_ranges(&mut lev, query, &candidate),
+        Some((23, vec![2..52]))
+    );
+}
+
+#[test]
+fn levenshtein_distance_within() {
+    let mut lev = Levenshtein::new();
+
+    let long = "abcdefghij".repeat(10);
+
+    let mut edited = long.clone();
+
+    edited.replace_range(40..43, "xyz");
+
+    let swapped = format!("ba{}", &long[2..]);
+
+    let candidates =
+        ["kitten", "sitting", "mitten", "", &long, &edited, &swapped];
+
+    for query in candidates {
+        let cutoffs = candidates
+            .iter()
+            .map(|candidate| lev.distance(query, candidate).unwrap())
+            .collect::<Vec<_>>();
+
+        for cutoff in &cutoffs {
+            for candidate in candidates {
+                assert_eq!(
+                    lev.distance_within(query, candidate, cutoff),
+                    lev.distance(query, candidate)
+                        .filter(|distance| distance <= cutoff),
+                    "{query:?} vs {candidate:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn levenshtein_distance_within_keeps_max_distance() {
+    let mut lev = Levenshtein::new();
+
+    let cutoff = lev.distance("kitten", "").unwrap();
+
+    lev.set_max_distance(Some(2));
+
+    // The lower of the two limits wins.
+    assert!(lev.distance_within("kitten", "sitting", &cutoff).is_none());
+
+    assert!(lev.distance_within("kitten", "mitten", &cutoff).is_some());
+
+    // The maximum distance is restored after the call.
+    assert!(lev.distance("kitten", "sitting").is_none());
+}
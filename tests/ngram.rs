@@ -0,0 +1,179 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+mod common;
+
+use common::{assert_no_match, ranges};
+use norm::ngram::{NGram, NGramSimilarity};
+use norm::Metric;
+
+fn similarity(ngram: &mut NGram, query: &str, candidate: &str) -> f64 {
+    ngram.distance(query, candidate).unwrap().similarity()
+}
+
+#[test]
+fn ngram_dice() {
+    let mut ngram = NGram::new();
+
+    ngram.set_n(2);
+
+    // {"ni", "ig", "gh", "ht"} and {"na", "ac", "ch", "ht"}.
+    assert_eq!(similarity(&mut ngram, "night", "nacht"), 0.25);
+
+    assert_eq!(similarity(&mut ngram, "night", "night"), 1.0);
+}
+
+#[test]
+fn ngram_jaccard() {
+    let mut ngram = NGram::new();
+
+    ngram.set_n(2).set_similarity(NGramSimilarity::Jaccard);
+
+    // 1 shared bigram out of 7 distinct ones.
+    assert_eq!(similarity(&mut ngram, "night", "nacht"), 1.0 / 7.0);
+}
+
+#[test]
+fn ngram_cosine() {
+    let mut ngram = NGram::new();
+
+    ngram.set_n(1).set_similarity(NGramSimilarity::Cosine);
+
+    // The count vectors are (a: 2, b: 1) and (a: 1, b: 1).
+    let expected = 3.0 / (5.0f64.sqrt() * 2.0f64.sqrt());
+
+    assert!((similarity(&mut ngram, "aab", "ab") - expected).abs() < 1e-9);
+}
+
+#[test]
+fn ngram_repeated_grams() {
+    let mut ngram = NGram::new();
+
+    ngram.set_n(1);
+
+    // Only one of the two "a"s in the query can be matched.
+    assert_eq!(similarity(&mut ngram, "aa", "a"), 2.0 / 3.0);
+
+    ngram.set_similarity(NGramSimilarity::Jaccard);
+
+    assert_eq!(similarity(&mut ngram, "aa", "a"), 0.5);
+}
+
+#[test]
+fn ngram_short_strings() {
+    let mut ngram = NGram::new();
+
+    // Strings shorter than n have a single n-gram made of all their chars.
+    assert_eq!(similarity(&mut ngram, "ab", "ab"), 1.0);
+
+    assert_no_match(&mut ngram, "ab", "abc");
+}
+
+#[test]
+fn ngram_padding() {
+    let mut ngram = NGram::new();
+
+    ngram.set_n(2).set_padding(true);
+
+    // {" a", "ab", "b "} and {" a", "ab", "b ", " c", "cd", "d "}.
+    assert_eq!(similarity(&mut ngram, "ab", "ab cd"), 6.0 / 9.0);
+
+    // The padding is not part of the ranges.
+    assert_eq!(ranges(&mut ngram, "ab", "ab cd"), [0..2]);
+
+    // Grams don't span multiple words, so "b c" is not a match.
+    assert_no_match(&mut ngram, "bc", "ab cd");
+}
+
+#[test]
+fn ngram_word_order() {
+    let mut ngram = NGram::new();
+
+    ngram.set_padding(true);
+
+    assert_eq!(similarity(&mut ngram, "foo bar", "bar foo"), 1.0);
+}
+
+#[test]
+fn ngram_ranges() {
+    let mut ngram = NGram::new();
+
+    assert_eq!(ranges(&mut ngram, "fuzzy", "buzzy"), [1..5]);
+
+    assert_eq!(ranges(&mut ngram, "abc", "xabcyabc"), [1..4, 5..8]);
+}
+
+#[test]
+fn ngram_arity() {
+    let mut ngram = NGram::new();
+
+    ngram.set_n(1);
+
+    // {"a", "b", "c", "d"} and {"a", "b", "x", "d"}.
+    assert_eq!(similarity(&mut ngram, "abcd", "abxd"), 0.75);
+
+    ngram.set_n(2);
+
+    // {"ab", "bc", "cd"} and {"ab", "bx", "xd"}.
+    assert_eq!(similarity(&mut ngram, "abcd", "abxd"), 1.0 / 3.0);
+
+    assert_eq!(ranges(&mut ngram, "abcd", "abxd"), [0..2]);
+
+    ngram.set_n(3);
+
+    // {"abc", "bcd"} and {"abx", "bxd"}.
+    assert_no_match(&mut ngram, "abcd", "abxd");
+}
+
+#[test]
+fn ngram_padding_word_edges() {
+    let mut ngram = NGram::new();
+
+    // {"abc"} and {"abd"}.
+    assert_no_match(&mut ngram, "abc", "abd");
+
+    ngram.set_padding(true);
+
+    // {"  a", " ab", "abc", "bc ", "c  "} and {"  a", " ab", "abd", "bd ",
+    // "d  "}.
+    assert_eq!(similarity(&mut ngram, "abc", "abd"), 0.4);
+
+    assert_eq!(ranges(&mut ngram, "abc", "abd"), [0..2]);
+}
+
+#[test]
+fn ngram_padding_short_words() {
+    let mut ngram = NGram::new();
+
+    // Without padding a word shorter than n is a single n-gram, so a
+    // one-letter query only matches that same letter.
+    assert_no_match(&mut ngram, "a", "ab");
+
+    ngram.set_padding(true);
+
+    // {"  a", " a ", "a  "} and {"  a", " ab", "ab ", "b  "}.
+    assert_eq!(similarity(&mut ngram, "a", "ab"), 2.0 / 7.0);
+
+    assert_eq!(ranges(&mut ngram, "a", "ab"), [0..1]);
+}
+
+#[test]
+fn ngram_padding_unigrams() {
+    let mut ngram = NGram::new();
+
+    ngram.set_n(1);
+
+    let unpadded = similarity(&mut ngram, "aab", "ab");
+
+    ngram.set_padding(true);
+
+    // With n = 1 words are padded with zero spaces.
+    assert_eq!(similarity(&mut ngram, "aab", "ab"), unpadded);
+
+    assert_eq!(unpadded, 0.8);
+}
+
+#[test]
+#[should_panic]
+fn ngram_zero_n() {
+    NGram::new().set_n(0);
+}
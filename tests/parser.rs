@@ -0,0 +1,76 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use core::ops::Range;
+
+use norm::fzf::{FzfParser, FzfV1, FzfV2, Skim};
+use norm::hamming::Hamming;
+use norm::levenshtein::Levenshtein;
+use norm::{Metric, Parser, StrParser};
+
+fn ranges<M: Metric, P: Parser<M>>(
+    metric: &mut M,
+    parser: &mut P,
+    query: &str,
+    candidate: &str,
+) -> Option<Vec<Range<usize>>> {
+    let query = parser.parse(query);
+    let mut ranges = Vec::new();
+    metric.distance_and_ranges(query, candidate, &mut ranges)?;
+    Some(ranges)
+}
+
+#[test]
+fn parser_fzf_extended() {
+    let mut parser = FzfParser::new();
+
+    assert_eq!(
+        ranges(&mut FzfV1::new(), &mut parser, "^foo baz$", "foo_bar_baz"),
+        Some(vec![0..3, 8..11])
+    );
+
+    assert_eq!(
+        ranges(&mut FzfV2::new(), &mut parser, "^foo baz$", "foo_bar_baz"),
+        Some(vec![0..3, 8..11])
+    );
+
+    assert_eq!(
+        ranges(&mut Skim::new(), &mut parser, "^foo baz$", "foo_bar_baz"),
+        Some(vec![0..3, 8..11])
+    );
+
+    assert_eq!(
+        ranges(&mut FzfV2::new(), &mut parser, "!bar", "foo_bar_baz"),
+        None
+    );
+}
+
+#[test]
+fn parser_str() {
+    assert_eq!(
+        ranges(&mut Levenshtein::new(), &mut StrParser, "foo", "foo"),
+        Some(vec![0..3])
+    );
+
+    assert_eq!(
+        ranges(&mut Hamming::new(), &mut StrParser, "bar", "foo_bar"),
+        Some(vec![4..7])
+    );
+
+    // The query is passed to the metric as is.
+    assert_eq!(
+        ranges(&mut Hamming::new(), &mut StrParser, "^bar", "foo_bar"),
+        Some(vec![3..7])
+    );
+}
+
+#[test]
+fn parser_str_borrows_query() {
+    let mut parser = StrParser;
+
+    let query = String::from("foo");
+
+    let parsed =
+        <StrParser as Parser<Levenshtein>>::parse(&mut parser, &query);
+
+    assert!(core::ptr::eq(parsed, query.as_str()));
+}
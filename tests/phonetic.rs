@@ -0,0 +1,206 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+mod common;
+
+use common::{assert_no_match, ranges};
+use norm::phonetic::{
+    double_metaphone,
+    metaphone,
+    soundex,
+    Phonetic,
+    PhoneticAlgorithm,
+};
+use norm::Metric;
+
+#[track_caller]
+fn assert_double_metaphone(word: &str, primary: &str, alternate: &str) {
+    assert_eq!(
+        double_metaphone(word),
+        (primary.to_owned(), alternate.to_owned())
+    );
+}
+
+#[test]
+fn phonetic_soundex() {
+    assert_eq!(soundex("Robert"), "R163");
+    assert_eq!(soundex("Rupert"), "R163");
+    assert_eq!(soundex("Rubin"), "R150");
+    assert_eq!(soundex("Ashcraft"), "A261");
+    assert_eq!(soundex("Ashcroft"), "A261");
+    assert_eq!(soundex("Tymczak"), "T522");
+    assert_eq!(soundex("Pfister"), "P236");
+    assert_eq!(soundex("Honeyman"), "H555");
+    assert_eq!(soundex("O'Hara"), "O600");
+    assert_eq!(soundex("A"), "A000");
+    assert_eq!(soundex("123"), "");
+}
+
+#[test]
+fn phonetic_metaphone() {
+    assert_eq!(metaphone("howl"), "HL");
+    assert_eq!(metaphone("testing"), "TSTN");
+    assert_eq!(metaphone("The"), "0");
+    assert_eq!(metaphone("quick"), "KK");
+    assert_eq!(metaphone("brown"), "BRN");
+    assert_eq!(metaphone("fox"), "FKS");
+    assert_eq!(metaphone("jumped"), "JMPT");
+    assert_eq!(metaphone("over"), "OFR");
+    assert_eq!(metaphone("lazy"), "LS");
+    assert_eq!(metaphone("dogs"), "TKS");
+}
+
+#[test]
+fn phonetic_metaphone_special_cases() {
+    assert_eq!(metaphone("COMB"), "KM");
+    assert_eq!(metaphone("WOMB"), "WM");
+    assert_eq!(metaphone("SCIENCE"), "SNS");
+    assert_eq!(metaphone("SCENE"), "SN");
+    assert_eq!(metaphone("WHY"), "");
+    assert_eq!(metaphone("CIAPO"), "XP");
+    assert_eq!(metaphone("SCHEDULE"), "SKTL");
+    assert_eq!(metaphone("CHARACTER"), "KRKT");
+    assert_eq!(metaphone("TEACH"), "TX");
+    assert_eq!(metaphone("DODGY"), "TJ");
+    assert_eq!(metaphone("GHENT"), "KNT");
+    assert_eq!(metaphone("BAUGH"), "B");
+    assert_eq!(metaphone("GNU"), "N");
+    assert_eq!(metaphone("SIGNED"), "SNT");
+    assert_eq!(metaphone("PHISH"), "FX");
+    assert_eq!(metaphone("ODSIAN"), "OTXN");
+    assert_eq!(metaphone("PORTION"), "PRXN");
+    assert_eq!(metaphone("RETCH"), "RX");
+    assert_eq!(metaphone("WATCH"), "WX");
+    assert_eq!(metaphone("AXEAXE"), "AKSK");
+}
+
+#[test]
+fn phonetic_double_metaphone() {
+    assert_double_metaphone("Smith", "SM0", "XMT");
+    assert_double_metaphone("Schmidt", "XMT", "SMT");
+    assert_double_metaphone("Jose", "HS", "HS");
+    assert_double_metaphone("Catherine", "K0RN", "KTRN");
+    assert_double_metaphone("Katherine", "K0RN", "KTRN");
+    assert_double_metaphone("Michael", "MKL", "MXL");
+    assert_double_metaphone("Wasserman", "ASRM", "FSRM");
+    assert_double_metaphone("Xavier", "SF", "SFR");
+    assert_double_metaphone("Edge", "AJ", "AJ");
+    assert_double_metaphone("Caesar", "SSR", "SSR");
+    assert_double_metaphone("Garçon", "KRSN", "KRSN");
+}
+
+#[test]
+fn phonetic_matches() {
+    let mut phonetic = Phonetic::new();
+
+    assert!(phonetic.distance("Smyth", "Smith").is_some());
+
+    assert!(phonetic.distance("Katherine", "Catherine").is_some());
+
+    assert!(phonetic.distance("Schmidt", "Smith").is_some());
+
+    assert_no_match(&mut phonetic, "Smith", "Jones");
+}
+
+#[test]
+fn phonetic_every_query_word_must_match() {
+    let mut phonetic = Phonetic::new();
+
+    assert!(phonetic.distance("jon smyth", "Smith, John").is_some());
+
+    assert_no_match(&mut phonetic, "jon smyth", "Smith, Mary");
+}
+
+#[test]
+fn phonetic_ranges() {
+    let mut phonetic = Phonetic::new();
+
+    assert_eq!(ranges(&mut phonetic, "smyth", "John Smith"), [5..10]);
+
+    assert_eq!(
+        ranges(&mut phonetic, "jon smyth", "Smith, John"),
+        [0..5, 7..11]
+    );
+
+    assert_eq!(ranges(&mut phonetic, "smith", "Smith-Smyth"), [0..5, 6..11]);
+}
+
+#[test]
+fn phonetic_algorithms() {
+    let mut phonetic = Phonetic::new();
+
+    phonetic.set_algorithm(PhoneticAlgorithm::Soundex);
+
+    assert!(phonetic.distance("Robert", "Rupert").is_some());
+
+    assert!(phonetic.distance("Katherine", "Catherine").is_none());
+
+    phonetic.set_algorithm(PhoneticAlgorithm::Metaphone);
+
+    assert!(phonetic.distance("Katherine", "Catherine").is_some());
+
+    // Only Double Metaphone has alternate codes.
+    assert!(phonetic.distance("Schmidt", "Smith").is_none());
+}
+
+#[test]
+fn phonetic_soundex_codes() {
+    let mut phonetic = Phonetic::new();
+
+    phonetic.set_algorithm(PhoneticAlgorithm::Soundex);
+
+    // Soundex keeps the first letter as is.
+    assert_eq!(soundex("Carl"), "C640");
+    assert_eq!(soundex("Karl"), "K640");
+
+    assert_no_match(&mut phonetic, "Carl", "Karl");
+
+    // Soundex codes are truncated to 4 characters.
+    assert_eq!(soundex("Robertson"), soundex("Roberts"));
+
+    assert!(phonetic.distance("Robertson", "Roberts").is_some());
+
+    phonetic.set_algorithm(PhoneticAlgorithm::Metaphone);
+
+    assert!(phonetic.distance("Carl", "Karl").is_some());
+
+    // Soundex maps "b" and "p" to the same digit, Metaphone doesn't.
+    assert_no_match(&mut phonetic, "Robert", "Rupert");
+}
+
+#[test]
+fn phonetic_double_metaphone_alternate_codes() {
+    let mut phonetic = Phonetic::new();
+
+    // The alternate code of "Wasserman" is the primary one of "Vasserman".
+    assert_double_metaphone("Vasserman", "FSRM", "FSRM");
+
+    let distance = phonetic.distance("Wasserman", "Vasserman").unwrap();
+
+    assert_eq!(distance.unmatched_words(), 0);
+
+    assert_eq!(distance.inexact_words(), 1);
+
+    assert_eq!(ranges(&mut phonetic, "Wasserman", "Vasserman"), [0..9]);
+
+    phonetic.set_algorithm(PhoneticAlgorithm::Metaphone);
+
+    assert_no_match(&mut phonetic, "Wasserman", "Vasserman");
+}
+
+#[test]
+fn phonetic_words_without_code() {
+    let mut phonetic = Phonetic::new();
+
+    assert_eq!(soundex("42"), "");
+    assert_eq!(metaphone("42"), "");
+    assert_double_metaphone("42", "", "");
+
+    // A query made only of words without a code doesn't match anything.
+    assert_no_match(&mut phonetic, "42", "Smith");
+
+    let distance = phonetic.distance("Smith 42", "Smith").unwrap();
+
+    assert_eq!(distance.unmatched_words(), 0);
+
+    assert_eq!(ranges(&mut phonetic, "Smith", "Smith 42"), [0..5]);
+}
@@ -0,0 +1,170 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use core::ops::Range;
+
+use norm::ratcliff_obershelp::RatcliffObershelp;
+use norm::{CaseSensitivity, Metric};
+
+/// Asserts that the ratio and the ranges between the query and the candidate
+/// are the same as the ones computed by Python's
+/// `SequenceMatcher(None, candidate, query)`.
+#[track_caller]
+fn assert_python(
+    query: &str,
+    candidate: &str,
+    ratio: f64,
+    ranges: &[Range<usize>],
+) {
+    let mut ro = RatcliffObershelp::new();
+
+    let mut actual_ranges = Vec::new();
+
+    let distance =
+        ro.distance_and_ranges(query, candidate, &mut actual_ranges).unwrap();
+
+    assert_eq!(distance.ratio(), ratio);
+
+    assert_eq!(actual_ranges, ranges);
+}
+
+#[test]
+fn ratcliff_obershelp_python_ratios() {
+    assert_python("appel", "apple", 0.8, &[0..4]);
+
+    assert_python("abcd", "bcda", 0.75, &[0..3]);
+
+    assert_python("qabxcd", "abycdf", 0.6666666666666666, &[0..2, 3..5]);
+
+    assert_python("wikimedia", "wikimania", 0.7777777777777778, &[0..5, 7..9]);
+
+    assert_python("kitten", "sitting", 0.6153846153846154, &[1..4, 5..6]);
+}
+
+#[test]
+fn ratcliff_obershelp_python_tie_breaking() {
+    assert_python(
+        "private Thread currentThread;",
+        "private volatile Thread currentThread;",
+        0.8656716417910447,
+        &[0..6, 15..38],
+    );
+
+    assert_python(
+        "GESTALT PATTERN MATCHING",
+        "GESTALT PRACTICE",
+        0.65,
+        &[0..12, 13..14],
+    );
+}
+
+#[test]
+fn ratcliff_obershelp_python_unicode() {
+    // The matched characters in "föo bär" are "f", "o b" and "r".
+    assert_python(
+        "foo bar",
+        "föo bär",
+        0.7142857142857143,
+        &[0..1, 3..6, 8..9],
+    );
+}
+
+#[test]
+fn ratcliff_obershelp_autojunk() {
+    let query = "a".repeat(150) + &"b".repeat(60);
+
+    let candidate = "xab".to_owned() + &"a".repeat(10);
+
+    let mut ro = RatcliffObershelp::new();
+
+    // Both "a" and "b" are popular, so no block can start with them.
+    assert!(ro.distance(&query, &candidate).is_none());
+
+    ro.set_autojunk(false);
+
+    let mut ranges = Vec::new();
+
+    let distance =
+        ro.distance_and_ranges(&query, &candidate, &mut ranges).unwrap();
+
+    assert_eq!(distance.ratio(), 0.08968609865470852);
+
+    assert_eq!(ranges, [3..13]);
+}
+
+#[test]
+fn ratcliff_obershelp_autojunk_short_query() {
+    // The junk heuristic only kicks in for queries of at least 200 chars.
+    let query = "a".repeat(199);
+
+    assert!(RatcliffObershelp::new().distance(&query, "bab").is_some());
+}
+
+#[test]
+fn ratcliff_obershelp_empty() {
+    let mut ro = RatcliffObershelp::new();
+
+    assert_eq!(ro.distance("", "").unwrap().ratio(), 1.0);
+
+    assert!(ro.distance("", "foo").is_none());
+
+    assert!(ro.distance("foo", "").is_none());
+}
+
+#[test]
+fn ratcliff_obershelp_no_match_leaves_ranges_untouched() {
+    let mut ranges = vec![0..1];
+
+    let distance = RatcliffObershelp::new().distance_and_ranges(
+        "abc",
+        "xyz",
+        &mut ranges,
+    );
+
+    assert!(distance.is_none());
+
+    assert_eq!(ranges, [0..1]);
+}
+
+#[test]
+fn ratcliff_obershelp_cutoff() {
+    let mut ro = RatcliffObershelp::new();
+
+    ro.set_cutoff(0.8);
+
+    assert!(ro.distance("appel", "apple").is_some());
+
+    assert!(ro.distance("abcd", "bcda").is_none());
+}
+
+#[test]
+#[should_panic]
+fn ratcliff_obershelp_cutoff_out_of_range() {
+    RatcliffObershelp::new().set_cutoff(1.5);
+}
+
+#[test]
+fn ratcliff_obershelp_distance_ordering() {
+    let mut ro = RatcliffObershelp::new();
+
+    let apple = ro.distance("appel", "apple").unwrap();
+
+    let ape = ro.distance("appel", "ape").unwrap();
+
+    assert!(apple < ape);
+}
+
+#[test]
+fn ratcliff_obershelp_case_insensitive() {
+    let mut ro = RatcliffObershelp::new();
+
+    ro.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    let mut ranges = Vec::new();
+
+    let distance =
+        ro.distance_and_ranges("Apple", "aPPLE", &mut ranges).unwrap();
+
+    assert_eq!(distance.ratio(), 1.0);
+
+    assert_eq!(ranges, [0..5]);
+}
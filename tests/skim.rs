@@ -0,0 +1,168 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use norm::fzf::{FzfParser, Skim};
+use norm::{CaseSensitivity, Metric};
+
+fn ranges(query: &str, candidate: &str) -> Vec<core::ops::Range<usize>> {
+    let mut skim = Skim::new();
+    let mut parser = FzfParser::new();
+    let mut ranges = Vec::new();
+    skim.distance_and_ranges(parser.parse(query), candidate, &mut ranges)
+        .unwrap();
+    ranges
+}
+
+#[test]
+fn skim_empty_query() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    let mut ranges = Vec::new();
+
+    assert!(skim
+        .distance_and_ranges(parser.parse(""), "foo", &mut ranges)
+        .is_some());
+
+    assert!(ranges.is_empty());
+}
+
+#[test]
+fn skim_non_match() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    assert!(skim.distance(parser.parse("abc"), "acb").is_none());
+
+    assert!(skim.distance(parser.parse("abc"), "").is_none());
+}
+
+#[test]
+fn skim_fuzzy_ranges() {
+    assert_eq!(ranges("abc", "abc"), [0..3]);
+
+    assert_eq!(ranges("abc", "a_b_c"), [0..1, 2..3, 4..5]);
+
+    // The later "bc" is consecutive.
+    assert_eq!(ranges("abc", "axbxxbc"), [0..1, 5..7]);
+
+    // The "f" and the "b" are at the start of words.
+    assert_eq!(ranges("fb", "xfx foo bar"), [4..5, 8..9]);
+}
+
+#[test]
+fn skim_unicode_ranges() {
+    // "ö" is 2 bytes long.
+    assert_eq!(ranges("öb", "aö ö/b"), [4..6, 7..8]);
+
+    assert_eq!(ranges("öb", "aööb"), [3..6]);
+}
+
+#[test]
+fn skim_prefers_word_starts() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("fb");
+
+    let separator = skim.distance(query, "foo_bar").unwrap();
+
+    let camel_case = skim.distance(query, "fooBar").unwrap();
+
+    let middle = skim.distance(query, "foobar").unwrap();
+
+    assert!(separator < camel_case);
+
+    assert!(camel_case < middle);
+}
+
+#[test]
+fn skim_prefers_consecutive() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    let query = parser.parse("oba");
+
+    assert!(
+        skim.distance(query, "foobar").unwrap()
+            < skim.distance(query, "fooxbxa").unwrap()
+    );
+}
+
+#[test]
+fn skim_case_mismatch_penalty() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    skim.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    let query = parser.parse("foo");
+
+    assert!(
+        skim.distance(query, "foo").unwrap()
+            < skim.distance(query, "FOO").unwrap()
+    );
+}
+
+#[test]
+fn skim_case_sensitivity() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    assert!(skim.distance(parser.parse("foo"), "FOO").is_some());
+
+    assert!(skim.distance(parser.parse("Foo"), "foo").is_none());
+
+    skim.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    assert!(skim.distance(parser.parse("Foo"), "fOO").is_some());
+
+    skim.set_case_sensitivity(CaseSensitivity::Sensitive);
+
+    assert!(skim.distance(parser.parse("foo"), "FOO").is_none());
+}
+
+#[test]
+fn skim_candidate_normalization() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    let mut ranges = Vec::new();
+
+    assert!(skim.distance(parser.parse("cafe"), "Café").is_none());
+
+    skim.set_candidate_normalization(true);
+
+    skim.distance_and_ranges(parser.parse("cafe"), "Café", &mut ranges)
+        .unwrap();
+
+    assert_eq!(ranges, [0..5]);
+}
+
+#[test]
+fn skim_extended_syntax() {
+    let mut skim = Skim::new();
+
+    let mut parser = FzfParser::new();
+
+    let mut ranges = Vec::new();
+
+    let query = parser.parse("^foo 'bar !baz qux | quux");
+
+    assert!(skim
+        .distance_and_ranges(query, "foo_xbar_quux", &mut ranges)
+        .is_some());
+
+    // Only the first pattern of the OR group that matches is highlighted.
+    assert_eq!(ranges, [0..3, 5..8, 9..10, 11..13]);
+
+    assert!(skim.distance(query, "foo_xbar_baz_qux").is_none());
+
+    assert!(skim.distance(query, "xfoo_bar_qux").is_none());
+}
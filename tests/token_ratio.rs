@@ -0,0 +1,180 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use core::ops::Range;
+
+use norm::token_ratio::{TokenRatio, TokenRatioMode};
+use norm::{CaseSensitivity, Metric};
+
+fn ratio(mode: TokenRatioMode, query: &str, candidate: &str) -> f64 {
+    let mut token_ratio = TokenRatio::new();
+    token_ratio.set_mode(mode);
+    token_ratio.distance(query, candidate).unwrap().ratio()
+}
+
+fn ranges(
+    mode: TokenRatioMode,
+    query: &str,
+    candidate: &str,
+) -> Vec<Range<usize>> {
+    let mut token_ratio = TokenRatio::new();
+    token_ratio.set_mode(mode);
+    let mut ranges = Vec::new();
+    token_ratio.distance_and_ranges(query, candidate, &mut ranges).unwrap();
+    ranges
+}
+
+#[test]
+fn token_ratio_sort_fuzzywuzzy() {
+    use TokenRatioMode::Sort;
+
+    assert_eq!(
+        ratio(Sort, "fuzzy wuzzy was a bear", "wuzzy fuzzy was a bear"),
+        1.0
+    );
+
+    assert_eq!(
+        ratio(Sort, "fuzzy was a bear", "fuzzy fuzzy was a bear"),
+        0.8421052631578947
+    );
+
+    assert_eq!(
+        ratio(Sort, "new york mets", "new york yankees"),
+        0.5517241379310345
+    );
+}
+
+#[test]
+fn token_ratio_set_fuzzywuzzy() {
+    use TokenRatioMode::Set;
+
+    assert_eq!(ratio(Set, "fuzzy was a bear", "fuzzy fuzzy was a bear"), 1.0);
+
+    assert_eq!(
+        ratio(Set, "new york mets", "new york yankees"),
+        0.7619047619047619
+    );
+
+    assert_eq!(
+        ratio(
+            Set,
+            "mariners vs angels",
+            "los angeles angels of anaheim at seattle mariners"
+        ),
+        0.9090909090909091
+    );
+}
+
+#[test]
+fn token_ratio_asymmetric_fuzzywuzzy() {
+    use TokenRatioMode::{Set, Sort};
+
+    // The ratio depends on which string comes first, and fuzzywuzzy passes
+    // the query first.
+    assert_eq!(ratio(Sort, "Aabbcb ,A  ", " - bcab-b"), 3.0 / 7.0);
+
+    assert_eq!(ratio(Sort, " - bcab-b", "Aabbcb ,A  "), 4.0 / 7.0);
+
+    assert_eq!(ratio(Set, "Aabbcb ,A  ", " - bcab-b"), 3.0 / 7.0);
+
+    assert_eq!(ranges(Sort, "Aabbcb ,A  ", " - bcab-b"), [3..7]);
+}
+
+#[test]
+fn token_ratio_word_order() {
+    assert_eq!(ratio(TokenRatioMode::Set, "york new", "New York City"), 1.0);
+
+    assert_eq!(
+        ratio(TokenRatioMode::Sort, "york new", "New York City"),
+        16.0 / 21.0
+    );
+}
+
+#[test]
+fn token_ratio_punctuation() {
+    assert_eq!(ratio(TokenRatioMode::Sort, "foo-bar", "bar_foo"), 1.0);
+
+    assert_eq!(
+        ranges(TokenRatioMode::Sort, "foo bar", "(bar), foo!"),
+        [1..4, 7..10]
+    );
+}
+
+#[test]
+fn token_ratio_set_ranges() {
+    // Only the tokens in the intersection are matched.
+    assert_eq!(
+        ranges(TokenRatioMode::Set, "york new", "New York City"),
+        [0..3, 4..8]
+    );
+
+    // Repeated tokens are all matched.
+    assert_eq!(
+        ranges(TokenRatioMode::Set, "fuzzy bear", "fuzzy fuzzy bear"),
+        [0..5, 6..11, 12..16]
+    );
+}
+
+#[test]
+fn token_ratio_sort_ranges() {
+    // "yankees" shares "e" with "mets", and the whole token is matched.
+    assert_eq!(
+        ranges(TokenRatioMode::Sort, "new york mets", "new york yankees"),
+        [0..3, 4..8, 9..16]
+    );
+}
+
+#[test]
+fn token_ratio_ranges_unicode() {
+    assert_eq!(ranges(TokenRatioMode::Sort, "café", "le café"), [3..8]);
+}
+
+#[test]
+fn token_ratio_empty() {
+    let mut token_ratio = TokenRatio::new();
+
+    assert_eq!(token_ratio.distance("", "").unwrap().ratio(), 1.0);
+
+    assert_eq!(token_ratio.distance("--", " ").unwrap().ratio(), 1.0);
+
+    assert!(token_ratio.distance("", "foo").is_none());
+
+    assert!(token_ratio.distance("foo", "").is_none());
+}
+
+#[test]
+fn token_ratio_no_match_leaves_ranges_untouched() {
+    let mut ranges = vec![0..1];
+
+    let distance =
+        TokenRatio::new().distance_and_ranges("abc", "xyz", &mut ranges);
+
+    assert!(distance.is_none());
+
+    assert_eq!(ranges, [0..1]);
+}
+
+#[test]
+fn token_ratio_case_sensitivity() {
+    let mut token_ratio = TokenRatio::new();
+
+    assert_eq!(token_ratio.distance("abc", "ABC").unwrap().ratio(), 1.0);
+
+    // Smart case with an uppercase letter in the query.
+    assert!(token_ratio.distance("Abc", "ABC").unwrap().ratio() < 1.0);
+
+    token_ratio.set_case_sensitivity(CaseSensitivity::Insensitive);
+
+    assert_eq!(token_ratio.distance("Abc", "ABC").unwrap().ratio(), 1.0);
+}
+
+#[test]
+fn token_ratio_distance_ordering() {
+    let mut token_ratio = TokenRatio::new();
+
+    let mets = token_ratio.distance("new york mets", "new york mets").unwrap();
+
+    let yankees =
+        token_ratio.distance("new york mets", "new york yankees").unwrap();
+
+    assert!(mets < yankees);
+}
@@ -0,0 +1,235 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use core::ops::Range;
+
+use norm::vscode::VsCode;
+use norm::Metric;
+
+fn score(query: &str, candidate: &str) -> i64 {
+    VsCode::new().distance(query, candidate).unwrap().into_score()
+}
+
+fn path_score(query: &str, candidate: &str) -> i64 {
+    VsCode::new()
+        .set_path_mode(true)
+        .distance(query, candidate)
+        .unwrap()
+        .into_score()
+}
+
+fn path_ranges(query: &str, candidate: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    VsCode::new()
+        .set_path_mode(true)
+        .distance_and_ranges(query, candidate, &mut ranges)
+        .unwrap();
+    ranges
+}
+
+/// Asserts that the query matches the word with the characters preceded by
+/// a `^` in `expected`, or that it doesn't match at all if `expected` is
+/// `None`. This is the same notation used by VS Code's own tests.
+#[track_caller]
+fn assert_matches(query: &str, word: &str, expected: Option<&str>) {
+    let mut ranges = Vec::new();
+
+    let distance = VsCode::new().distance_and_ranges(query, word, &mut ranges);
+
+    let Some(expected) = expected else {
+        assert!(distance.is_none(), "{query:?} shouldn't match {word:?}");
+        return;
+    };
+
+    assert!(distance.is_some(), "{query:?} should match {word:?}");
+
+    let mut expected_ranges: Vec<Range<usize>> = Vec::new();
+
+    let mut offset = 0;
+
+    let mut is_match = false;
+
+    for ch in expected.chars() {
+        if ch == '^' {
+            is_match = true;
+            continue;
+        }
+
+        if is_match {
+            match expected_ranges.last_mut() {
+                Some(last) if last.end == offset => last.end += ch.len_utf8(),
+                _ => expected_ranges.push(offset..offset + ch.len_utf8()),
+            }
+        }
+
+        offset += ch.len_utf8();
+        is_match = false;
+    }
+
+    assert_eq!(ranges, expected_ranges, "{query:?} in {expected:?}");
+}
+
+#[test]
+fn vscode_upstream_fuzzy_score() {
+    assert_matches("ab", "abA", Some("^a^bA"));
+    assert_matches("ccm", "cacmelCase", Some("^ca^c^melCase"));
+    assert_matches("bti", "the_black_knight", None);
+    assert_matches("ccm", "camelCase", None);
+    assert_matches("cmcm", "camelCase", None);
+    assert_matches("BK", "the_black_knight", Some("the_^black_^knight"));
+    assert_matches("KeyboardLayout=", "KeyboardLayout", None);
+    assert_matches(
+        "LLL",
+        "SVisualLoggerLogsList",
+        Some("SVisual^Logger^Logs^List"),
+    );
+    assert_matches("LLLL", "SVilLoLosLi", None);
+    assert_matches("LLLL", "SVisualLoggerLogsList", None);
+    assert_matches("TEdit", "TextEdit", Some("^Text^E^d^i^t"));
+    assert_matches("TEdit", "TextEditor", Some("^Text^E^d^i^tor"));
+    assert_matches("TEdit", "Textedit", Some("^Text^e^d^i^t"));
+    assert_matches("TEdit", "text_edit", Some("^text_^e^d^i^t"));
+    assert_matches(
+        "TEditDit",
+        "TextEditorDecorationType",
+        Some("^Text^E^d^i^tor^Decorat^ion^Type"),
+    );
+    assert_matches(
+        "TEdit",
+        "TextEditorDecorationType",
+        Some("^Text^E^d^i^torDecorationType"),
+    );
+    assert_matches("Tedit", "TextEdit", Some("^Text^E^d^i^t"));
+    assert_matches("ba", "?AB?", None);
+    assert_matches("bkn", "the_black_knight", Some("the_^black_^k^night"));
+    assert_matches("bt", "the_black_knight", Some("the_^black_knigh^t"));
+    assert_matches("fo", "barfoo", None);
+    assert_matches("fo", "bar_foo", Some("bar_^f^oo"));
+    assert_matches("fo", "bar_Foo", Some("bar_^F^oo"));
+    assert_matches("fo", "bar foo", Some("bar ^f^oo"));
+    assert_matches("fo", "bar.foo", Some("bar.^f^oo"));
+    assert_matches("fo", "bar/foo", Some("bar/^f^oo"));
+    assert_matches("fo", "bar\\foo", Some("bar\\^f^oo"));
+}
+
+#[test]
+fn vscode_empty_query() {
+    let mut vscode = VsCode::new();
+
+    assert_eq!(vscode.distance("", "foo").unwrap().into_score(), 0);
+
+    let mut ranges = Vec::new();
+
+    vscode.distance_and_ranges("", "foo", &mut ranges).unwrap();
+
+    assert!(ranges.is_empty());
+}
+
+#[test]
+fn vscode_query_longer_than_candidate() {
+    assert!(VsCode::new().distance("foobar", "foo").is_none());
+}
+
+#[test]
+fn vscode_weak_first_match() {
+    let mut vscode = VsCode::new();
+
+    assert!(vscode.distance("o", "foo").is_none());
+
+    vscode.set_first_match_can_be_weak(true);
+
+    let mut ranges = Vec::new();
+
+    vscode.distance_and_ranges("o", "foo", &mut ranges).unwrap();
+
+    assert_eq!(ranges, [1..2]);
+}
+
+#[test]
+fn vscode_prefer_same_case() {
+    assert!(score("Foo", "Foo") > score("Foo", "foo"));
+}
+
+#[test]
+fn vscode_prefer_full_matches() {
+    assert!(score("foo", "foo") > score("foo", "foobar"));
+}
+
+#[test]
+fn vscode_prefer_fewer_skipped_chars() {
+    assert!(score("fb", "foo_bar") > score("fb", "foooo_bar"));
+}
+
+#[test]
+fn vscode_only_first_128_chars_are_considered() {
+    let mut candidate = "a".repeat(128);
+
+    candidate.push('b');
+
+    assert!(VsCode::new().distance("ab", &candidate).is_none());
+
+    let query = "a".repeat(200);
+
+    assert!(VsCode::new().distance(&query, &candidate).is_some());
+}
+
+#[test]
+fn vscode_unicode() {
+    let mut ranges = Vec::new();
+
+    VsCode::new().distance_and_ranges("öb", "ö_bär", &mut ranges).unwrap();
+
+    assert_eq!(ranges, [0..2, 3..4]);
+}
+
+#[test]
+fn vscode_path_mode_prefers_basename() {
+    assert!(
+        path_score("main", "src/lib/main.rs")
+            > path_score("main", "src/main/lib.rs")
+    );
+
+    // A match in the middle of the basename is still better than one in the
+    // directory.
+    assert!(
+        path_score("lib", "src/main/my_lib.rs")
+            > path_score("lib", "lib/main.rs")
+    );
+}
+
+#[test]
+fn vscode_path_mode_prefers_basename_prefix() {
+    assert!(
+        path_score("window", "src/window.ts")
+            > path_score("window", "src/my_window.ts")
+    );
+
+    // Shorter basenames starting with the query are better.
+    assert!(
+        path_score("window", "src/window.ts")
+            > path_score("window", "src/windowActions.ts")
+    );
+}
+
+#[test]
+fn vscode_path_mode_ranges() {
+    assert_eq!(path_ranges("win", "src/windowActions.ts"), [4..7]);
+
+    assert_eq!(path_ranges("act", "src/windowActions.ts"), [10..13]);
+
+    assert_eq!(path_ranges("srcwin", "src/windowActions.ts"), [0..3, 4..7]);
+
+    assert_eq!(path_ranges("foo", "foo.rs"), [0..3]);
+}
+
+#[test]
+fn vscode_path_mode_no_match() {
+    let mut vscode = VsCode::new();
+
+    vscode.set_path_mode(true);
+
+    assert!(vscode.distance("xyz", "src/windowActions.ts").is_none());
+
+    // Without a directory the basename is the only thing that's matched.
+    assert!(vscode.distance("srcwin", "srcwindow.ts").is_some());
+    assert!(vscode.distance("wsrc", "srcwindow.ts").is_none());
+}